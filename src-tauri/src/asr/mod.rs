@@ -7,6 +7,15 @@ pub mod volcengine;
 
 pub use volcengine::VolcEngineAsr;
 
+/// 带时间的识别分句，时间相对录音开始（毫秒），保存在历史记录的 utterances 字段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Utterance {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
 /// ASR 识别事件（所有引擎共用）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AsrEvent {
@@ -14,6 +23,8 @@ pub enum AsrEvent {
     PartialResult(String),
     /// 最终识别结果 (文本, 音频时长毫秒)
     FinalResult(String, Option<i64>),
+    /// 带时间的分句，在 FinalResult 之前发送，引擎不提供时间时不发送
+    Utterances(Vec<Utterance>),
     /// 错误
     Error(String),
     /// 连接已建立
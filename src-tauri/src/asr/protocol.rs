@@ -128,6 +128,15 @@ pub struct AsrRequestInfo {
     pub enable_itn: bool,
     pub enable_punc: bool,
     pub result_type: String,
+    /// 热词等识别上下文，没有时不发送
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corpus: Option<AsrCorpus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsrCorpus {
+    /// JSON 字符串，如 {"hotwords":[{"word":"Sayble"}]}
+    pub context: String,
 }
 
 /// ASR 识别结果（v3）
@@ -175,9 +184,24 @@ impl AsrRequest {
                 enable_itn: true,
                 enable_punc,
                 result_type: "full".to_string(),
+                corpus: None,
             },
         }
     }
+
+    /// 附带热词，提高专有名词的识别率；列表为空时不发送
+    pub fn with_hotwords(mut self, hotwords: &[String]) -> Self {
+        let words: Vec<_> = hotwords
+            .iter()
+            .map(|w| w.trim())
+            .filter(|w| !w.is_empty())
+            .map(|word| serde_json::json!({ "word": word }))
+            .collect();
+        self.request.corpus = (!words.is_empty()).then(|| AsrCorpus {
+            context: serde_json::json!({ "hotwords": words }).to_string(),
+        });
+        self
+    }
 }
 
 /// 构建完整客户端请求帧（full client request with JSON payload）
@@ -380,6 +404,21 @@ mod tests {
         assert!(req.request.enable_itn);
     }
 
+    #[test]
+    fn test_asr_request_with_hotwords() {
+        let req = AsrRequest::new(true).with_hotwords(&[]);
+        assert!(req.request.corpus.is_none());
+        assert!(!serde_json::to_string(&req).unwrap().contains("corpus"));
+
+        let req = AsrRequest::new(true).with_hotwords(&["Sayble".to_string(), " ".to_string()]);
+        let context: serde_json::Value =
+            serde_json::from_str(&req.request.corpus.unwrap().context).unwrap();
+        assert_eq!(
+            context,
+            serde_json::json!({ "hotwords": [{ "word": "Sayble" }] })
+        );
+    }
+
     #[test]
     fn test_build_full_client_request() {
         let req = AsrRequest::new(true);
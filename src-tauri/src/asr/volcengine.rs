@@ -1,6 +1,6 @@
-use super::AsrEvent;
+use super::{AsrEvent, Utterance};
 use crate::asr::protocol::{
-    build_audio_request, build_full_client_request, parse_server_response, AsrRequest,
+    build_audio_request, build_full_client_request, parse_server_response, AsrRequest, AsrResult,
 };
 use crate::config::{AsrConfig, ASR_RESOURCE_ID};
 use futures_util::{SinkExt, StreamExt};
@@ -102,6 +102,23 @@ impl VolcEngineAsr {
     }
 }
 
+/// 取出最终结果中带起止时间的分句，缺少时间或文本为空的分句跳过
+fn timed_utterances(result: &AsrResult) -> Vec<Utterance> {
+    result
+        .utterances
+        .iter()
+        .flatten()
+        .filter_map(|u| {
+            let text = u.text.as_deref().map(str::trim).filter(|t| !t.is_empty())?;
+            Some(Utterance {
+                text: text.to_string(),
+                start_ms: u.start_time?,
+                end_ms: u.end_time?,
+            })
+        })
+        .collect()
+}
+
 /// 构建 WebSocket 握手请求
 fn build_ws_request(
     config: &AsrConfig,
@@ -140,7 +157,7 @@ pub async fn test_connection(config: &AsrConfig) -> Result<String, String> {
     let (mut write, mut read) = ws_stream.split();
 
     // 发送 full client request 初始化帧
-    let asr_request = AsrRequest::new(config.auto_punctuation);
+    let asr_request = AsrRequest::new(config.auto_punctuation).with_hotwords(&config.hotwords);
     let full_request = build_full_client_request(&asr_request)?;
     write
         .send(Message::Binary(full_request.into()))
@@ -210,7 +227,7 @@ pub async fn run_asr_session(
     let (mut write, mut read) = ws_stream.split();
 
     // 发送 full client request
-    let asr_request = AsrRequest::new(config.auto_punctuation);
+    let asr_request = AsrRequest::new(config.auto_punctuation).with_hotwords(&config.hotwords);
     let full_request = build_full_client_request(&asr_request)?;
     tokio::time::timeout(
         std::time::Duration::from_secs(5),
@@ -246,6 +263,11 @@ pub async fn run_asr_session(
                                             .audio_info
                                             .as_ref()
                                             .and_then(|info| info.duration);
+                                        let utterances = timed_utterances(result);
+                                        if !utterances.is_empty() {
+                                            let _ = event_tx_recv
+                                                .send(AsrEvent::Utterances(utterances));
+                                        }
                                         let _ = event_tx_recv
                                             .send(AsrEvent::FinalResult(text.clone(), duration_ms));
                                     } else {
@@ -450,6 +472,26 @@ mod tests {
         assert!(json_err.contains("connection failed"));
     }
 
+    #[test]
+    fn test_timed_utterances_skip_untimed() {
+        let result: AsrResult = serde_json::from_value(serde_json::json!({
+            "text": "你好。世界。",
+            "utterances": [
+                {"text": "你好。", "start_time": 0, "end_time": 800, "definite": true},
+                {"text": "世界。", "definite": true}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            timed_utterances(&result),
+            [Utterance {
+                text: "你好。".to_string(),
+                start_ms: 0,
+                end_ms: 800,
+            }]
+        );
+    }
+
     #[test]
     fn test_stop_session() {
         let config = AsrConfig::default();
@@ -19,7 +19,7 @@ pub struct AudioCapture {
 }
 
 /// 目标采集参数：PCM 16kHz mono 16bit（火山引擎要求）
-const TARGET_SAMPLE_RATE: u32 = 16000;
+pub(super) const TARGET_SAMPLE_RATE: u32 = 16000;
 
 impl AudioCapture {
     pub fn new() -> Self {
@@ -204,7 +204,7 @@ impl AudioCapture {
 }
 
 /// 多声道混缩为单声道
-fn downmix_to_mono(samples: &[i16], channels: usize) -> Vec<i16> {
+pub(super) fn downmix_to_mono(samples: &[i16], channels: usize) -> Vec<i16> {
     if channels <= 1 {
         return samples.to_vec();
     }
@@ -218,7 +218,7 @@ fn downmix_to_mono(samples: &[i16], channels: usize) -> Vec<i16> {
 }
 
 /// 简单线性重采样
-fn resample(samples: &[i16], from_rate: u32, to_rate: u32) -> Vec<i16> {
+pub(super) fn resample(samples: &[i16], from_rate: u32, to_rate: u32) -> Vec<i16> {
     if from_rate == to_rate || samples.is_empty() {
         return samples.to_vec();
     }
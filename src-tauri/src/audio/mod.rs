@@ -1,3 +1,4 @@
 pub mod capture;
+pub mod wav;
 
 pub use capture::{AudioCapture, AudioDevice};
@@ -0,0 +1,164 @@
+//! WAV 文件解码，供文件转写使用
+//!
+//! 支持 8/16/24/32 位整数 PCM 和 32 位浮点，输出与麦克风采集一致的 16kHz 单声道 i16。
+
+use super::capture::{downmix_to_mono, resample, TARGET_SAMPLE_RATE};
+use std::path::Path;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// 读取 WAV 文件并转换为 16kHz 单声道
+pub fn read_wav(path: &Path) -> Result<Vec<i16>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("读取音频文件失败: {}", e))?;
+    decode_wav(&bytes)
+}
+
+/// 解码 WAV 数据并转换为 16kHz 单声道
+pub fn decode_wav(bytes: &[u8]) -> Result<Vec<i16>, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("不是 WAV 文件，目前只支持 WAV 格式".to_string());
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let start = offset + 8;
+        // 录音软件未写完的文件 data 长度可能超出实际大小
+        let end = start.saturating_add(size).min(bytes.len());
+        match id {
+            b"fmt " => format = Some(WavFormat::parse(&bytes[start..end])?),
+            b"data" => data = Some(&bytes[start..end]),
+            _ => {}
+        }
+        // 块按偶数字节对齐
+        offset = start.saturating_add(size).saturating_add(size & 1);
+    }
+
+    let format = format.ok_or("WAV 文件缺少 fmt 块")?;
+    let data = data.ok_or("WAV 文件缺少音频数据")?;
+    let samples = format.samples(data)?;
+    let mono = downmix_to_mono(&samples, format.channels as usize);
+    Ok(resample(&mono, format.sample_rate, TARGET_SAMPLE_RATE))
+}
+
+struct WavFormat {
+    tag: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits: u16,
+}
+
+impl WavFormat {
+    fn parse(chunk: &[u8]) -> Result<Self, String> {
+        if chunk.len() < 16 {
+            return Err("WAV 文件的 fmt 块不完整".to_string());
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([chunk[i], chunk[i + 1]]);
+        let mut tag = u16_at(0);
+        if tag == FORMAT_EXTENSIBLE && chunk.len() >= 26 {
+            // WAVE_FORMAT_EXTENSIBLE 的子格式 GUID 前两个字节即实际格式
+            tag = u16_at(24);
+        }
+        let format = Self {
+            tag,
+            channels: u16_at(2),
+            sample_rate: u32::from_le_bytes(chunk[4..8].try_into().unwrap()),
+            bits: u16_at(14),
+        };
+        if format.channels == 0 || format.sample_rate == 0 {
+            return Err("WAV 文件的声道数或采样率无效".to_string());
+        }
+        Ok(format)
+    }
+
+    fn samples(&self, data: &[u8]) -> Result<Vec<i16>, String> {
+        let samples = match (self.tag, self.bits) {
+            (FORMAT_PCM, 8) => data.iter().map(|&b| (b as i16 - 128) << 8).collect(),
+            (FORMAT_PCM, 16) => data
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]))
+                .collect(),
+            (FORMAT_PCM, 24) => data
+                .chunks_exact(3)
+                .map(|b| i16::from_le_bytes([b[1], b[2]]))
+                .collect(),
+            (FORMAT_PCM, 32) => data
+                .chunks_exact(4)
+                .map(|b| i16::from_le_bytes([b[2], b[3]]))
+                .collect(),
+            (FORMAT_FLOAT, 32) => data
+                .chunks_exact(4)
+                .map(|b| {
+                    let sample = f32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+                    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+                })
+                .collect(),
+            _ => {
+                return Err(format!(
+                    "不支持的 WAV 编码（格式 {}，{} 位），请转换为 PCM 后重试",
+                    self.tag, self.bits
+                ))
+            }
+        };
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(tag: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        let block_align = channels * bits / 8;
+        bytes.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(b"LIST");
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn test_decode_pcm16_stereo() {
+        let data: Vec<u8> = [100i16, 300, -200, -400]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let samples = decode_wav(&wav(FORMAT_PCM, 2, 16000, 16, &data)).unwrap();
+        assert_eq!(samples, vec![200, -300]);
+    }
+
+    #[test]
+    fn test_decode_float_and_resample() {
+        let data: Vec<u8> = [0.5f32; 32000]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let samples = decode_wav(&wav(FORMAT_FLOAT, 1, 32000, 32, &data)).unwrap();
+        assert_eq!(samples.len(), 16000);
+        assert_eq!(samples[0], i16::MAX / 2);
+    }
+
+    #[test]
+    fn test_rejects_unsupported() {
+        assert!(decode_wav(b"ID3\x03\x00\x00\x00\x00\x00\x00\x00\x00").is_err());
+        assert!(decode_wav(&wav(2, 1, 16000, 4, &[0; 8])).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! 备份导出与导入
+//!
+//! 备份是单个 JSON 文件，包含 settings.json（含润色提示词）、tunnels.json、共享设置，
+//! 可选包含历史记录。密钥不以明文导出：未设置密码时不导出，设置密码时用密码加密。
+//! 导入时先迁移并校验备份中的设置，全部通过后才写入，支持合并和替换两种方式。
+
+use crate::secrets::SealedSecrets;
+use crate::settings;
+use crate::tunnel::TunnelConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 备份文件的格式标识
+pub const BACKUP_FORMAT: &str = "sayble-backup";
+
+/// 备份文件格式的当前版本
+pub const BACKUP_VERSION: u64 = 1;
+
+/// 按机器区分、不随备份迁移的通用设置字段
+const LOCAL_APP_FIELDS: &[&str] = &["deviceId"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// 与现有数据合并，同名项以备份为准
+    Merge,
+    /// 用备份覆盖现有数据
+    Replace,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    pub format: String,
+    pub version: u64,
+    #[serde(default)]
+    pub created_at: String,
+    /// settings.json 的内容，不含密钥字段
+    #[serde(default)]
+    pub settings: Map<String, Value>,
+    /// 用密码加密的密钥，导出时未设置密码则没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<SealedSecrets>,
+    #[serde(default)]
+    pub tunnels: Vec<TunnelConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_settings: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<Vec<Value>>,
+}
+
+/// 导入结果，返回给前端提示
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub secrets: usize,
+    pub tunnels: usize,
+    pub history: usize,
+    /// 备份中有加密的密钥但未提供密码，已跳过
+    pub secrets_skipped: bool,
+}
+
+impl Backup {
+    /// 生成备份；settings 为已填入密钥的完整设置，密钥在此取出并加密或丢弃
+    pub fn new(
+        mut settings: Map<String, Value>,
+        password: Option<&str>,
+        tunnels: Vec<TunnelConfig>,
+        share_settings: Value,
+        history: Option<Vec<Value>>,
+    ) -> Result<Self, String> {
+        let secrets: Vec<_> = settings::take_secrets(&mut settings)
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+        let secrets = match password.filter(|p| !p.is_empty()) {
+            Some(password) if !secrets.is_empty() => Some(SealedSecrets::seal(password, &secrets)?),
+            _ => None,
+        };
+        if let Some(app) = settings
+            .get_mut(settings::APP_SETTINGS_KEY)
+            .and_then(|v| v.as_object_mut())
+        {
+            for field in LOCAL_APP_FIELDS {
+                app.remove(*field);
+            }
+        }
+        Ok(Self {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at: chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+            settings,
+            secrets,
+            tunnels,
+            share_settings: Some(share_settings),
+            history,
+        })
+    }
+
+    /// 解析备份文件，检查格式与版本，并把其中的设置迁移到当前版本后严格校验
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(content).map_err(|e| format!("备份文件格式错误: {}", e))?;
+        if value.get("format").and_then(|v| v.as_str()) != Some(BACKUP_FORMAT) {
+            return Err("不是有效的备份文件".to_string());
+        }
+        let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version == 0 || version > BACKUP_VERSION {
+            return Err(format!(
+                "备份文件版本（v{}）不受支持，请升级应用后再导入",
+                version
+            ));
+        }
+        let mut backup: Backup =
+            serde_json::from_value(value).map_err(|e| format!("备份文件格式错误: {}", e))?;
+
+        if settings::version_of(&backup.settings) > settings::SETTINGS_VERSION {
+            return Err("备份中的设置来自更新的版本，请升级应用后再导入".to_string());
+        }
+        settings::migrate(&mut backup.settings)?;
+        settings::validate(&backup.settings).map_err(|errors| errors.join("\n"))?;
+        Ok(backup)
+    }
+
+    /// 解密备份中的密钥；没有密钥时返回空列表，缺少密码时返回 None
+    pub fn open_secrets(
+        &self,
+        password: Option<&str>,
+    ) -> Result<Option<Vec<(String, String)>>, String> {
+        let Some(sealed) = self.secrets.as_ref().filter(|s| !s.is_empty()) else {
+            return Ok(Some(Vec::new()));
+        };
+        let Some(password) = password.filter(|p| !p.is_empty()) else {
+            return Ok(None);
+        };
+        sealed
+            .open(password)
+            .map(Some)
+            .map_err(|_| "密码错误，无法解密备份中的密钥".to_string())
+    }
+}
+
+/// 把备份中的设置合并到当前设置
+///
+/// 合并时对象逐字段合并，元素都带 id 的列表（快捷键方案、润色服务商、提示词）按 id 覆盖或追加，
+/// 其余值以备份为准；替换时整体使用备份的设置。两种方式都保留本机的设备 id。
+pub fn merge_settings(
+    current: &mut Map<String, Value>,
+    incoming: Map<String, Value>,
+    mode: ImportMode,
+) {
+    let local: Vec<(String, Value)> = current
+        .get(settings::APP_SETTINGS_KEY)
+        .and_then(|v| v.as_object())
+        .map(|app| {
+            LOCAL_APP_FIELDS
+                .iter()
+                .filter_map(|field| Some((field.to_string(), app.get(*field)?.clone())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match mode {
+        ImportMode::Replace => *current = incoming,
+        ImportMode::Merge => {
+            for (key, value) in incoming {
+                match current.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        current.insert(key, value);
+                    }
+                }
+            }
+        }
+    }
+
+    if !local.is_empty() {
+        let app = current
+            .entry(settings::APP_SETTINGS_KEY)
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Some(app) = app.as_object_mut() {
+            app.extend(local);
+        }
+    }
+    current.insert(
+        settings::VERSION_KEY.to_string(),
+        Value::from(settings::SETTINGS_VERSION),
+    );
+}
+
+fn merge_value(current: &mut Value, incoming: Value) {
+    match (current, incoming) {
+        (Value::Object(current), Value::Object(incoming)) => {
+            for (key, value) in incoming {
+                match current.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        current.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(current), Value::Array(incoming))
+            if current
+                .iter()
+                .chain(&incoming)
+                .all(|item| item_id(item).is_some()) =>
+        {
+            for item in incoming {
+                let id = item_id(&item).map(str::to_string);
+                match current.iter_mut().find(|c| item_id(c) == id.as_deref()) {
+                    Some(existing) => *existing = item,
+                    None => current.push(item),
+                }
+            }
+        }
+        (current, incoming) => *current = incoming,
+    }
+}
+
+fn item_id(item: &Value) -> Option<&str> {
+    item.get("id")
+        .and_then(|v| v.as_str())
+        .filter(|id| !id.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn map(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    fn sample_settings() -> Map<String, Value> {
+        map(json!({
+            "settings_version": 2,
+            "app_settings": { "toggleHotkey": "右Ctrl", "deviceId": "local-device" },
+            "polish_settings": {
+                "enabled": true,
+                "providers": [{ "id": "deepseek", "name": "DeepSeek", "apiKey": "sk-1" }],
+                "prompts": [{ "id": "default", "name": "默认", "content": "润色" }]
+            }
+        }))
+    }
+
+    #[test]
+    fn test_export_strips_or_seals_secrets() {
+        let backup = Backup::new(sample_settings(), None, Vec::new(), json!({}), None).unwrap();
+        let content = serde_json::to_string(&backup).unwrap();
+        assert!(!content.contains("sk-1"));
+        assert!(!content.contains("local-device"));
+        assert!(backup.secrets.is_none());
+
+        let backup =
+            Backup::new(sample_settings(), Some("pw"), Vec::new(), json!({}), None).unwrap();
+        let content = serde_json::to_string(&backup).unwrap();
+        assert!(!content.contains("sk-1"));
+
+        let parsed = Backup::parse(&content).unwrap();
+        assert_eq!(parsed.open_secrets(None), Ok(None));
+        assert!(parsed.open_secrets(Some("wrong")).is_err());
+        assert_eq!(
+            parsed.open_secrets(Some("pw")),
+            Ok(Some(vec![(
+                "polish.deepseek.apiKey".to_string(),
+                "sk-1".to_string()
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_format_and_version() {
+        assert!(Backup::parse("{}").is_err());
+        let newer = json!({ "format": BACKUP_FORMAT, "version": BACKUP_VERSION + 1 });
+        assert!(Backup::parse(&newer.to_string())
+            .unwrap_err()
+            .contains("不受支持"));
+        let newer_settings = json!({
+            "format": BACKUP_FORMAT,
+            "version": BACKUP_VERSION,
+            "settings": { "settings_version": settings::SETTINGS_VERSION + 1 }
+        });
+        assert!(Backup::parse(&newer_settings.to_string()).is_err());
+        let invalid = json!({
+            "format": BACKUP_FORMAT,
+            "version": BACKUP_VERSION,
+            "settings": { "polish_settings": { "providers": [{ "id": "a", "temperature": 5 }] } }
+        });
+        assert!(Backup::parse(&invalid.to_string())
+            .unwrap_err()
+            .contains("temperature"));
+    }
+
+    #[test]
+    fn test_merge_settings_by_id() {
+        let mut current = sample_settings();
+        let incoming = map(json!({
+            "app_settings": { "autoOutput": false },
+            "polish_settings": {
+                "prompts": [
+                    { "id": "default", "name": "默认", "content": "新内容" },
+                    { "id": "translate", "name": "翻译", "content": "翻译成英文" }
+                ]
+            }
+        }));
+        merge_settings(&mut current, incoming, ImportMode::Merge);
+
+        let app = &current["app_settings"];
+        assert_eq!(app["toggleHotkey"], "右Ctrl");
+        assert_eq!(app["autoOutput"], false);
+        let polish = &current["polish_settings"];
+        assert_eq!(polish["providers"][0]["id"], "deepseek");
+        let prompts = polish["prompts"].as_array().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert_eq!(prompts[0]["content"], "新内容");
+    }
+
+    #[test]
+    fn test_replace_settings_keeps_device_id() {
+        let mut current = sample_settings();
+        let incoming = map(json!({
+            "app_settings": { "toggleHotkey": "F9", "deviceId": "other-device" }
+        }));
+        merge_settings(&mut current, incoming, ImportMode::Replace);
+
+        assert!(!current.contains_key("polish_settings"));
+        assert_eq!(current["app_settings"]["toggleHotkey"], "F9");
+        assert_eq!(current["app_settings"]["deviceId"], "local-device");
+        assert_eq!(
+            current[settings::VERSION_KEY],
+            json!(settings::SETTINGS_VERSION)
+        );
+    }
+}
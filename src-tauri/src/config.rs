@@ -139,10 +139,107 @@ fn key_label_to_vk(label: &str) -> Option<u32> {
     }
 }
 
+/// 快捷键触发的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HotkeyAction {
+    /// 开始/停止听写
+    ToggleRecording,
+    /// 复制选中文本，录一段语音指令，润色模型改写后粘贴回原处
+    TransformSelection,
+    /// 听写并翻译成目标语言后输出
+    Translate,
+    /// 确认输出待确认的识别结果（关闭自动输出时）
+    AcceptOutput,
+    /// 撤销上一次听写输出
+    UndoOutput,
+}
+
+impl Default for HotkeyAction {
+    fn default() -> Self {
+        Self::ToggleRecording
+    }
+}
+
 /// 快捷键配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HotkeyConfig {
     pub binding: HotkeyBinding,
+    #[serde(default)]
+    pub action: HotkeyAction,
+    /// 翻译目标（仅 Translate 动作使用）
+    #[serde(default)]
+    pub translation: Option<TranslationTarget>,
+    /// 所属快捷键方案 id，None 表示使用全局设置
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    #[serde(default)]
+    pub trigger: TriggerMode,
+}
+
+/// 快捷键触发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerMode {
+    /// 按一下开始，再按一下停止
+    #[default]
+    Toggle,
+    /// 按住说话，松开停止；点按时退化为切换
+    Hold,
+}
+
+/// 翻译快捷键的语言设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationTarget {
+    /// 源语言，为空时由模型自动判断
+    #[serde(default)]
+    pub source_language: Option<String>,
+    /// 目标语言，如 "en"、"ja"、"English"
+    pub target_language: String,
+}
+
+/// 快捷键方案的润色设置
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", content = "promptId", rename_all = "camelCase")]
+pub enum ProfilePolish {
+    /// 跟随全局润色设置
+    #[default]
+    Global,
+    /// 不润色
+    Off,
+    /// 使用指定提示词润色
+    Prompt(String),
+}
+
+/// 命名快捷键方案：每个快捷键可以有独立的识别、润色和输出设置
+/// 可选字段为空时沿用全局设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyProfile {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    /// 快捷键标签，如 "左Alt + D"
+    pub hotkey: String,
+    #[serde(default)]
+    pub action: HotkeyAction,
+    #[serde(default)]
+    pub trigger: TriggerMode,
+    /// ASR 服务商 id，如 "volcengine"、"sapi"
+    #[serde(default)]
+    pub asr_provider: Option<String>,
+    /// 识别语言: "zh", "en", "auto"
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub polish: ProfilePolish,
+    /// 翻译目标（仅 Translate 动作使用）
+    #[serde(default)]
+    pub translation: Option<TranslationTarget>,
+    #[serde(default)]
+    pub output_mode: Option<OutputMode>,
+    #[serde(default)]
+    pub auto_output: Option<bool>,
 }
 
 /// 固定的 ASR Resource ID
@@ -158,6 +255,9 @@ pub struct AsrConfig {
     pub language: String,
     /// 是否自动添加标点
     pub auto_punctuation: bool,
+    /// 热词，提高专有名词的识别率
+    #[serde(default)]
+    pub hotwords: Vec<String>,
 }
 
 impl Default for AsrConfig {
@@ -167,6 +267,7 @@ impl Default for AsrConfig {
             access_key: String::new(),
             language: "zh".to_string(),
             auto_punctuation: true,
+            hotwords: Vec::new(),
         }
     }
 }
@@ -178,6 +279,10 @@ pub enum OutputMode {
     Clipboard,
     /// 模拟键盘输入
     SimulateKeyboard,
+    /// 只写入剪贴板，由用户自行粘贴
+    CopyOnly,
+    /// 分段慢速模拟键盘输入，适用于会丢字的应用
+    PacedKeyboard,
 }
 
 impl Default for OutputMode {
@@ -186,10 +291,74 @@ impl Default for OutputMode {
     }
 }
 
+/// 剪贴板输出使用的粘贴快捷键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteShortcut {
+    #[default]
+    CtrlV,
+    /// 终端常用的 Ctrl+Shift+V
+    CtrlShiftV,
+    ShiftInsert,
+}
+
+/// 输出文字后追加的按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrailingAction {
+    #[default]
+    None,
+    Enter,
+    Tab,
+}
+
+/// 按前台应用区分的输出规则（app_settings.outputRules）
+/// 可选字段为空时沿用快捷键方案或全局设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputRule {
+    /// 进程名（可省略 .exe）或窗口类名，不区分大小写，如 "WindowsTerminal"、"kitty"
+    pub app: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub output_mode: Option<OutputMode>,
+    #[serde(default)]
+    pub paste_shortcut: Option<PasteShortcut>,
+    #[serde(default)]
+    pub trailing_action: Option<TrailingAction>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl OutputRule {
+    /// 规则是否匹配前台应用的进程名或窗口类名
+    pub fn matches(&self, process_name: &str, window_class: &str) -> bool {
+        let pattern = self.app.trim();
+        if !self.enabled || pattern.is_empty() {
+            return false;
+        }
+        let strip_exe = |name: &str| {
+            let lower = name.to_lowercase();
+            lower
+                .strip_suffix(".exe")
+                .map(str::to_string)
+                .unwrap_or(lower)
+        };
+        let pattern = strip_exe(pattern);
+        strip_exe(process_name) == pattern || window_class.to_lowercase() == pattern
+    }
+}
+
 /// 完整应用配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub toggle_hotkey: HotkeyConfig,
+    /// 命名快捷键方案
+    #[serde(default)]
+    pub hotkey_profiles: Vec<HotkeyProfile>,
     pub asr: AsrConfig,
     pub output_mode: OutputMode,
     /// 麦克风设备名（空字符串表示默认设备）
@@ -208,7 +377,12 @@ impl Default for AppConfig {
                     modifiers: vec![Modifier::RightCtrl],
                     key: 0,
                 },
+                action: HotkeyAction::ToggleRecording,
+                translation: None,
+                profile_id: None,
+                trigger: TriggerMode::Toggle,
             },
+            hotkey_profiles: Vec::new(),
             asr: AsrConfig::default(),
             output_mode: OutputMode::default(),
             microphone_device: String::new(),
@@ -251,6 +425,79 @@ mod tests {
         assert_eq!(state, AppState::Idle);
     }
 
+    #[test]
+    fn test_hotkey_config_action_defaults_to_toggle() {
+        let json = r#"{"binding": {"modifiers": ["RightCtrl"], "key": 0}}"#;
+        let config: HotkeyConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.action, HotkeyAction::ToggleRecording);
+    }
+
+    #[test]
+    fn test_hotkey_profile_deserialize() {
+        let profile: HotkeyProfile = serde_json::from_value(serde_json::json!({
+            "id": "meeting",
+            "name": "会议纪要",
+            "hotkey": "左Alt + M",
+            "asrProvider": "volcengine",
+            "language": "en",
+            "polish": {"type": "prompt", "promptId": "p1"},
+            "outputMode": "SimulateKeyboard",
+            "autoOutput": false,
+        }))
+        .unwrap();
+        assert_eq!(profile.action, HotkeyAction::ToggleRecording);
+        assert_eq!(profile.trigger, TriggerMode::Toggle);
+        assert_eq!(profile.language.as_deref(), Some("en"));
+        assert_eq!(profile.polish, ProfilePolish::Prompt("p1".to_string()));
+        assert_eq!(profile.output_mode, Some(OutputMode::SimulateKeyboard));
+        assert_eq!(profile.auto_output, Some(false));
+
+        // 最简方案：其余字段沿用全局设置
+        let profile: HotkeyProfile = serde_json::from_value(serde_json::json!({
+            "id": "plain",
+            "hotkey": "右Alt",
+            "trigger": "hold",
+            "polish": {"type": "off"},
+        }))
+        .unwrap();
+        assert_eq!(profile.polish, ProfilePolish::Off);
+        assert_eq!(profile.trigger, TriggerMode::Hold);
+        assert!(profile.asr_provider.is_none());
+        assert!(profile.output_mode.is_none());
+    }
+
+    #[test]
+    fn test_output_rule_matches() {
+        let rule: OutputRule = serde_json::from_value(serde_json::json!({
+            "app": "WindowsTerminal.exe",
+            "outputMode": "Clipboard",
+            "pasteShortcut": "ctrlShiftV",
+            "trailingAction": "enter",
+        }))
+        .unwrap();
+        assert!(rule.enabled);
+        assert_eq!(rule.paste_shortcut, Some(PasteShortcut::CtrlShiftV));
+        assert_eq!(rule.trailing_action, Some(TrailingAction::Enter));
+        assert!(rule.matches("windowsterminal.exe", ""));
+        assert!(rule.matches("WindowsTerminal", ""));
+        assert!(!rule.matches("cmd.exe", "ConsoleWindowClass"));
+
+        // 按窗口类名匹配（X11 WM_CLASS）
+        let rule: OutputRule = serde_json::from_value(serde_json::json!({
+            "app": "kitty",
+            "outputMode": "PacedKeyboard",
+        }))
+        .unwrap();
+        assert_eq!(rule.output_mode, Some(OutputMode::PacedKeyboard));
+        assert!(rule.matches("python3", "Kitty"));
+
+        let disabled = OutputRule {
+            enabled: false,
+            ..rule
+        };
+        assert!(!disabled.matches("kitty", "kitty"));
+    }
+
     #[test]
     fn test_hotkey_binding_default() {
         let binding = HotkeyBinding::default();
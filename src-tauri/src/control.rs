@@ -0,0 +1,633 @@
+//! 本地控制接口：供脚本和其他工具通过 HTTP 控制 Sayble
+//!
+//! 只监听 127.0.0.1，每个请求需带 `Authorization: Bearer <token>`。token 在首次启用时生成，
+//! 保存在数据目录的 control_token 文件中，只有当前用户可读。
+//!
+//! | 请求 | 说明 |
+//! |---|---|
+//! | `GET /state` | 录音状态、当前会话 id 和方案 |
+//! | `POST /recording/start` | 开始录音，可带 `{"profile": "方案 id"}` |
+//! | `POST /recording/stop` | 停止录音，识别结果按设置输出 |
+//! | `POST /recording/cancel` | 取消录音，不输出 |
+//! | `GET /result` | 最近一条识别记录，没有时为 null |
+//! | `POST /profile` | `{"id": "方案 id"}`，之后通过接口开始的录音使用该方案，null 恢复默认 |
+//! | `POST /transcribe` | `{"path": "WAV 文件路径"}`，返回识别文本和分句 |
+//! | `GET /events` | Server-Sent Events，推送与界面相同的 asr-event |
+//!
+//! 成功时返回 JSON，失败时返回 `{"error": "..."}` 和相应的状态码。
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 数据目录中保存 token 的文件
+pub const TOKEN_FILE: &str = "control_token";
+
+pub const DEFAULT_CONTROL_PORT: u16 = 47621;
+
+/// 请求体上限，控制请求只有少量 JSON
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// 请求行加请求头的总字节上限
+const MAX_HEAD_BYTES: u64 = 8 * 1024;
+
+/// 请求头数量上限
+const MAX_HEADERS: usize = 32;
+
+/// 同时处理的请求连接数上限，不含 SSE 长连接
+const MAX_CONNECTIONS: usize = 16;
+
+/// 同时保持的 SSE 连接数上限，单独计数，订阅者再多也不影响普通请求
+const MAX_EVENT_STREAMS: usize = 8;
+
+/// SSE 连接空闲时发送注释行的间隔，及时发现已断开的客户端
+const SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// 本地控制接口设置（app_settings.control）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ControlSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_CONTROL_PORT,
+        }
+    }
+}
+
+/// 解析后的控制请求，由 ControlHandler 执行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlRequest {
+    State,
+    Start {
+        profile: Option<String>,
+    },
+    Stop,
+    Cancel,
+    LastResult,
+    SetProfile(Option<String>),
+    Transcribe {
+        path: PathBuf,
+        profile: Option<String>,
+    },
+}
+
+/// 执行控制请求，返回作为响应体的 JSON
+pub type ControlHandler = dyn Fn(ControlRequest) -> Result<Value, String> + Send + Sync;
+
+/// 控制接口的运行状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub token_file: PathBuf,
+    /// 最近一次启动失败的原因
+    pub error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct HttpError {
+    status: u16,
+    message: String,
+}
+
+impl HttpError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+type Subscribers = Arc<Mutex<Vec<mpsc::Sender<String>>>>;
+
+/// 请求头，名称已转为小写
+type Headers = Vec<(String, String)>;
+
+struct RunningServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// 连接处理线程共享的数据
+struct Context {
+    token: String,
+    handler: Arc<ControlHandler>,
+    subscribers: Subscribers,
+    stop: Arc<AtomicBool>,
+    connections: AtomicUsize,
+    event_streams: AtomicUsize,
+}
+
+/// 占用的一个连接名额，连接线程结束（包括 panic）时释放
+struct ConnectionSlot {
+    context: Arc<Context>,
+    counter: fn(&Context) -> &AtomicUsize,
+}
+
+impl ConnectionSlot {
+    /// 占用 counter 计数的名额，已达上限 limit 时返回 None
+    fn acquire(
+        context: &Arc<Context>,
+        counter: fn(&Context) -> &AtomicUsize,
+        limit: usize,
+    ) -> Option<Self> {
+        if counter(context).fetch_add(1, Ordering::SeqCst) >= limit {
+            counter(context).fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(Self {
+            context: Arc::clone(context),
+            counter,
+        })
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        (self.counter)(&self.context).fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 控制接口，设置变化时由 apply 启动、重启或停止
+pub struct ControlApi {
+    handler: Arc<ControlHandler>,
+    server: Mutex<Option<RunningServer>>,
+    subscribers: Subscribers,
+    last_error: Mutex<Option<String>>,
+}
+
+impl ControlApi {
+    pub fn new(handler: Arc<ControlHandler>) -> Self {
+        Self {
+            handler,
+            server: Mutex::new(None),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            last_error: Mutex::new(None),
+        }
+    }
+
+    /// 按设置启动或停止服务，端口变化时重新监听
+    pub fn apply(&self, settings: &ControlSettings) -> Result<(), String> {
+        let mut server = self.server.lock().unwrap_or_else(|e| e.into_inner());
+        let wanted = settings.enabled.then_some(settings.port);
+        if server.as_ref().map(|s| s.port) == wanted {
+            return Ok(());
+        }
+        if let Some(running) = server.take() {
+            running.stop.store(true, Ordering::SeqCst);
+            // 等监听线程退出并释放端口，之后才能在同一端口重新监听
+            let _ = running.thread.join();
+            log::info!("[control] server on port {} stopped", running.port);
+        }
+        let result = match wanted {
+            Some(port) => self.start(port).map(|running| *server = Some(running)),
+            None => Ok(()),
+        };
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = result.clone().err();
+        result
+    }
+
+    fn start(&self, port: u16) -> Result<RunningServer, String> {
+        if port == 0 {
+            return Err("端口必须在 1-65535 之间".to_string());
+        }
+        let token = load_or_create_token(&store::base_dir())?;
+        let listener =
+            TcpListener::bind((Ipv4Addr::LOCALHOST, port)).map_err(|e| match e.kind() {
+                io::ErrorKind::AddrInUse => format!("端口 {} 已被占用，请换一个端口", port),
+                _ => format!("控制接口无法监听端口 {}: {}", port, e),
+            })?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("设置控制接口监听模式失败: {}", e))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let context = Arc::new(Context {
+            token,
+            handler: Arc::clone(&self.handler),
+            subscribers: Arc::clone(&self.subscribers),
+            stop: Arc::clone(&stop),
+            connections: AtomicUsize::new(0),
+            event_streams: AtomicUsize::new(0),
+        });
+        let thread = thread::spawn(move || accept_loop(listener, context));
+        log::info!("[control] server started at http://127.0.0.1:{}", port);
+        Ok(RunningServer { port, stop, thread })
+    }
+
+    /// 推送事件给所有 /events 连接，payload 为 JSON 字符串
+    pub fn broadcast(&self, event: &str, payload: &str) {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if subscribers.is_empty() {
+            return;
+        }
+        let message = sse_message(event, payload);
+        subscribers.retain(|tx| tx.send(message.clone()).is_ok());
+    }
+
+    pub fn status(&self) -> ControlStatus {
+        let port = self
+            .server
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|s| s.port);
+        ControlStatus {
+            running: port.is_some(),
+            port,
+            token_file: store::base_dir().join(TOKEN_FILE),
+            error: self
+                .last_error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        }
+    }
+}
+
+/// 读取 token，不存在时生成并保存为只有当前用户可读的文件
+pub fn load_or_create_token(dir: &Path) -> Result<String, String> {
+    let path = dir.join(TOKEN_FILE);
+    if let Ok(content) = fs::read_to_string(&path) {
+        let token = content.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string());
+        }
+    }
+
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let tmp_path = path.with_extension("tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(token.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp_path, &path))
+        .map_err(|e| format!("保存控制接口 token 失败: {}", e))?;
+    log::info!("[control] token created at {}", path.display());
+    Ok(token)
+}
+
+fn accept_loop(listener: TcpListener, context: Arc<Context>) {
+    while !context.stop.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((mut stream, _addr)) => {
+                let Some(slot) =
+                    ConnectionSlot::acquire(&context, |c| &c.connections, MAX_CONNECTIONS)
+                else {
+                    log::warn!("[control] too many connections, rejecting");
+                    let error = HttpError::new(503, "连接数过多，请稍后重试");
+                    let _ = write_json(&mut stream, error.status, &error_body(&error));
+                    continue;
+                };
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(slot, stream) {
+                        log::debug!("[control] request failed: {}", e);
+                    }
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => {
+                log::warn!("[control] incoming connection failed: {}", e);
+                thread::sleep(Duration::from_millis(200));
+            }
+        }
+    }
+    log::debug!("[control] server thread exited");
+}
+
+fn handle_connection(slot: ConnectionSlot, mut stream: TcpStream) -> io::Result<()> {
+    let context = Arc::clone(&slot.context);
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(&mut stream);
+    let Some((request_line, headers)) = read_head(&mut reader)? else {
+        let error = HttpError::new(431, "请求头过大");
+        return write_json(reader.get_mut(), error.status, &error_body(&error));
+    };
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_ascii_uppercase();
+    let path = parts.next().unwrap_or("/").split('?').next().unwrap_or("/");
+
+    let authorized = header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| token_matches(given.trim(), &context.token));
+    if !authorized {
+        let error = HttpError::new(401, "缺少或错误的 token");
+        return write_json(reader.get_mut(), error.status, &error_body(&error));
+    }
+
+    let content_length = header("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    if content_length > MAX_BODY_BYTES {
+        let error = HttpError::new(413, "请求体过大");
+        return write_json(reader.get_mut(), error.status, &error_body(&error));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    if method == "GET" && path == "/events" {
+        // SSE 长连接改占订阅名额，释放请求名额
+        let Some(_stream_slot) =
+            ConnectionSlot::acquire(&context, |c| &c.event_streams, MAX_EVENT_STREAMS)
+        else {
+            log::warn!("[control] too many event streams, rejecting");
+            let error = HttpError::new(503, "事件订阅连接数过多，请稍后重试");
+            return write_json(reader.get_mut(), error.status, &error_body(&error));
+        };
+        drop(slot);
+        return stream_events(&context, reader.get_mut());
+    }
+
+    let result = parse_request(&method, path, &body).and_then(|request| {
+        log::info!("[control] {:?}", request);
+        (context.handler)(request).map_err(|e| HttpError::new(400, e))
+    });
+    match result {
+        Ok(value) => write_json(reader.get_mut(), 200, &value),
+        Err(error) => write_json(reader.get_mut(), error.status, &error_body(&error)),
+    }
+}
+
+/// 读取请求行和请求头，超出大小或数量上限时返回 None
+fn read_head(reader: impl BufRead) -> io::Result<Option<(String, Headers)>> {
+    let mut head = reader.take(MAX_HEAD_BYTES);
+    let mut request_line = String::new();
+    head.read_line(&mut request_line)?;
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        let bytes = head.read_line(&mut line)?;
+        if head.limit() == 0 && !line.ends_with('\n') {
+            return Ok(None);
+        }
+        if bytes == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Ok(None);
+        }
+        if let Some((key, value)) = line.trim_end().split_once(':') {
+            headers.push((key.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    Ok(Some((request_line, headers)))
+}
+
+/// 按方法和路径解析请求
+fn parse_request(method: &str, path: &str, body: &[u8]) -> Result<ControlRequest, HttpError> {
+    let body: Value = if body.iter().all(u8::is_ascii_whitespace) {
+        Value::Null
+    } else {
+        serde_json::from_slice(body)
+            .map_err(|e| HttpError::new(400, format!("请求体不是有效的 JSON: {}", e)))?
+    };
+    let field = |key: &str| {
+        body.get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    let request = match (method, path) {
+        ("GET", "/state") => ControlRequest::State,
+        ("POST", "/recording/start") => ControlRequest::Start {
+            profile: field("profile"),
+        },
+        ("POST", "/recording/stop") => ControlRequest::Stop,
+        ("POST", "/recording/cancel") => ControlRequest::Cancel,
+        ("GET", "/result") => ControlRequest::LastResult,
+        ("POST", "/profile") => ControlRequest::SetProfile(field("id")),
+        ("POST", "/transcribe") => ControlRequest::Transcribe {
+            path: PathBuf::from(field("path").ok_or_else(|| HttpError::new(400, "缺少 path"))?),
+            profile: field("profile"),
+        },
+        (
+            _,
+            "/state" | "/recording/start" | "/recording/stop" | "/recording/cancel" | "/result"
+            | "/profile" | "/transcribe" | "/events",
+        ) => return Err(HttpError::new(405, "不支持的请求方法")),
+        _ => return Err(HttpError::new(404, "未知的接口")),
+    };
+    Ok(request)
+}
+
+/// 保持连接并逐条写出事件，客户端断开或服务停止时返回
+fn stream_events(context: &Context, stream: &mut TcpStream) -> io::Result<()> {
+    // 先订阅再响应，客户端收到 connected 后发生的事件都不会丢
+    let (tx, rx) = mpsc::channel();
+    context
+        .subscribers
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(tx);
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream; charset=utf-8\r\n\
+         Cache-Control: no-store\r\nConnection: keep-alive\r\n\r\n: connected\n\n"
+    )?;
+    stream.flush()?;
+
+    let mut last_write = Instant::now();
+    while !context.stop.load(Ordering::SeqCst) {
+        match rx.recv_timeout(Duration::from_secs(1)) {
+            Ok(message) => stream.write_all(message.as_bytes())?,
+            Err(mpsc::RecvTimeoutError::Timeout) if last_write.elapsed() >= SSE_KEEPALIVE => {
+                stream.write_all(b": ping\n\n")?
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        stream.flush()?;
+        last_write = Instant::now();
+    }
+    Ok(())
+}
+
+fn sse_message(event: &str, payload: &str) -> String {
+    let mut message = format!("event: {}\n", event);
+    for line in payload.lines() {
+        message.push_str("data: ");
+        message.push_str(line);
+        message.push('\n');
+    }
+    message.push('\n');
+    message
+}
+
+/// 比较 token，耗时与内容无关
+fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn error_body(error: &HttpError) -> Value {
+    serde_json::json!({ "error": error.message })
+}
+
+fn write_json(stream: &mut TcpStream, status: u16, value: &Value) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Bad Request",
+    };
+    let body = serde_json::to_string(value).unwrap_or_else(|_| "null".to_string());
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\n\
+         Content-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            parse_request("POST", "/recording/start", br#"{"profile": "meeting"}"#),
+            Ok(ControlRequest::Start {
+                profile: Some("meeting".to_string())
+            })
+        );
+        assert_eq!(
+            parse_request("POST", "/recording/start", b""),
+            Ok(ControlRequest::Start { profile: None })
+        );
+        assert_eq!(
+            parse_request("POST", "/profile", br#"{"id": null}"#),
+            Ok(ControlRequest::SetProfile(None))
+        );
+        assert_eq!(
+            parse_request("POST", "/transcribe", b"{}")
+                .unwrap_err()
+                .status,
+            400
+        );
+        assert_eq!(
+            parse_request("GET", "/recording/stop", b"")
+                .unwrap_err()
+                .status,
+            405
+        );
+        assert_eq!(
+            parse_request("GET", "/missing", b"").unwrap_err().status,
+            404
+        );
+        assert_eq!(
+            parse_request("POST", "/profile", b"{").unwrap_err().status,
+            400
+        );
+    }
+
+    #[test]
+    fn test_token_file_is_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let token = load_or_create_token(dir.path()).unwrap();
+        assert_eq!(token.len(), 64);
+        assert_eq!(load_or_create_token(dir.path()).unwrap(), token);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join(TOKEN_FILE))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert!(token_matches(&token, &token));
+        assert!(!token_matches(&token[1..], &token));
+    }
+
+    #[test]
+    fn test_sse_message() {
+        assert_eq!(
+            sse_message("asr-event", r#"{"sessionId":1}"#),
+            "event: asr-event\ndata: {\"sessionId\":1}\n\n"
+        );
+    }
+
+    #[test]
+    fn test_read_head_limits() {
+        let request = b"POST /recording/start HTTP/1.1\r\nAuthorization: Bearer t\r\n\r\n{}";
+        let (request_line, headers) = read_head(&request[..]).unwrap().unwrap();
+        assert_eq!(request_line, "POST /recording/start HTTP/1.1\r\n");
+        assert_eq!(
+            headers,
+            [("authorization".to_string(), "Bearer t".to_string())]
+        );
+
+        let long_line = format!(
+            "GET /{} HTTP/1.1\r\n\r\n",
+            "a".repeat(MAX_HEAD_BYTES as usize)
+        );
+        assert_eq!(read_head(long_line.as_bytes()).unwrap(), None);
+
+        let long_header = format!(
+            "GET / HTTP/1.1\r\nX: {}\r\n\r\n",
+            "a".repeat(MAX_HEAD_BYTES as usize)
+        );
+        assert_eq!(read_head(long_header.as_bytes()).unwrap(), None);
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X: 1\r\n".repeat(MAX_HEADERS + 1)
+        );
+        assert_eq!(read_head(many_headers.as_bytes()).unwrap(), None);
+    }
+}
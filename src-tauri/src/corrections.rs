@@ -0,0 +1,506 @@
+//! 从用户修改过的输出中学习纠正
+//!
+//! 对比修改前后的文本得到词级替换（英文按单词、中文按字），记入 ~/.sayble/corrections.db。
+//! 同一替换出现达到阈值且修改后的文本像专有名词时，自动加入 ASR 热词和本地词典
+//! （词典在启用文本规整时生效）。
+
+use crate::history::relocate_connection;
+use crate::settings::{APP_SETTINGS_KEY, ASR_SETTINGS_KEY};
+use crate::spacing::is_cjk;
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// 一次替换两侧最多的词数，超过时视为改写而不是纠正
+const MAX_PHRASE_TOKENS: usize = 4;
+
+/// 修改的词数超过原文的这一比例时视为整体改写，不学习
+const MAX_CHANGED_RATIO: f64 = 0.5;
+
+/// 超过这一长度（词数乘积）的文本不做比对
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+/// 专有名词的中文长度范围（字）
+const CJK_NOUN_CHARS: std::ops::RangeInclusive<usize> = 2..=8;
+
+/// 纠正学习设置（app_settings.corrections）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CorrectionSettings {
+    pub enabled: bool,
+    /// 自动把反复出现的专有名词加入热词和词典
+    pub auto_promote: bool,
+    /// 同一替换出现多少次后自动加入
+    pub promote_threshold: u32,
+}
+
+impl Default for CorrectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_promote: true,
+            promote_threshold: 3,
+        }
+    }
+}
+
+/// 一条词级替换
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitution {
+    pub original: String,
+    pub corrected: String,
+}
+
+/// 纠正记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Correction {
+    pub original: String,
+    pub corrected: String,
+    pub count: u32,
+    pub first_seen: String,
+    pub last_seen: String,
+    /// 是否已加入热词和词典
+    pub promoted: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Word,
+    Cjk,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Token<'a> {
+    text: &'a str,
+    kind: TokenKind,
+}
+
+/// 切分为词：连续的字母数字为一个词，中日韩文字每字一个词，标点单独成词，空白丢弃
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut word_start: Option<usize> = None;
+    for (i, c) in text.char_indices() {
+        let is_word_char =
+            (c.is_alphanumeric() && !is_cjk(c)) || (c == '\'' && word_start.is_some());
+        if is_word_char {
+            word_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            tokens.push(Token {
+                text: &text[start..i],
+                kind: TokenKind::Word,
+            });
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        let kind = if c.is_alphanumeric() {
+            TokenKind::Cjk
+        } else {
+            TokenKind::Punct
+        };
+        tokens.push(Token {
+            text: &text[i..i + c.len_utf8()],
+            kind,
+        });
+    }
+    if let Some(start) = word_start {
+        tokens.push(Token {
+            text: &text[start..],
+            kind: TokenKind::Word,
+        });
+    }
+    tokens
+}
+
+/// 拼接词，两个英文单词之间加空格
+fn join(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 && token.kind == TokenKind::Word && tokens[i - 1].kind == TokenKind::Word {
+            text.push(' ');
+        }
+        text.push_str(token.text);
+    }
+    text
+}
+
+/// 最长公共子序列对齐，返回 (修改前区间, 修改后区间) 形式的差异块
+fn diff_hunks(a: &[Token], b: &[Token]) -> Vec<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+    let (n, m) = (a.len(), b.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i].text == b[j].text {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut hunk_i, mut hunk_j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a[i].text == b[j].text {
+            if hunk_i < i || hunk_j < j {
+                hunks.push((hunk_i..i, hunk_j..j));
+            }
+            i += 1;
+            j += 1;
+            hunk_i = i;
+            hunk_j = j;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            j += 1;
+        } else {
+            i += 1;
+        }
+    }
+    if hunk_i < n || hunk_j < m {
+        hunks.push((hunk_i..n, hunk_j..m));
+    }
+    hunks
+}
+
+/// 去掉区间两端的标点
+fn trim_punct(tokens: &[Token], mut range: std::ops::Range<usize>) -> std::ops::Range<usize> {
+    while range.start < range.end && tokens[range.start].kind == TokenKind::Punct {
+        range.start += 1;
+    }
+    while range.start < range.end && tokens[range.end - 1].kind == TokenKind::Punct {
+        range.end -= 1;
+    }
+    range
+}
+
+/// 对比修改前后的文本，返回词级替换；改动过多（整体改写）时返回空
+pub fn substitutions(before: &str, after: &str) -> Vec<Substitution> {
+    let a = tokenize(before);
+    let b = tokenize(after);
+    if a.is_empty() || b.is_empty() || a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        return Vec::new();
+    }
+
+    let hunks = diff_hunks(&a, &b);
+    let changed: usize = hunks.iter().map(|(ra, rb)| ra.len().max(rb.len())).sum();
+    if changed as f64 > a.len() as f64 * MAX_CHANGED_RATIO {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    for (ra, rb) in hunks {
+        let (mut ra, mut rb) = (trim_punct(&a, ra), trim_punct(&b, rb));
+        if ra.is_empty() || rb.is_empty() {
+            continue;
+        }
+        // 中文单字修改向相邻的相同汉字扩展一字，避免把单字加入词典
+        if ra.len() == 1 && rb.len() == 1 && a[ra.start].kind == TokenKind::Cjk {
+            if ra.end < a.len() && rb.end < b.len() && a[ra.end].kind == TokenKind::Cjk {
+                ra.end += 1;
+                rb.end += 1;
+            } else if ra.start > 0 && rb.start > 0 && a[ra.start - 1].kind == TokenKind::Cjk {
+                ra.start -= 1;
+                rb.start -= 1;
+            }
+        }
+        if ra.len() > MAX_PHRASE_TOKENS || rb.len() > MAX_PHRASE_TOKENS {
+            continue;
+        }
+        let substitution = Substitution {
+            original: join(&a[ra]),
+            corrected: join(&b[rb]),
+        };
+        if substitution.original != substitution.corrected && !result.contains(&substitution) {
+            result.push(substitution);
+        }
+    }
+    result
+}
+
+/// 修改后的文本是否像专有名词：含大写字母的英文词（GitHub、iPhone），或 2-8 个字的中文词
+pub fn is_proper_noun(text: &str) -> bool {
+    let tokens = tokenize(text);
+    if tokens.is_empty() || tokens.iter().any(|t| t.kind == TokenKind::Punct) {
+        return false;
+    }
+    if tokens.iter().all(|t| t.kind == TokenKind::Cjk) {
+        return CJK_NOUN_CHARS.contains(&tokens.len());
+    }
+    tokens
+        .iter()
+        .filter(|t| t.kind == TokenKind::Word)
+        .any(|t| t.text.chars().any(char::is_uppercase))
+}
+
+/// 把纠正加入 ASR 热词和本地词典，已存在时不重复添加，返回是否有改动
+pub fn promote(settings: &mut Map<String, Value>, correction: &Correction) -> bool {
+    let mut changed = false;
+
+    let hotwords = section_list(settings, ASR_SETTINGS_KEY, &["hotwords"]);
+    if !hotwords
+        .iter()
+        .any(|w| w.as_str() == Some(&correction.corrected))
+    {
+        hotwords.push(Value::String(correction.corrected.clone()));
+        changed = true;
+    }
+
+    let dictionary = section_list(settings, APP_SETTINGS_KEY, &["normalize", "dictionary"]);
+    let exists = dictionary.iter().any(|entry| {
+        entry.get("pattern").and_then(|v| v.as_str()) == Some(&correction.original)
+            && !entry
+                .get("regex")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+    });
+    if !exists {
+        dictionary.push(serde_json::json!({
+            "pattern": correction.original,
+            "replacement": correction.corrected,
+            "regex": false,
+        }));
+        changed = true;
+    }
+    changed
+}
+
+/// 取出设置中某段下的列表，路径上缺失或类型不对的节点被重建
+fn section_list<'a>(
+    settings: &'a mut Map<String, Value>,
+    section: &str,
+    path: &[&str],
+) -> &'a mut Vec<Value> {
+    let mut node = settings
+        .entry(section)
+        .or_insert_with(|| Value::Object(Map::new()));
+    for (i, key) in path.iter().enumerate() {
+        if !node.is_object() {
+            *node = Value::Object(Map::new());
+        }
+        let last = i + 1 == path.len();
+        node = node
+            .as_object_mut()
+            .expect("object")
+            .entry(*key)
+            .or_insert_with(|| {
+                if last {
+                    Value::Array(Vec::new())
+                } else {
+                    Value::Object(Map::new())
+                }
+            });
+    }
+    if !node.is_array() {
+        *node = Value::Array(Vec::new());
+    }
+    node.as_array_mut().expect("array")
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS corrections (
+    original TEXT NOT NULL,
+    corrected TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    promoted INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (original, corrected)
+);
+";
+
+const SELECT_COLUMNS: &str = "original, corrected, count, first_seen, last_seen, promoted";
+
+/// 基于 SQLite 的纠正记录表
+pub struct CorrectionDb {
+    conn: Mutex<Connection>,
+}
+
+impl CorrectionDb {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Self::with_connection(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to create corrections schema: {}", e))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 把数据库导出到 path 并改为使用新文件
+    pub fn relocate(&self, path: &Path) -> Result<(), String> {
+        let mut conn = self.conn();
+        *conn = relocate_connection(&conn, path, SCHEMA)?;
+        Ok(())
+    }
+
+    /// 累加一组替换的出现次数，返回更新后的记录
+    pub fn record(&self, items: &[Substitution], now: &str) -> Result<Vec<Correction>, String> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let mut recorded = Vec::new();
+        for item in items {
+            tx.execute(
+                "INSERT INTO corrections (original, corrected, count, first_seen, last_seen)
+                 VALUES (?1, ?2, 1, ?3, ?3)
+                 ON CONFLICT (original, corrected)
+                 DO UPDATE SET count = count + 1, last_seen = excluded.last_seen",
+                params![item.original, item.corrected, now],
+            )
+            .map_err(|e| format!("Failed to record correction: {}", e))?;
+            let correction = tx
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM corrections WHERE original = ?1 AND corrected = ?2",
+                        SELECT_COLUMNS
+                    ),
+                    params![item.original, item.corrected],
+                    row_to_correction,
+                )
+                .map_err(|e| e.to_string())?;
+            recorded.push(correction);
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(recorded)
+    }
+
+    pub fn mark_promoted(&self, original: &str, corrected: &str) -> Result<(), String> {
+        self.conn()
+            .execute(
+                "UPDATE corrections SET promoted = 1 WHERE original = ?1 AND corrected = ?2",
+                params![original, corrected],
+            )
+            .map_err(|e| format!("Failed to update correction: {}", e))?;
+        Ok(())
+    }
+
+    /// 按出现次数倒序列出全部记录
+    pub fn list(&self) -> Vec<Correction> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM corrections ORDER BY count DESC, last_seen DESC",
+            SELECT_COLUMNS
+        );
+        let result = conn.prepare(&sql).and_then(|mut stmt| {
+            stmt.query_map([], row_to_correction)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        });
+        result.unwrap_or_else(|e| {
+            log::error!("[corrections] list failed: {}", e);
+            Vec::new()
+        })
+    }
+
+    pub fn remove(&self, original: &str, corrected: &str) -> Result<usize, String> {
+        self.conn()
+            .execute(
+                "DELETE FROM corrections WHERE original = ?1 AND corrected = ?2",
+                params![original, corrected],
+            )
+            .map_err(|e| format!("Failed to remove correction: {}", e))
+    }
+}
+
+fn row_to_correction(row: &Row) -> rusqlite::Result<Correction> {
+    Ok(Correction {
+        original: row.get(0)?,
+        corrected: row.get(1)?,
+        count: row.get(2)?,
+        first_seen: row.get(3)?,
+        last_seen: row.get(4)?,
+        promoted: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sub(original: &str, corrected: &str) -> Substitution {
+        Substitution {
+            original: original.to_string(),
+            corrected: corrected.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_substitutions_words_and_cjk() {
+        assert_eq!(
+            substitutions("把代码推到 get hub 上", "把代码推到 GitHub 上"),
+            [sub("get hub", "GitHub")]
+        );
+        // 单字修改扩展到相邻的字
+        assert_eq!(
+            substitutions("明天和张三开会，", "明天和章三开会。"),
+            [sub("张三", "章三")]
+        );
+        assert_eq!(
+            substitutions("请联系李雷", "请联系李蕾"),
+            [sub("李雷", "李蕾")]
+        );
+        // 只改标点或整体改写不学习
+        assert!(substitutions("好的，收到", "好的。收到").is_empty());
+        assert!(substitutions("今天天气不错", "明早九点出发去机场").is_empty());
+        assert!(substitutions("", "文本").is_empty());
+    }
+
+    #[test]
+    fn test_is_proper_noun() {
+        assert!(is_proper_noun("GitHub"));
+        assert!(is_proper_noun("iPhone 16"));
+        assert!(is_proper_noun("章三"));
+        assert!(!is_proper_noun("hello world"));
+        assert!(!is_proper_noun("的"));
+        assert!(!is_proper_noun("这是一段很长很长的中文句子"));
+    }
+
+    #[test]
+    fn test_record_and_promote() {
+        let db = CorrectionDb::open_in_memory().unwrap();
+        let items = [sub("get hub", "GitHub")];
+        db.record(&items, "2026-10-01T09:00:00").unwrap();
+        let recorded = db.record(&items, "2026-10-02T09:00:00").unwrap();
+        assert_eq!(recorded[0].count, 2);
+        assert_eq!(recorded[0].first_seen, "2026-10-01T09:00:00");
+        assert_eq!(recorded[0].last_seen, "2026-10-02T09:00:00");
+
+        let mut settings = json!({
+            "asr_settings": { "selectedProvider": "volcengine" },
+            "app_settings": { "normalize": { "enabled": true } }
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+        assert!(promote(&mut settings, &recorded[0]));
+        assert!(!promote(&mut settings, &recorded[0]));
+        assert_eq!(settings["asr_settings"]["hotwords"], json!(["GitHub"]));
+        assert_eq!(
+            settings["app_settings"]["normalize"]["dictionary"][0]["replacement"],
+            "GitHub"
+        );
+
+        db.mark_promoted("get hub", "GitHub").unwrap();
+        assert!(db.list()[0].promoted);
+        assert_eq!(db.remove("get hub", "GitHub").unwrap(), 1);
+        assert!(db.list().is_empty());
+    }
+}
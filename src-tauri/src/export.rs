@@ -0,0 +1,260 @@
+//! 历史记录导出：Markdown 日志、CSV、JSON Lines，以及有分句时间时的 SRT / VTT 字幕
+//!
+//! 记录按时间顺序写入，不限制条数和文件大小。
+
+use crate::asr::Utterance;
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{self, Write};
+
+/// CSV 的列，与历史记录字段一致
+const CSV_COLUMNS: &[&str] = &[
+    "timestamp",
+    "app",
+    "engine",
+    "mode",
+    "promptId",
+    "status",
+    "asrText",
+    "polishedText",
+    "outputText",
+];
+
+/// 多条记录连成一个字幕文件时，记录之间留出的间隔（毫秒）
+const SUBTITLE_GAP_MS: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// 按日期分组的 Markdown 日志
+    Markdown,
+    Csv,
+    /// 每行一条记录的 JSON
+    JsonLines,
+    Srt,
+    Vtt,
+}
+
+/// 把记录（按时间正序）写入 out，返回写入的记录条数
+///
+/// 字幕格式只导出带分句时间的记录，所选记录都没有时间时返回错误。
+pub fn write_history(
+    records: &[Value],
+    format: ExportFormat,
+    out: &mut impl Write,
+) -> Result<usize, String> {
+    let result = match format {
+        ExportFormat::Markdown => write_markdown(records, out).map(|_| records.len()),
+        ExportFormat::Csv => write_csv(records, out).map(|_| records.len()),
+        ExportFormat::JsonLines => write_json_lines(records, out).map(|_| records.len()),
+        ExportFormat::Srt | ExportFormat::Vtt => {
+            let timed: Vec<_> = records
+                .iter()
+                .map(utterances_of)
+                .filter(|u| !u.is_empty())
+                .collect();
+            if timed.is_empty() {
+                return Err("所选记录没有分句时间，无法导出字幕".to_string());
+            }
+            write_subtitles(&timed, format == ExportFormat::Vtt, out).map(|_| timed.len())
+        }
+    };
+    result.map_err(|e| format!("写入导出文件失败: {}", e))
+}
+
+fn field<'a>(record: &'a Value, key: &str) -> &'a str {
+    record.get(key).and_then(|v| v.as_str()).unwrap_or_default()
+}
+
+/// 实际输出的文本，旧记录没有 outputText 时依次使用润色结果和识别原文
+fn output_text(record: &Value) -> &str {
+    ["outputText", "polishedText", "asrText"]
+        .iter()
+        .map(|key| field(record, key))
+        .find(|text| !text.is_empty())
+        .unwrap_or_default()
+}
+
+fn write_markdown(records: &[Value], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "# Sayble 识别记录")?;
+    let mut current_date = "";
+    for record in records {
+        let timestamp = field(record, "timestamp");
+        let date = timestamp.get(..10).unwrap_or(timestamp);
+        let time = timestamp.get(11..19).unwrap_or_default();
+        if date != current_date {
+            writeln!(out, "\n## {}", date)?;
+            current_date = date;
+        }
+        match field(record, "app") {
+            "" => writeln!(out, "\n### {}\n", time)?,
+            app => writeln!(out, "\n### {} · {}\n", time, app)?,
+        }
+        let text = output_text(record);
+        writeln!(out, "{}", text.trim_end())?;
+        let asr_text = field(record, "asrText").trim();
+        if !asr_text.is_empty() && asr_text != text.trim() {
+            writeln!(out)?;
+            for (i, line) in asr_text.lines().enumerate() {
+                let prefix = if i == 0 { "> 原文：" } else { "> " };
+                writeln!(out, "{}{}", prefix, line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_csv(records: &[Value], out: &mut impl Write) -> io::Result<()> {
+    // 带 BOM，Excel 打开时按 UTF-8 识别中文
+    out.write_all("\u{FEFF}".as_bytes())?;
+    writeln!(out, "{}", CSV_COLUMNS.join(","))?;
+    for record in records {
+        let row: Vec<_> = CSV_COLUMNS
+            .iter()
+            .map(|key| csv_escape(field(record, key)))
+            .collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_json_lines(records: &[Value], out: &mut impl Write) -> io::Result<()> {
+    for record in records {
+        serde_json::to_writer(&mut *out, record)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn utterances_of(record: &Value) -> Vec<Utterance> {
+    record
+        .get("utterances")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// 各记录的分句按顺序首尾相接，时间从 0 开始
+fn write_subtitles(records: &[Vec<Utterance>], vtt: bool, out: &mut impl Write) -> io::Result<()> {
+    if vtt {
+        writeln!(out, "WEBVTT")?;
+    }
+    let mut offset = 0;
+    let mut index = 0;
+    for utterances in records {
+        let mut record_end = 0;
+        for utterance in utterances {
+            let start = offset + utterance.start_ms.max(0);
+            let end = offset + utterance.end_ms.max(utterance.start_ms).max(0);
+            record_end = record_end.max(end);
+            index += 1;
+            if vtt {
+                writeln!(out, "\n{} --> {}", cue_time(start, '.'), cue_time(end, '.'))?;
+            } else {
+                if index > 1 {
+                    writeln!(out)?;
+                }
+                writeln!(out, "{}", index)?;
+                writeln!(out, "{} --> {}", cue_time(start, ','), cue_time(end, ','))?;
+            }
+            writeln!(out, "{}", utterance.text)?;
+        }
+        offset = record_end + SUBTITLE_GAP_MS;
+    }
+    Ok(())
+}
+
+/// 字幕时间 HH:MM:SS,mmm（VTT 使用 . 分隔毫秒）
+fn cue_time(ms: i64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn export(records: &[Value], format: ExportFormat) -> Result<String, String> {
+        let mut out = Vec::new();
+        write_history(records, format, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    fn sample() -> Vec<Value> {
+        vec![
+            json!({
+                "timestamp": "2026-10-01T09:30:12",
+                "asrText": "明天开会",
+                "polishedText": "明天开会。",
+                "outputText": "明天开会。",
+                "app": "WeChat.exe",
+                "utterances": [
+                    {"text": "明天开会。", "startMs": 200, "endMs": 1500}
+                ]
+            }),
+            json!({
+                "timestamp": "2026-10-02T18:00:00",
+                "asrText": "he said \"ok\", then left",
+                "polishedText": null,
+                "outputText": "he said \"ok\", then left"
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_markdown_groups_by_date() {
+        let markdown = export(&sample(), ExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("## 2026-10-01\n\n### 09:30:12 · WeChat.exe\n\n明天开会。\n"));
+        assert!(markdown.contains("> 原文：明天开会"));
+        assert!(markdown.contains("## 2026-10-02"));
+    }
+
+    #[test]
+    fn test_csv_and_json_lines() {
+        let csv = export(&sample(), ExportFormat::Csv).unwrap();
+        let lines: Vec<_> = csv.trim_start_matches('\u{FEFF}').lines().collect();
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        assert!(lines[2].ends_with(",\"he said \"\"ok\"\", then left\""));
+
+        let jsonl = export(&sample(), ExportFormat::JsonLines).unwrap();
+        let parsed: Vec<Value> = jsonl
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(parsed, sample());
+    }
+
+    #[test]
+    fn test_subtitles_use_timed_records_only() {
+        let mut records = sample();
+        records.push(json!({
+            "timestamp": "2026-10-03T08:00:00",
+            "outputText": "第二段",
+            "utterances": [{"text": "第二段", "startMs": 0, "endMs": 61_000}]
+        }));
+        let srt = export(&records, ExportFormat::Srt).unwrap();
+        assert_eq!(
+            srt,
+            "1\n00:00:00,200 --> 00:00:01,500\n明天开会。\n\n\
+             2\n00:00:02,500 --> 00:01:03,500\n第二段\n"
+        );
+        let vtt = export(&records, ExportFormat::Vtt).unwrap();
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.200 --> 00:00:01.500\n"));
+
+        assert!(export(&sample()[1..], ExportFormat::Srt).is_err());
+    }
+}
@@ -0,0 +1,610 @@
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// 历史记录保留策略（app_settings.historyRetention）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryRetention {
+    /// 最多保留的条数，0 表示不限制
+    pub max_records: usize,
+    /// 最多保留的天数，0 表示不限制
+    pub max_days: u32,
+}
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        Self {
+            max_records: 200,
+            max_days: 0,
+        }
+    }
+}
+
+/// 历史记录搜索条件，所有条件均可为空
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryQuery {
+    /// 在识别原文和润色文本中搜索
+    pub text: Option<String>,
+    /// 起始时间（含），如 "2026-01-01" 或 "2026-01-01T08:00:00"
+    pub from: Option<String>,
+    /// 结束时间（含），只写日期时包含当天全部记录
+    pub to: Option<String>,
+    /// 目标应用进程名
+    pub app: Option<String>,
+    /// ASR 服务商
+    pub engine: Option<String>,
+    /// 润色提示词 id
+    pub prompt_id: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+/// 单独成列的记录字段，顺序与 SELECT_COLUMNS 一致，其余字段存入 extra 列
+const FIELDS: &[&str] = &[
+    "id",
+    "timestamp",
+    "asrText",
+    "polishedText",
+    "outputText",
+    "app",
+    "engine",
+    "promptId",
+    "mode",
+    "status",
+];
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    seq INTEGER PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    timestamp TEXT NOT NULL,
+    asr_text TEXT NOT NULL DEFAULT '',
+    polished_text TEXT,
+    output_text TEXT NOT NULL DEFAULT '',
+    app TEXT,
+    engine TEXT,
+    prompt_id TEXT,
+    mode TEXT,
+    status TEXT,
+    extra TEXT NOT NULL DEFAULT '{}'
+);
+CREATE INDEX IF NOT EXISTS idx_history_timestamp ON history(timestamp);
+CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+    asr_text, polished_text, content='history', content_rowid='seq', tokenize='trigram'
+);
+CREATE TRIGGER IF NOT EXISTS history_ai AFTER INSERT ON history BEGIN
+    INSERT INTO history_fts(rowid, asr_text, polished_text)
+    VALUES (new.seq, new.asr_text, new.polished_text);
+END;
+CREATE TRIGGER IF NOT EXISTS history_ad AFTER DELETE ON history BEGIN
+    INSERT INTO history_fts(history_fts, rowid, asr_text, polished_text)
+    VALUES ('delete', old.seq, old.asr_text, old.polished_text);
+END;
+CREATE TRIGGER IF NOT EXISTS history_au AFTER UPDATE ON history BEGIN
+    INSERT INTO history_fts(history_fts, rowid, asr_text, polished_text)
+    VALUES ('delete', old.seq, old.asr_text, old.polished_text);
+    INSERT INTO history_fts(rowid, asr_text, polished_text)
+    VALUES (new.seq, new.asr_text, new.polished_text);
+END;
+";
+
+const SELECT_COLUMNS: &str = "id, timestamp, asr_text, polished_text, output_text, app, engine, prompt_id, mode, status, extra";
+
+/// 基于 SQLite 的历史记录存储，记录以 JSON 对象形式读写，与前端字段保持一致
+pub struct HistoryDb {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryDb {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Self::with_connection(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to create history schema: {}", e))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 把数据库导出到 path 并改为使用新文件
+    pub fn relocate(&self, path: &Path) -> Result<(), String> {
+        let mut conn = self.conn();
+        *conn = relocate_connection(&conn, path, SCHEMA)?;
+        Ok(())
+    }
+
+    /// 导入旧版 history.json 或备份中的记录（按原顺序），返回导入条数
+    pub fn import_json(&self, records: &[Value]) -> Result<usize, String> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        for record in records {
+            insert_record(&tx, record)?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(records.len())
+    }
+
+    /// 写入一条记录，缺少 id 时自动生成，返回记录 id
+    pub fn insert(&self, record: &Value) -> Result<String, String> {
+        insert_record(&self.conn(), record)
+    }
+
+    /// 按时间倒序列出全部记录
+    pub fn list(&self) -> Vec<Value> {
+        self.search(&HistoryQuery {
+            limit: Some(usize::MAX),
+            ..Default::default()
+        })
+        .unwrap_or_else(|e| {
+            log::error!("[history] list failed: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// 按 id 或 timestamp 查找，timestamp 重复时返回最近的一条
+    pub fn find(&self, key: &str) -> Option<Value> {
+        let sql = format!(
+            "SELECT {} FROM history WHERE id = ?1 OR timestamp = ?1 ORDER BY (id = ?1) DESC, seq DESC LIMIT 1",
+            SELECT_COLUMNS
+        );
+        self.conn()
+            .query_row(&sql, params![key], row_to_record)
+            .optional()
+            .unwrap_or_else(|e| {
+                log::error!("[history] find failed: {}", e);
+                None
+            })
+    }
+
+    /// 修改一条记录（按 id 或 timestamp 匹配），返回是否找到
+    pub fn update(&self, key: &str, update: impl FnOnce(&mut Value)) -> Result<bool, String> {
+        let Some(mut record) = self.find(key) else {
+            return Ok(false);
+        };
+        let id = record
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        update(&mut record);
+        record["id"] = Value::String(id.clone());
+
+        let fields = RecordFields::from_record(&record);
+        self.conn()
+            .execute(
+                "UPDATE history SET timestamp = ?2, asr_text = ?3, polished_text = ?4, output_text = ?5,
+                 app = ?6, engine = ?7, prompt_id = ?8, mode = ?9, status = ?10, extra = ?11 WHERE id = ?1",
+                params![
+                    id,
+                    fields.timestamp,
+                    fields.asr_text,
+                    fields.polished_text,
+                    fields.output_text,
+                    fields.app,
+                    fields.engine,
+                    fields.prompt_id,
+                    fields.mode,
+                    fields.status,
+                    fields.extra,
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    /// 按 id 删除；兼容旧版按 timestamp 删除
+    pub fn remove(&self, key: &str) -> Result<usize, String> {
+        self.conn()
+            .execute(
+                "DELETE FROM history WHERE id = ?1 OR (timestamp = ?1 AND NOT EXISTS (SELECT 1 FROM history WHERE id = ?1))",
+                params![key],
+            )
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        self.conn()
+            .execute("DELETE FROM history", [])
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    /// 按保留策略删除过旧或超出条数的记录，返回删除条数
+    pub fn prune(&self, retention: &HistoryRetention, now: &str) -> Result<usize, String> {
+        let conn = self.conn();
+        let mut removed = 0;
+        if retention.max_days > 0 {
+            let cutoff = chrono::NaiveDateTime::parse_from_str(now, "%Y-%m-%dT%H:%M:%S")
+                .map_err(|e| e.to_string())?
+                - chrono::Duration::days(retention.max_days as i64);
+            removed += conn
+                .execute(
+                    "DELETE FROM history WHERE timestamp < ?1",
+                    params![cutoff.format("%Y-%m-%dT%H:%M:%S").to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+        }
+        if retention.max_records > 0 {
+            removed += conn
+                .execute(
+                    "DELETE FROM history WHERE seq NOT IN (
+                        SELECT seq FROM history ORDER BY timestamp DESC, seq DESC LIMIT ?1)",
+                    params![retention.max_records as i64],
+                )
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(removed)
+    }
+
+    /// 按条件搜索，按时间倒序返回
+    /// 文本不少于 3 个字符时使用全文索引（trigram 分词），更短时退化为 LIKE 匹配
+    pub fn search(&self, query: &HistoryQuery) -> Result<Vec<Value>, String> {
+        let mut sql = format!("SELECT {} FROM history WHERE 1 = 1", SELECT_COLUMNS);
+        let mut args: Vec<String> = Vec::new();
+
+        if let Some(text) = non_empty(&query.text) {
+            if text.chars().count() >= 3 {
+                sql.push_str(
+                    " AND seq IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)",
+                );
+                args.push(format!("\"{}\"", text.replace('"', "\"\"")));
+            } else {
+                sql.push_str(
+                    " AND (asr_text LIKE ? ESCAPE '\\' OR IFNULL(polished_text, '') LIKE ? ESCAPE '\\')",
+                );
+                let pattern = format!("%{}%", escape_like(text));
+                args.push(pattern.clone());
+                args.push(pattern);
+            }
+        }
+        if let Some(from) = non_empty(&query.from) {
+            sql.push_str(" AND timestamp >= ?");
+            args.push(from.to_string());
+        }
+        if let Some(to) = non_empty(&query.to) {
+            // 比较相同长度的前缀，"2026-01-31" 包含当天全部记录
+            sql.push_str(" AND substr(timestamp, 1, length(?)) <= ?");
+            args.push(to.to_string());
+            args.push(to.to_string());
+        }
+        for (value, column) in [
+            (&query.app, "app"),
+            (&query.engine, "engine"),
+            (&query.prompt_id, "prompt_id"),
+        ] {
+            if let Some(value) = non_empty(value) {
+                sql.push_str(&format!(" AND {} = ? COLLATE NOCASE", column));
+                args.push(value.to_string());
+            }
+        }
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .min(i64::MAX as usize);
+        sql.push_str(&format!(
+            " ORDER BY timestamp DESC, seq DESC LIMIT {} OFFSET {}",
+            limit,
+            query.offset.unwrap_or(0)
+        ));
+
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params_from_iter(args.iter()), row_to_record)
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// 用 VACUUM INTO 导出一致的副本并打开，供数据目录迁移使用
+///
+/// path 已存在时（迁移失败退回原目录）直接打开原有的数据库。
+pub(crate) fn relocate_connection(
+    conn: &Connection,
+    path: &Path,
+    schema: &str,
+) -> Result<Connection, String> {
+    if !path.exists() {
+        conn.execute("VACUUM INTO ?1", params![path.to_string_lossy()])
+            .map_err(|e| format!("Failed to copy database to {}: {}", path.display(), e))?;
+    }
+    let new_conn =
+        Connection::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    new_conn
+        .execute_batch(schema)
+        .map_err(|e| format!("Failed to create schema: {}", e))?;
+    Ok(new_conn)
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// 一条记录拆分到各列后的值
+struct RecordFields {
+    timestamp: String,
+    asr_text: String,
+    polished_text: Option<String>,
+    output_text: String,
+    app: Option<String>,
+    engine: Option<String>,
+    prompt_id: Option<String>,
+    mode: Option<String>,
+    status: Option<String>,
+    extra: String,
+}
+
+impl RecordFields {
+    fn from_record(record: &Value) -> Self {
+        let text = |key: &str| record.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let extra: Map<String, Value> = record
+            .as_object()
+            .map(|obj| {
+                obj.iter()
+                    .filter(|(key, _)| !FIELDS.contains(&key.as_str()))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            timestamp: text("timestamp").unwrap_or_default(),
+            asr_text: text("asrText").unwrap_or_default(),
+            polished_text: text("polishedText"),
+            output_text: text("outputText").unwrap_or_default(),
+            app: text("app"),
+            engine: text("engine"),
+            prompt_id: text("promptId"),
+            mode: text("mode"),
+            status: text("status"),
+            extra: Value::Object(extra).to_string(),
+        }
+    }
+}
+
+fn insert_record(conn: &Connection, record: &Value) -> Result<String, String> {
+    let id = record
+        .get("id")
+        .and_then(|v| v.as_str())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let fields = RecordFields::from_record(record);
+    conn.execute(
+        "INSERT OR IGNORE INTO history
+         (id, timestamp, asr_text, polished_text, output_text, app, engine, prompt_id, mode, status, extra)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            id,
+            fields.timestamp,
+            fields.asr_text,
+            fields.polished_text,
+            fields.output_text,
+            fields.app,
+            fields.engine,
+            fields.prompt_id,
+            fields.mode,
+            fields.status,
+            fields.extra,
+        ],
+    )
+    .map_err(|e| format!("Failed to insert history: {}", e))?;
+    Ok(id)
+}
+
+fn row_to_record(row: &Row) -> rusqlite::Result<Value> {
+    let mut record: Map<String, Value> = row
+        .get::<_, String>(10)
+        .ok()
+        .and_then(|extra| serde_json::from_str(&extra).ok())
+        .unwrap_or_default();
+    for (index, field) in FIELDS.iter().enumerate() {
+        let value: Option<String> = row.get(index)?;
+        match value {
+            Some(value) => {
+                record.insert(field.to_string(), Value::String(value));
+            }
+            // polishedText 为空时保留 null，与旧版记录格式一致
+            None if *field == "polishedText" => {
+                record.insert(field.to_string(), Value::Null);
+            }
+            None => {}
+        }
+    }
+    Ok(Value::Object(record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(timestamp: &str, asr: &str) -> Value {
+        json!({
+            "timestamp": timestamp,
+            "asrText": asr,
+            "polishedText": null,
+            "outputText": asr,
+        })
+    }
+
+    #[test]
+    fn test_insert_find_update_remove() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        // 同一秒的两条记录通过 id 区分
+        let first = db.insert(&record("2026-01-01T08:00:00", "第一条")).unwrap();
+        let second = db
+            .insert(&json!({
+                "timestamp": "2026-01-01T08:00:00",
+                "asrText": "第二条",
+                "polishedText": "第二条。",
+                "outputText": "第二条。",
+                "mode": "translate",
+                "targetLanguage": "en",
+            }))
+            .unwrap();
+        assert_ne!(first, second);
+
+        let found = db.find(&second).unwrap();
+        assert_eq!(found["polishedText"], "第二条。");
+        assert_eq!(found["targetLanguage"], "en");
+        assert_eq!(db.find(&first).unwrap()["polishedText"], Value::Null);
+        // 按 timestamp 查找时返回最近的一条
+        assert_eq!(
+            db.find("2026-01-01T08:00:00").unwrap()["id"],
+            second.as_str()
+        );
+
+        assert!(db
+            .update(&first, |r| r["status"] = json!("undone"))
+            .unwrap());
+        assert_eq!(db.find(&first).unwrap()["status"], "undone");
+        assert!(!db.update("missing", |_| {}).unwrap());
+
+        assert_eq!(db.remove(&first).unwrap(), 1);
+        assert_eq!(db.list().len(), 1);
+        db.clear().unwrap();
+        assert!(db.list().is_empty());
+    }
+
+    #[test]
+    fn test_import_keeps_existing_ids() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let mut legacy = record("2025-12-31T23:59:59", "旧记录");
+        legacy["id"] = json!("legacy-id");
+        let imported = db
+            .import_json(&[legacy, record("2026-01-01T00:00:00", "新记录")])
+            .unwrap();
+        assert_eq!(imported, 2);
+
+        let records = db.list();
+        assert_eq!(records[0]["asrText"], "新记录");
+        assert_eq!(records[1]["id"], "legacy-id");
+    }
+
+    #[test]
+    fn test_prune_by_count_and_age() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        for day in 1..=5 {
+            db.insert(&record(&format!("2026-01-0{}T12:00:00", day), "记录"))
+                .unwrap();
+        }
+        let retention = HistoryRetention {
+            max_records: 3,
+            max_days: 0,
+        };
+        assert_eq!(db.prune(&retention, "2026-01-05T12:00:00").unwrap(), 2);
+
+        let retention = HistoryRetention {
+            max_records: 0,
+            max_days: 1,
+        };
+        assert_eq!(db.prune(&retention, "2026-01-05T12:00:00").unwrap(), 1);
+        let records = db.list();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["timestamp"], "2026-01-04T12:00:00");
+    }
+
+    #[test]
+    fn test_search_text_and_filters() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        db.insert(&json!({
+            "timestamp": "2026-01-01T09:00:00",
+            "asrText": "明天下午开会讨论预算",
+            "outputText": "明天下午开会讨论预算",
+            "app": "WeChat.exe",
+            "engine": "volcengine",
+        }))
+        .unwrap();
+        db.insert(&json!({
+            "timestamp": "2026-01-02T10:00:00",
+            "asrText": "send the budget report",
+            "polishedText": "Send the budget report.",
+            "outputText": "Send the budget report.",
+            "app": "slack",
+            "engine": "whisper",
+            "promptId": "formal",
+        }))
+        .unwrap();
+
+        let search = |query: HistoryQuery| db.search(&query).unwrap();
+        let text = |t: &str| HistoryQuery {
+            text: Some(t.to_string()),
+            ..Default::default()
+        };
+
+        // 全文索引支持中文子串
+        assert_eq!(search(text("讨论预算")).len(), 1);
+        assert_eq!(search(text("Budget Report")).len(), 1);
+        // 短于 3 个字符时使用 LIKE
+        assert_eq!(search(text("开会")).len(), 1);
+        assert_eq!(search(text("%")).len(), 0);
+
+        let by_date = search(HistoryQuery {
+            from: Some("2026-01-02".to_string()),
+            to: Some("2026-01-02".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(by_date.len(), 1);
+        assert_eq!(by_date[0]["app"], "slack");
+
+        assert_eq!(
+            search(HistoryQuery {
+                to: Some("2026-01-01".to_string()),
+                ..Default::default()
+            })
+            .len(),
+            1
+        );
+        assert_eq!(
+            search(HistoryQuery {
+                app: Some("wechat.exe".to_string()),
+                engine: Some("volcengine".to_string()),
+                ..Default::default()
+            })
+            .len(),
+            1
+        );
+        assert_eq!(
+            search(HistoryQuery {
+                prompt_id: Some("formal".to_string()),
+                text: Some("明天".to_string()),
+                ..Default::default()
+            })
+            .len(),
+            0
+        );
+        assert_eq!(
+            search(HistoryQuery {
+                limit: Some(1),
+                ..Default::default()
+            })[0]["app"],
+            "slack"
+        );
+    }
+}
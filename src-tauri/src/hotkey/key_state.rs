@@ -0,0 +1,498 @@
+use super::HotkeyEvent;
+use crate::config::{HotkeyBinding, HotkeyConfig, Modifier, TriggerMode};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// 修饰键虚拟键码（与 Windows VK code 一致，各平台后端统一转换为这套键码）
+pub(crate) const VK_LSHIFT: u32 = 0xA0;
+pub(crate) const VK_RSHIFT: u32 = 0xA1;
+pub(crate) const VK_LCONTROL: u32 = 0xA2;
+pub(crate) const VK_RCONTROL: u32 = 0xA3;
+pub(crate) const VK_LMENU: u32 = 0xA4;
+pub(crate) const VK_RMENU: u32 = 0xA5;
+pub(crate) const VK_ESCAPE: u32 = 0x1B;
+
+/// 普通键超时时间（秒）：超过此时间未收到 KeyUp 则自动清除
+pub(crate) const KEY_EXPIRE_SECS: u64 = 5;
+
+/// 平台钩子产生的原始按键事件（键码为虚拟键码）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RawKeyEvent {
+    KeyDown(u32),
+    KeyUp(u32),
+}
+
+/// 判断虚拟键码是否为已知的普通键（字母、数字、标点、功能键等）
+/// 过滤掉未知/特殊键码，防止因 KeyUp 丢失导致 pressed_keys 永久卡住
+pub(crate) fn is_known_key(vk: u32) -> bool {
+    matches!(vk,
+        0x08..=0x09 |       // Backspace, Tab
+        0x0D |              // Enter
+        0x1B |              // Escape
+        0x20 |              // Space
+        0x21..=0x2E |       // PageUp ~ Delete
+        0x30..=0x39 |       // 0-9
+        0x41..=0x5A |       // A-Z
+        0x60..=0x6F |       // Numpad 0-9, *, +, -, ., /
+        0x70..=0x87 |       // F1-F24
+        0xBA..=0xC0 |       // ;=,-./`
+        0xDB..=0xDF |       // [\]'
+        0xE2                // OEM_102
+    )
+}
+
+/// 按键状态追踪，与具体平台的键盘钩子无关
+#[derive(Debug, Default)]
+pub(crate) struct KeyState {
+    pub(crate) pressed_modifiers: HashSet<u32>,
+    pub(crate) pressed_keys: HashSet<u32>,
+    /// 修饰键按下时间戳，用于 KeyUp 丢失时恢复热键状态
+    pub(crate) modifier_press_times: HashMap<u32, Instant>,
+    /// 普通键按下时间戳，用于超时清理
+    pub(crate) key_press_times: HashMap<u32, Instant>,
+    /// 当前按住中的按住说话快捷键
+    held_binding: Option<HotkeyBinding>,
+}
+
+impl KeyState {
+    pub(crate) fn modifier_from_vk(vk: u32) -> Option<Modifier> {
+        match vk {
+            VK_LCONTROL => Some(Modifier::LeftCtrl),
+            VK_RCONTROL => Some(Modifier::RightCtrl),
+            VK_LMENU => Some(Modifier::LeftAlt),
+            VK_RMENU => Some(Modifier::RightAlt),
+            VK_LSHIFT => Some(Modifier::LeftShift),
+            VK_RSHIFT => Some(Modifier::RightShift),
+            _ => None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn vk_from_modifier(modifier: &Modifier) -> u32 {
+        match modifier {
+            Modifier::LeftCtrl => VK_LCONTROL,
+            Modifier::RightCtrl => VK_RCONTROL,
+            Modifier::LeftAlt => VK_LMENU,
+            Modifier::RightAlt => VK_RMENU,
+            Modifier::LeftShift => VK_LSHIFT,
+            Modifier::RightShift => VK_RSHIFT,
+        }
+    }
+
+    pub(crate) fn is_modifier(vk: u32) -> bool {
+        Self::modifier_from_vk(vk).is_some()
+    }
+
+    /// 清除超时按键（KeyUp 丢失保护）
+    /// is_physical_key_down 查询修饰键是否仍被物理按住，按住的修饰键只刷新时间戳
+    pub(crate) fn expire_stale_keys(
+        &mut self,
+        now: Instant,
+        is_physical_key_down: impl Fn(u32) -> bool,
+    ) {
+        let (refreshed_modifiers, expired_modifiers): (Vec<u32>, Vec<u32>) = self
+            .modifier_press_times
+            .iter()
+            .filter(|(_, t)| now.duration_since(**t).as_secs() >= KEY_EXPIRE_SECS)
+            .map(|(&vk, _)| vk)
+            .partition(|&vk| is_physical_key_down(vk));
+        for vk in &refreshed_modifiers {
+            self.modifier_press_times.insert(*vk, now);
+        }
+        for vk in &expired_modifiers {
+            self.pressed_modifiers.remove(vk);
+            self.modifier_press_times.remove(vk);
+        }
+        if !expired_modifiers.is_empty() {
+            log::debug!(
+                "[hotkey] expired stale modifiers: {:?}, remaining pressed_modifiers={:?}",
+                expired_modifiers,
+                self.pressed_modifiers
+            );
+        }
+
+        let expired: Vec<u32> = self
+            .key_press_times
+            .iter()
+            .filter(|(_, t)| now.duration_since(**t).as_secs() >= KEY_EXPIRE_SECS)
+            .map(|(&vk, _)| vk)
+            .collect();
+        for vk in &expired {
+            self.pressed_keys.remove(vk);
+            self.key_press_times.remove(vk);
+        }
+        if !expired.is_empty() {
+            log::debug!(
+                "[hotkey] expired stale keys: {:?}, remaining pressed_keys={:?}",
+                expired,
+                self.pressed_keys
+            );
+        }
+    }
+
+    pub(crate) fn active_modifiers(&self) -> HashSet<Modifier> {
+        self.pressed_modifiers
+            .iter()
+            .filter_map(|&vk| Self::modifier_from_vk(vk))
+            .collect()
+    }
+
+    /// 判断按键是否属于该快捷键组合（用于检测按住说话的松开）
+    pub(crate) fn is_part_of_binding(vk: u32, binding: &HotkeyBinding) -> bool {
+        match Self::modifier_from_vk(vk) {
+            Some(modifier) => binding.modifiers.contains(&modifier),
+            None => binding.key != 0 && vk == binding.key,
+        }
+    }
+
+    pub(crate) fn matches_binding(&self, binding: &HotkeyBinding) -> bool {
+        let active = self.active_modifiers();
+        let required: HashSet<Modifier> = binding.modifiers.iter().cloned().collect();
+
+        if active != required {
+            return false;
+        }
+
+        if binding.key == 0 {
+            // 仅修饰键模式：不能有其他普通键同时按下
+            self.pressed_keys.is_empty()
+        } else {
+            self.pressed_keys.contains(&binding.key)
+        }
+    }
+
+    /// 处理一个原始按键事件，返回需要发出的快捷键事件
+    pub(crate) fn handle_event(
+        &mut self,
+        event: RawKeyEvent,
+        configs: &[HotkeyConfig],
+        now: Instant,
+        is_physical_key_down: impl Fn(u32) -> bool,
+    ) -> Vec<HotkeyEvent> {
+        let mut events = Vec::new();
+        match event {
+            RawKeyEvent::KeyDown(vk) => {
+                // 过滤未知键码：非修饰键且不在已知键码范围内，直接忽略
+                if !Self::is_modifier(vk) && !is_known_key(vk) {
+                    log::debug!("[hotkey] ignoring unknown vk=0x{:X}", vk);
+                    return events;
+                }
+
+                // 每次 KeyDown 时顺便清理超时的 stale keys
+                self.expire_stale_keys(now, is_physical_key_down);
+
+                // 去重：忽略按键重复（键已在 pressed 集合中）
+                let is_repeat = if Self::is_modifier(vk) {
+                    let inserted = self.pressed_modifiers.insert(vk);
+                    if inserted {
+                        self.modifier_press_times.insert(vk, now);
+                    }
+                    !inserted
+                } else {
+                    let inserted = self.pressed_keys.insert(vk);
+                    if inserted {
+                        self.key_press_times.insert(vk, now);
+                    }
+                    !inserted
+                };
+                if is_repeat {
+                    return events;
+                }
+
+                log::debug!(
+                    "[hotkey] KeyDown vk=0x{:X}, is_modifier={}, active_modifiers={:?}, pressed_keys={:?}",
+                    vk,
+                    Self::is_modifier(vk),
+                    self.active_modifiers(),
+                    self.pressed_keys
+                );
+
+                // 检查 ESC 键取消（始终发送，由前端判断是否在录音）
+                if vk == VK_ESCAPE {
+                    log::info!("[hotkey] ESC cancel");
+                    events.push(HotkeyEvent::CancelRecording);
+                    return events;
+                }
+
+                for config in configs {
+                    if self.matches_binding(&config.binding) {
+                        if config.trigger == TriggerMode::Hold {
+                            events.push(HotkeyEvent::HoldPressed);
+                            self.held_binding = Some(config.binding.clone());
+                        }
+                        let event = HotkeyEvent::for_config(config);
+                        log::info!("[hotkey] {:?} => sending {:?}", config.action, event);
+                        events.push(event);
+                    }
+                }
+            }
+            RawKeyEvent::KeyUp(vk) => {
+                if Self::is_modifier(vk) {
+                    self.pressed_modifiers.remove(&vk);
+                    self.modifier_press_times.remove(&vk);
+                } else {
+                    self.pressed_keys.remove(&vk);
+                    self.key_press_times.remove(&vk);
+                }
+
+                if self
+                    .held_binding
+                    .as_ref()
+                    .is_some_and(|b| Self::is_part_of_binding(vk, b))
+                {
+                    self.held_binding = None;
+                    log::info!("[hotkey] hold released vk=0x{:X}", vk);
+                    events.push(HotkeyEvent::HoldReleased);
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HotkeyAction;
+    use std::time::Duration;
+
+    fn not_down(_vk: u32) -> bool {
+        false
+    }
+
+    fn config(modifiers: Vec<Modifier>, key: u32, trigger: TriggerMode) -> HotkeyConfig {
+        HotkeyConfig {
+            binding: HotkeyBinding { modifiers, key },
+            action: HotkeyAction::ToggleRecording,
+            translation: None,
+            profile_id: None,
+            trigger,
+        }
+    }
+
+    #[test]
+    fn test_modifier_from_vk() {
+        assert_eq!(
+            KeyState::modifier_from_vk(VK_LCONTROL),
+            Some(Modifier::LeftCtrl)
+        );
+        assert_eq!(
+            KeyState::modifier_from_vk(VK_RCONTROL),
+            Some(Modifier::RightCtrl)
+        );
+        assert_eq!(
+            KeyState::modifier_from_vk(VK_LMENU),
+            Some(Modifier::LeftAlt)
+        );
+        assert_eq!(
+            KeyState::modifier_from_vk(VK_RMENU),
+            Some(Modifier::RightAlt)
+        );
+        assert_eq!(
+            KeyState::modifier_from_vk(VK_LSHIFT),
+            Some(Modifier::LeftShift)
+        );
+        assert_eq!(
+            KeyState::modifier_from_vk(VK_RSHIFT),
+            Some(Modifier::RightShift)
+        );
+        // 非修饰键
+        assert_eq!(KeyState::modifier_from_vk(0x41), None); // 'A'
+    }
+
+    #[test]
+    fn test_vk_from_modifier() {
+        assert_eq!(KeyState::vk_from_modifier(&Modifier::LeftCtrl), VK_LCONTROL);
+        assert_eq!(
+            KeyState::vk_from_modifier(&Modifier::RightCtrl),
+            VK_RCONTROL
+        );
+    }
+
+    #[test]
+    fn test_is_modifier() {
+        assert!(KeyState::is_modifier(VK_LCONTROL));
+        assert!(KeyState::is_modifier(VK_RSHIFT));
+        assert!(!KeyState::is_modifier(0x41)); // 'A'
+        assert!(!KeyState::is_modifier(0x20)); // Space
+    }
+
+    #[test]
+    fn test_key_state_default() {
+        let state = KeyState::default();
+        assert!(state.pressed_modifiers.is_empty());
+        assert!(state.pressed_keys.is_empty());
+        assert!(state.modifier_press_times.is_empty());
+    }
+
+    #[test]
+    fn test_expire_stale_modifier() {
+        let mut state = KeyState::default();
+        let ctrl = VK_LCONTROL;
+        state.pressed_modifiers.insert(ctrl);
+        state.modifier_press_times.insert(
+            ctrl,
+            Instant::now() - Duration::from_secs(KEY_EXPIRE_SECS + 1),
+        );
+
+        state.expire_stale_keys(Instant::now(), not_down);
+
+        assert!(state.pressed_modifiers.is_empty());
+        assert!(state.modifier_press_times.is_empty());
+    }
+
+    #[test]
+    fn test_expire_keeps_physically_held_modifier() {
+        let mut state = KeyState::default();
+        let start = Instant::now();
+        state.pressed_modifiers.insert(VK_RCONTROL);
+        state.modifier_press_times.insert(VK_RCONTROL, start);
+        state.pressed_keys.insert(0x41);
+        state.key_press_times.insert(0x41, start);
+
+        let later = start + Duration::from_secs(KEY_EXPIRE_SECS + 1);
+        state.expire_stale_keys(later, |vk| vk == VK_RCONTROL);
+
+        assert!(state.pressed_modifiers.contains(&VK_RCONTROL));
+        assert_eq!(state.modifier_press_times.get(&VK_RCONTROL), Some(&later));
+        assert!(state.pressed_keys.is_empty());
+    }
+
+    #[test]
+    fn test_active_modifiers() {
+        let mut state = KeyState::default();
+        state.pressed_modifiers.insert(VK_LCONTROL);
+        state.pressed_modifiers.insert(VK_LSHIFT);
+
+        let active = state.active_modifiers();
+        assert!(active.contains(&Modifier::LeftCtrl));
+        assert!(active.contains(&Modifier::LeftShift));
+        assert!(!active.contains(&Modifier::RightCtrl));
+        assert_eq!(active.len(), 2);
+    }
+
+    #[test]
+    fn test_matches_binding_modifier_only() {
+        let mut state = KeyState::default();
+        state.pressed_modifiers.insert(VK_RCONTROL);
+
+        // 仅右Ctrl
+        let binding = HotkeyBinding {
+            modifiers: vec![Modifier::RightCtrl],
+            key: 0,
+        };
+        assert!(state.matches_binding(&binding));
+
+        // 左Ctrl 不匹配
+        let binding_left = HotkeyBinding {
+            modifiers: vec![Modifier::LeftCtrl],
+            key: 0,
+        };
+        assert!(!state.matches_binding(&binding_left));
+    }
+
+    #[test]
+    fn test_matches_binding_modifier_plus_key() {
+        let mut state = KeyState::default();
+        state.pressed_modifiers.insert(VK_LCONTROL);
+        state.pressed_keys.insert(0x20); // Space
+
+        let binding = HotkeyBinding {
+            modifiers: vec![Modifier::LeftCtrl],
+            key: 0x20,
+        };
+        assert!(state.matches_binding(&binding));
+
+        // 缺少 Space 键
+        let mut state_no_key = KeyState::default();
+        state_no_key.pressed_modifiers.insert(VK_LCONTROL);
+        assert!(!state_no_key.matches_binding(&binding));
+    }
+
+    #[test]
+    fn test_matches_binding_extra_modifiers_fail() {
+        let mut state = KeyState::default();
+        state.pressed_modifiers.insert(VK_LCONTROL);
+        state.pressed_modifiers.insert(VK_LSHIFT);
+
+        // 只要求左Ctrl，但按了左Ctrl+左Shift，不应匹配
+        let binding = HotkeyBinding {
+            modifiers: vec![Modifier::LeftCtrl],
+            key: 0,
+        };
+        assert!(!state.matches_binding(&binding));
+    }
+
+    #[test]
+    fn test_is_part_of_binding() {
+        let binding = HotkeyBinding {
+            modifiers: vec![Modifier::RightCtrl],
+            key: 0x20,
+        };
+        assert!(KeyState::is_part_of_binding(VK_RCONTROL, &binding));
+        assert!(KeyState::is_part_of_binding(0x20, &binding));
+        assert!(!KeyState::is_part_of_binding(VK_LCONTROL, &binding));
+        assert!(!KeyState::is_part_of_binding(0x41, &binding));
+
+        let modifier_only = HotkeyBinding {
+            modifiers: vec![Modifier::RightCtrl],
+            key: 0,
+        };
+        assert!(!KeyState::is_part_of_binding(0, &modifier_only));
+    }
+
+    #[test]
+    fn test_handle_event_toggle_binding() {
+        let configs = vec![config(vec![Modifier::LeftCtrl], 0x20, TriggerMode::Toggle)];
+        let mut state = KeyState::default();
+        let now = Instant::now();
+
+        let events = state.handle_event(RawKeyEvent::KeyDown(VK_LCONTROL), &configs, now, not_down);
+        assert!(events.is_empty());
+        let events = state.handle_event(RawKeyEvent::KeyDown(0x20), &configs, now, not_down);
+        assert!(matches!(events.as_slice(), [HotkeyEvent::ToggleRecording]));
+        // 按键重复不会再次触发
+        let events = state.handle_event(RawKeyEvent::KeyDown(0x20), &configs, now, not_down);
+        assert!(events.is_empty());
+        // 右Ctrl + Space 不匹配左Ctrl 绑定
+        state.handle_event(RawKeyEvent::KeyUp(VK_LCONTROL), &configs, now, not_down);
+        state.handle_event(RawKeyEvent::KeyUp(0x20), &configs, now, not_down);
+        state.handle_event(RawKeyEvent::KeyDown(VK_RCONTROL), &configs, now, not_down);
+        let events = state.handle_event(RawKeyEvent::KeyDown(0x20), &configs, now, not_down);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_handle_event_hold_binding() {
+        let configs = vec![config(vec![Modifier::RightAlt], 0, TriggerMode::Hold)];
+        let mut state = KeyState::default();
+        let now = Instant::now();
+
+        let events = state.handle_event(RawKeyEvent::KeyDown(VK_RMENU), &configs, now, not_down);
+        assert!(matches!(
+            events.as_slice(),
+            [HotkeyEvent::HoldPressed, HotkeyEvent::ToggleRecording]
+        ));
+        // 松开其他键不算松开快捷键
+        let events = state.handle_event(RawKeyEvent::KeyUp(VK_LSHIFT), &configs, now, not_down);
+        assert!(events.is_empty());
+        let events = state.handle_event(RawKeyEvent::KeyUp(VK_RMENU), &configs, now, not_down);
+        assert!(matches!(events.as_slice(), [HotkeyEvent::HoldReleased]));
+        let events = state.handle_event(RawKeyEvent::KeyUp(VK_RMENU), &configs, now, not_down);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_handle_event_escape_and_unknown_keys() {
+        let configs = vec![config(vec![Modifier::RightCtrl], 0, TriggerMode::Toggle)];
+        let mut state = KeyState::default();
+        let now = Instant::now();
+
+        let events = state.handle_event(RawKeyEvent::KeyDown(VK_ESCAPE), &configs, now, not_down);
+        assert!(matches!(events.as_slice(), [HotkeyEvent::CancelRecording]));
+        // 未知键码（如 0xFF）被忽略，不进入 pressed_keys
+        let events = state.handle_event(RawKeyEvent::KeyDown(0xFF), &configs, now, not_down);
+        assert!(events.is_empty());
+        assert!(!state.pressed_keys.contains(&0xFF));
+    }
+}
@@ -0,0 +1,582 @@
+//! Linux 全局快捷键后端
+//!
+//! X11 会话通过 XInput2 在根窗口上监听 RawKeyPress/RawKeyRelease，不抢占按键；
+//! Wayland 会话（或 X11 不可用时）回退为直接读取 /dev/input 下的 evdev 键盘设备，
+//! 需要当前用户有读取权限（通常加入 input 组）。
+//! 两个后端都把 evdev 键码转换为虚拟键码，产生与 Windows 钩子相同的 RawKeyEvent。
+//! 本程序自己模拟的按键（XTest、uinput 虚拟键盘）会被忽略，与 Windows 钩子跳过注入按键一致。
+
+use super::key_state::{
+    RawKeyEvent, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_RCONTROL, VK_RMENU, VK_RSHIFT,
+};
+use super::win_hook::{process_raw_events, HotkeyManager};
+use crate::input::linux::VIRTUAL_KEYBOARD_NAME;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// X11 键码 = evdev 键码 + 8
+const X11_KEYCODE_OFFSET: u32 = 8;
+
+/// 监听线程等待按键的超时（毫秒），超时后检查是否已停止
+const POLL_TIMEOUT_MS: i32 = 200;
+
+/// 按键监听后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    X11,
+    Evdev,
+}
+
+/// 正在使用的后端，查询按键真实状态时使用同一来源
+static BACKEND: Mutex<Option<Backend>> = Mutex::new(None);
+
+impl HotkeyManager {
+    pub fn start(&mut self) -> Result<(), String> {
+        if *self.running.lock().map_err(|e| e.to_string())? {
+            return Err("Hotkey manager already running".to_string());
+        }
+
+        let (event_tx, event_rx) = mpsc::channel();
+        let (raw_tx, raw_rx) = mpsc::channel::<RawKeyEvent>();
+
+        *self.running.lock().map_err(|e| e.to_string())? = true;
+        match start_listener(raw_tx.clone(), self.running.clone()) {
+            Ok(backend) => *BACKEND.lock().unwrap_or_else(|e| e.into_inner()) = Some(backend),
+            Err(e) => {
+                *self.running.lock().map_err(|e| e.to_string())? = false;
+                return Err(e);
+            }
+        }
+        self.event_rx = Some(event_rx);
+        if let Ok(mut tx) = self.inject_tx.lock() {
+            *tx = Some(raw_tx);
+        }
+
+        let configs = self.configs.clone();
+        let running = self.running.clone();
+        let modifiers_held = self.modifiers_held.clone();
+        thread::spawn(move || {
+            process_raw_events(raw_rx, configs, running, modifiers_held, event_tx)
+        });
+
+        Ok(())
+    }
+}
+
+/// 查询修饰键是否仍被物理按住，用于清理 KeyUp 丢失的修饰键
+/// 非修饰键或查询失败时返回 false，由超时清除
+pub(crate) fn is_key_down(vk: u32) -> bool {
+    let Some(code) = evdev_from_modifier(vk) else {
+        return false;
+    };
+    let backend = *BACKEND.lock().unwrap_or_else(|e| e.into_inner());
+    let result = match backend {
+        Some(Backend::X11) => x11::is_key_down(code),
+        Some(Backend::Evdev) => evdev_backend::is_key_down(code),
+        None => return false,
+    };
+    result.unwrap_or_else(|e| {
+        log::debug!("[hotkey] key state query failed: {}", e);
+        false
+    })
+}
+
+/// 启动合适的按键监听后端，返回所用后端
+fn start_listener(
+    raw_tx: mpsc::Sender<RawKeyEvent>,
+    running: Arc<Mutex<bool>>,
+) -> Result<Backend, String> {
+    let session_type = std::env::var("XDG_SESSION_TYPE").ok();
+    let wayland_display = std::env::var("WAYLAND_DISPLAY").ok();
+    let display = std::env::var("DISPLAY").ok();
+
+    if !prefers_evdev(
+        session_type.as_deref(),
+        wayland_display.as_deref(),
+        display.as_deref(),
+    ) {
+        match x11::start(raw_tx.clone(), running.clone()) {
+            Ok(()) => {
+                log::info!("[hotkey] using X11 XInput2 backend");
+                return Ok(Backend::X11);
+            }
+            Err(e) => {
+                log::warn!(
+                    "[hotkey] X11 backend unavailable: {}, falling back to evdev",
+                    e
+                );
+            }
+        }
+    }
+
+    evdev_backend::start(raw_tx, running)?;
+    log::info!("[hotkey] using evdev backend");
+    Ok(Backend::Evdev)
+}
+
+/// Wayland 下 XInput2 只能看到 XWayland 窗口的按键，必须改用 evdev
+fn prefers_evdev(
+    session_type: Option<&str>,
+    wayland_display: Option<&str>,
+    display: Option<&str>,
+) -> bool {
+    let is_wayland =
+        session_type == Some("wayland") || wayland_display.is_some_and(|d| !d.is_empty());
+    let has_x11 = display.is_some_and(|d| !d.is_empty());
+    is_wayland || !has_x11
+}
+
+/// 将 Linux evdev 键码（KEY_*）转换为虚拟键码，未知键返回 None
+pub(crate) fn vk_from_evdev(code: u16) -> Option<u32> {
+    let vk = match code {
+        1 => 0x1B,                               // KEY_ESC
+        2..=10 => 0x31 + (code as u32 - 2),      // KEY_1 ~ KEY_9
+        11 => 0x30,                              // KEY_0
+        12 => 0xBD,                              // KEY_MINUS
+        13 => 0xBB,                              // KEY_EQUAL
+        14 => 0x08,                              // KEY_BACKSPACE
+        15 => 0x09,                              // KEY_TAB
+        16 => 0x51,                              // Q
+        17 => 0x57,                              // W
+        18 => 0x45,                              // E
+        19 => 0x52,                              // R
+        20 => 0x54,                              // T
+        21 => 0x59,                              // Y
+        22 => 0x55,                              // U
+        23 => 0x49,                              // I
+        24 => 0x4F,                              // O
+        25 => 0x50,                              // P
+        26 => 0xDB,                              // KEY_LEFTBRACE
+        27 => 0xDD,                              // KEY_RIGHTBRACE
+        28 | 96 => 0x0D,                         // KEY_ENTER, KEY_KPENTER
+        29 => VK_LCONTROL,                       // KEY_LEFTCTRL
+        30 => 0x41,                              // A
+        31 => 0x53,                              // S
+        32 => 0x44,                              // D
+        33 => 0x46,                              // F
+        34 => 0x47,                              // G
+        35 => 0x48,                              // H
+        36 => 0x4A,                              // J
+        37 => 0x4B,                              // K
+        38 => 0x4C,                              // L
+        39 => 0xBA,                              // KEY_SEMICOLON
+        40 => 0xDE,                              // KEY_APOSTROPHE
+        41 => 0xC0,                              // KEY_GRAVE
+        42 => VK_LSHIFT,                         // KEY_LEFTSHIFT
+        43 => 0xDC,                              // KEY_BACKSLASH
+        44 => 0x5A,                              // Z
+        45 => 0x58,                              // X
+        46 => 0x43,                              // C
+        47 => 0x56,                              // V
+        48 => 0x42,                              // B
+        49 => 0x4E,                              // N
+        50 => 0x4D,                              // M
+        51 => 0xBC,                              // KEY_COMMA
+        52 => 0xBE,                              // KEY_DOT
+        53 => 0xBF,                              // KEY_SLASH
+        54 => VK_RSHIFT,                         // KEY_RIGHTSHIFT
+        55 => 0x6A,                              // KEY_KPASTERISK
+        56 => VK_LMENU,                          // KEY_LEFTALT
+        57 => 0x20,                              // KEY_SPACE
+        58 => 0x14,                              // KEY_CAPSLOCK
+        59..=68 => 0x70 + (code as u32 - 59),    // KEY_F1 ~ KEY_F10
+        69 => 0x90,                              // KEY_NUMLOCK
+        70 => 0x91,                              // KEY_SCROLLLOCK
+        71 => 0x67,                              // KEY_KP7
+        72 => 0x68,                              // KEY_KP8
+        73 => 0x69,                              // KEY_KP9
+        74 => 0x6D,                              // KEY_KPMINUS
+        75 => 0x64,                              // KEY_KP4
+        76 => 0x65,                              // KEY_KP5
+        77 => 0x66,                              // KEY_KP6
+        78 => 0x6B,                              // KEY_KPPLUS
+        79 => 0x61,                              // KEY_KP1
+        80 => 0x62,                              // KEY_KP2
+        81 => 0x63,                              // KEY_KP3
+        82 => 0x60,                              // KEY_KP0
+        83 => 0x6E,                              // KEY_KPDOT
+        86 => 0xE2,                              // KEY_102ND
+        87 => 0x7A,                              // KEY_F11
+        88 => 0x7B,                              // KEY_F12
+        97 => VK_RCONTROL,                       // KEY_RIGHTCTRL
+        98 => 0x6F,                              // KEY_KPSLASH
+        99 => 0x2C,                              // KEY_SYSRQ
+        100 => VK_RMENU,                         // KEY_RIGHTALT
+        102 => 0x24,                             // KEY_HOME
+        103 => 0x26,                             // KEY_UP
+        104 => 0x21,                             // KEY_PAGEUP
+        105 => 0x25,                             // KEY_LEFT
+        106 => 0x27,                             // KEY_RIGHT
+        107 => 0x23,                             // KEY_END
+        108 => 0x28,                             // KEY_DOWN
+        109 => 0x22,                             // KEY_PAGEDOWN
+        110 => 0x2D,                             // KEY_INSERT
+        111 => 0x2E,                             // KEY_DELETE
+        119 => 0x13,                             // KEY_PAUSE
+        125 => 0x5B,                             // KEY_LEFTMETA
+        126 => 0x5C,                             // KEY_RIGHTMETA
+        127 => 0x5D,                             // KEY_COMPOSE
+        183..=194 => 0x7C + (code as u32 - 183), // KEY_F13 ~ KEY_F24
+        _ => return None,
+    };
+    Some(vk)
+}
+
+/// 修饰键虚拟键码对应的 evdev 键码，与 vk_from_evdev 相反
+fn evdev_from_modifier(vk: u32) -> Option<u16> {
+    match vk {
+        VK_LCONTROL => Some(29),
+        VK_RCONTROL => Some(97),
+        VK_LSHIFT => Some(42),
+        VK_RSHIFT => Some(54),
+        VK_LMENU => Some(56),
+        VK_RMENU => Some(100),
+        _ => None,
+    }
+}
+
+fn raw_event(code: u16, is_down: bool) -> Option<RawKeyEvent> {
+    let vk = vk_from_evdev(code)?;
+    Some(if is_down {
+        RawKeyEvent::KeyDown(vk)
+    } else {
+        RawKeyEvent::KeyUp(vk)
+    })
+}
+
+fn is_running(running: &Arc<Mutex<bool>>) -> bool {
+    *running.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 是否为模拟按键的设备：XTest 设备（enigo、xdotool 经由它注入）或本程序的 uinput 虚拟键盘
+fn is_injecting_device(name: &str) -> bool {
+    name.contains("XTEST") || name == VIRTUAL_KEYBOARD_NAME
+}
+
+/// 等待文件描述符可读，超时返回 false
+fn wait_readable(fd: RawFd, timeout_ms: i32) -> io::Result<bool> {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    match unsafe { libc::poll(&mut pollfd, 1, timeout_ms) } {
+        -1 => {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                Ok(false)
+            } else {
+                Err(err)
+            }
+        }
+        0 => Ok(false),
+        _ => Ok(true),
+    }
+}
+
+mod x11 {
+    use super::*;
+    use std::collections::HashSet;
+    use std::os::unix::io::AsRawFd;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xinput::{self, ConnectionExt as _};
+    use x11rb::protocol::xproto::ConnectionExt as _;
+    use x11rb::protocol::Event;
+
+    /// 通过 QueryKeymap 查询按键当前是否按下
+    pub(super) fn is_key_down(code: u16) -> Result<bool, String> {
+        let (conn, _) = x11rb::connect(None).map_err(|e| e.to_string())?;
+        let keys = conn
+            .query_keymap()
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?
+            .keys;
+        let keycode = code as usize + X11_KEYCODE_OFFSET as usize;
+        Ok(keys[keycode / 8] & (1 << (keycode % 8)) != 0)
+    }
+
+    /// 查询模拟按键设备的 id，设备增减时需要重新查询
+    fn injecting_devices(conn: &impl Connection) -> Result<HashSet<xinput::DeviceId>, String> {
+        let reply = conn
+            .xinput_xi_query_device(xinput::Device::ALL)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        Ok(reply
+            .infos
+            .iter()
+            .filter(|info| is_injecting_device(&String::from_utf8_lossy(&info.name)))
+            .map(|info| info.deviceid)
+            .collect())
+    }
+
+    pub(super) fn start(
+        raw_tx: mpsc::Sender<RawKeyEvent>,
+        running: Arc<Mutex<bool>>,
+    ) -> Result<(), String> {
+        let (conn, screen_num) = x11rb::connect(None).map_err(|e| e.to_string())?;
+        let root = conn.setup().roots[screen_num].root;
+
+        // XI 2.1 起原始事件带有 sourceid，可据此区分物理键盘和 XTest 设备
+        let version = conn
+            .xinput_xi_query_version(2, 2)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| format!("XInput2 not available: {}", e))?;
+        log::info!(
+            "[hotkey] XInput {}.{}",
+            version.major_version,
+            version.minor_version
+        );
+
+        // 原始按键事件不受焦点窗口影响，也不会抢占按键
+        let mask = xinput::XIEventMask::RAW_KEY_PRESS | xinput::XIEventMask::RAW_KEY_RELEASE;
+        conn.xinput_xi_select_events(
+            root,
+            &[xinput::EventMask {
+                deviceid: xinput::Device::ALL_MASTER.into(),
+                mask: vec![mask],
+            }],
+        )
+        .map_err(|e| e.to_string())?
+        .check()
+        .map_err(|e| e.to_string())?;
+        conn.xinput_xi_select_events(
+            root,
+            &[xinput::EventMask {
+                deviceid: xinput::Device::ALL.into(),
+                mask: vec![xinput::XIEventMask::HIERARCHY],
+            }],
+        )
+        .map_err(|e| e.to_string())?
+        .check()
+        .map_err(|e| e.to_string())?;
+        let mut ignored = injecting_devices(&conn)?;
+
+        thread::spawn(move || {
+            log::info!("[hotkey] X11 raw key listener started");
+            let fd = conn.stream().as_raw_fd();
+            while is_running(&running) {
+                let event = match conn.poll_for_event() {
+                    Ok(Some(event)) => event,
+                    Ok(None) => {
+                        // 定时醒来检查停止标志，stop() 后线程能及时退出
+                        if let Err(e) = wait_readable(fd, POLL_TIMEOUT_MS) {
+                            log::error!("[hotkey] X11 connection poll failed: {}", e);
+                            break;
+                        }
+                        continue;
+                    }
+                    Err(e) => {
+                        log::error!("[hotkey] X11 connection error: {}", e);
+                        break;
+                    }
+                };
+                let (keycode, is_down, sourceid) = match event {
+                    Event::XinputRawKeyPress(ev) => (ev.detail, true, ev.sourceid),
+                    Event::XinputRawKeyRelease(ev) => (ev.detail, false, ev.sourceid),
+                    Event::XinputHierarchy(_) => {
+                        // 键盘插拔或 uinput 虚拟键盘创建后刷新忽略列表
+                        match injecting_devices(&conn) {
+                            Ok(devices) => ignored = devices,
+                            Err(e) => log::warn!("[hotkey] XInput device query failed: {}", e),
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if ignored.contains(&sourceid) {
+                    continue;
+                }
+                let Some(code) = keycode
+                    .checked_sub(X11_KEYCODE_OFFSET)
+                    .and_then(|c| u16::try_from(c).ok())
+                else {
+                    continue;
+                };
+                if let Some(raw) = raw_event(code, is_down) {
+                    if raw_tx.send(raw).is_err() {
+                        break;
+                    }
+                }
+            }
+            log::info!("[hotkey] X11 raw key listener exited");
+        });
+        Ok(())
+    }
+}
+
+mod evdev_backend {
+    use super::*;
+    use evdev::{Device, EventType, Key};
+    use std::os::unix::io::AsRawFd;
+
+    /// 同时具备字母键和回车键的设备视为键盘，本程序的虚拟键盘除外
+    fn is_keyboard(device: &Device) -> bool {
+        device
+            .supported_keys()
+            .is_some_and(|keys| keys.contains(Key::KEY_A) && keys.contains(Key::KEY_ENTER))
+            && !device.name().is_some_and(is_injecting_device)
+    }
+
+    /// 通过 EVIOCGKEY 查询任一键盘上该键当前是否按下
+    pub(super) fn is_key_down(code: u16) -> Result<bool, String> {
+        let key = Key::new(code);
+        Ok(evdev::enumerate()
+            .filter(|(_, device)| is_keyboard(device))
+            .any(|(_, device)| device.get_key_state().is_ok_and(|keys| keys.contains(key))))
+    }
+
+    fn set_nonblocking(fd: RawFd) -> io::Result<()> {
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+        if flags == -1 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } == -1
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub(super) fn start(
+        raw_tx: mpsc::Sender<RawKeyEvent>,
+        running: Arc<Mutex<bool>>,
+    ) -> Result<(), String> {
+        let keyboards: Vec<(std::path::PathBuf, Device)> = evdev::enumerate()
+            .filter(|(_, device)| is_keyboard(device))
+            .collect();
+        if keyboards.is_empty() {
+            return Err(
+                "No readable keyboard device in /dev/input, add the user to the input group"
+                    .to_string(),
+            );
+        }
+
+        for (path, mut device) in keyboards {
+            log::info!(
+                "[hotkey] evdev keyboard: {} ({})",
+                path.display(),
+                device.name().unwrap_or("unknown")
+            );
+            let fd = device.as_raw_fd();
+            if let Err(e) = set_nonblocking(fd) {
+                log::warn!("[hotkey] evdev {} setup failed: {}", path.display(), e);
+                continue;
+            }
+            let raw_tx = raw_tx.clone();
+            let running = running.clone();
+            thread::spawn(move || {
+                while is_running(&running) {
+                    // 定时醒来检查停止标志，stop() 后线程能及时退出
+                    match wait_readable(fd, POLL_TIMEOUT_MS) {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(e) => {
+                            log::warn!("[hotkey] evdev {} poll failed: {}", path.display(), e);
+                            break;
+                        }
+                    }
+                    let events = match device.fetch_events() {
+                        Ok(events) => events,
+                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                        Err(e) => {
+                            log::warn!("[hotkey] evdev {} read failed: {}", path.display(), e);
+                            break;
+                        }
+                    };
+                    for event in events {
+                        // value: 0 = 松开, 1 = 按下, 2 = 自动重复（忽略）
+                        if event.event_type() != EventType::KEY || event.value() == 2 {
+                            continue;
+                        }
+                        if let Some(raw) = raw_event(event.code(), event.value() == 1) {
+                            if raw_tx.send(raw).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evdev_from_modifier_round_trips() {
+        for vk in [
+            VK_LCONTROL,
+            VK_RCONTROL,
+            VK_LSHIFT,
+            VK_RSHIFT,
+            VK_LMENU,
+            VK_RMENU,
+        ] {
+            let code = evdev_from_modifier(vk).unwrap();
+            assert_eq!(vk_from_evdev(code), Some(vk));
+        }
+        assert_eq!(evdev_from_modifier(0x41), None);
+    }
+
+    #[test]
+    fn test_vk_from_evdev_modifiers_keep_sides() {
+        assert_eq!(vk_from_evdev(29), Some(VK_LCONTROL));
+        assert_eq!(vk_from_evdev(97), Some(VK_RCONTROL));
+        assert_eq!(vk_from_evdev(42), Some(VK_LSHIFT));
+        assert_eq!(vk_from_evdev(54), Some(VK_RSHIFT));
+        assert_eq!(vk_from_evdev(56), Some(VK_LMENU));
+        assert_eq!(vk_from_evdev(100), Some(VK_RMENU));
+    }
+
+    #[test]
+    fn test_vk_from_evdev_keys() {
+        assert_eq!(vk_from_evdev(30), Some(0x41)); // A
+        assert_eq!(vk_from_evdev(50), Some(0x4D)); // M
+        assert_eq!(vk_from_evdev(2), Some(0x31)); // 1
+        assert_eq!(vk_from_evdev(11), Some(0x30)); // 0
+        assert_eq!(vk_from_evdev(57), Some(0x20)); // Space
+        assert_eq!(vk_from_evdev(59), Some(0x70)); // F1
+        assert_eq!(vk_from_evdev(88), Some(0x7B)); // F12
+        assert_eq!(vk_from_evdev(194), Some(0x87)); // F24
+        assert_eq!(vk_from_evdev(1), Some(0x1B)); // Esc
+        assert_eq!(vk_from_evdev(0), None);
+        assert_eq!(vk_from_evdev(240), None);
+    }
+
+    #[test]
+    fn test_raw_event_direction() {
+        assert_eq!(raw_event(97, true), Some(RawKeyEvent::KeyDown(VK_RCONTROL)));
+        assert_eq!(raw_event(97, false), Some(RawKeyEvent::KeyUp(VK_RCONTROL)));
+        assert_eq!(raw_event(0, true), None);
+    }
+
+    #[test]
+    fn test_is_injecting_device() {
+        assert!(is_injecting_device("Virtual core XTEST keyboard"));
+        assert!(is_injecting_device(VIRTUAL_KEYBOARD_NAME));
+        assert!(!is_injecting_device("AT Translated Set 2 keyboard"));
+        assert!(!is_injecting_device("Virtual core keyboard"));
+    }
+
+    #[test]
+    fn test_prefers_evdev() {
+        // X11 会话
+        assert!(!prefers_evdev(Some("x11"), None, Some(":0")));
+        // Wayland 会话（即使有 XWayland 的 DISPLAY）
+        assert!(prefers_evdev(
+            Some("wayland"),
+            Some("wayland-0"),
+            Some(":0")
+        ));
+        assert!(prefers_evdev(None, Some("wayland-0"), Some(":0")));
+        // 没有图形会话（如 tty）
+        assert!(prefers_evdev(None, None, None));
+        assert!(prefers_evdev(Some("tty"), None, Some("")));
+    }
+}
@@ -1,3 +1,8 @@
+pub mod key_state;
+#[cfg(target_os = "linux")]
+pub mod linux_hook;
+pub mod push_to_talk;
 pub mod win_hook;
 
+pub use push_to_talk::PushToTalk;
 pub use win_hook::{HotkeyEvent, HotkeyManager};
@@ -0,0 +1,89 @@
+use std::time::{Duration, Instant};
+
+/// 按住说话状态机：决定松开快捷键时是否停止录音
+///
+/// - 按下快捷键开始了新录音，按住超过阈值后松开 => 停止录音
+/// - 按住时间不足阈值（点按）=> 保持录音，退化为切换模式，再按一次停止
+/// - 按下快捷键是为了停止录音 => 松开时不做任何事
+#[derive(Debug)]
+pub struct PushToTalk {
+    threshold: Duration,
+    /// 本次按下开始录音的时间，None 表示当前没有按住中的录音
+    pressed_at: Option<Instant>,
+}
+
+impl PushToTalk {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            pressed_at: None,
+        }
+    }
+
+    /// 按住说话快捷键按下；started 表示这次按下开始了新录音
+    pub fn on_press(&mut self, now: Instant, started: bool) {
+        self.pressed_at = if started { Some(now) } else { None };
+    }
+
+    /// 快捷键松开，返回是否应停止录音
+    pub fn on_release(&mut self, now: Instant) -> bool {
+        match self.pressed_at.take() {
+            Some(pressed_at) => now.duration_since(pressed_at) >= self.threshold,
+            None => false,
+        }
+    }
+
+    /// 录音被取消或由其他途径结束时清除按住状态
+    pub fn reset(&mut self) {
+        self.pressed_at = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: Duration = Duration::from_millis(800);
+
+    #[test]
+    fn test_hold_then_release_stops() {
+        let mut ptt = PushToTalk::new(THRESHOLD);
+        let t0 = Instant::now();
+        ptt.on_press(t0, true);
+        assert!(ptt.on_release(t0 + Duration::from_millis(1500)));
+        // 同一次按住只触发一次停止
+        assert!(!ptt.on_release(t0 + Duration::from_millis(1600)));
+    }
+
+    #[test]
+    fn test_tap_falls_back_to_toggle() {
+        let mut ptt = PushToTalk::new(THRESHOLD);
+        let t0 = Instant::now();
+        // 点按：开始录音，松开不停止
+        ptt.on_press(t0, true);
+        assert!(!ptt.on_release(t0 + Duration::from_millis(200)));
+        // 再次按下用于停止录音，松开时不再处理
+        let t1 = t0 + Duration::from_secs(5);
+        ptt.on_press(t1, false);
+        assert!(!ptt.on_release(t1 + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_threshold_boundary_counts_as_hold() {
+        let mut ptt = PushToTalk::new(THRESHOLD);
+        let t0 = Instant::now();
+        ptt.on_press(t0, true);
+        assert!(ptt.on_release(t0 + THRESHOLD));
+    }
+
+    #[test]
+    fn test_release_without_press_and_reset() {
+        let mut ptt = PushToTalk::new(THRESHOLD);
+        let t0 = Instant::now();
+        assert!(!ptt.on_release(t0));
+
+        ptt.on_press(t0, true);
+        ptt.reset();
+        assert!(!ptt.on_release(t0 + Duration::from_secs(2)));
+    }
+}
@@ -1,22 +1,28 @@
-use crate::config::{HotkeyBinding, HotkeyConfig, Modifier};
+use super::key_state::RawKeyEvent;
+use crate::config::{HotkeyAction, HotkeyConfig, TranslationTarget};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
+
+#[cfg(any(windows, target_os = "linux"))]
+use super::key_state::KeyState;
+#[cfg(windows)]
 use std::thread;
+#[cfg(any(windows, target_os = "linux"))]
 use std::time::Instant;
 
 #[cfg(windows)]
-use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::atomic::AtomicPtr;
+#[cfg(any(windows, target_os = "linux"))]
+use std::sync::atomic::Ordering;
 
 #[cfg(windows)]
 use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
 #[cfg(windows)]
 use windows::Win32::System::Threading::GetThreadId;
 #[cfg(windows)]
-use windows::Win32::UI::Input::KeyboardAndMouse::{
-    GetAsyncKeyState, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_RCONTROL, VK_RMENU, VK_RSHIFT,
-};
+use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
 #[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::{
     CallNextHookEx, DispatchMessageW, GetMessageW, PostThreadMessageW, SetWindowsHookExW,
@@ -35,28 +41,45 @@ pub enum HotkeyEvent {
     ToggleRecording,
     /// 取消录音
     CancelRecording,
+    /// 切换选中文本改写录音
+    ToggleTransform,
+    /// 复制选中文本并开始录制改写指令
+    StartTransform,
+    /// 切换翻译录音
+    ToggleTranslate(TranslationTarget),
+    /// 开始录制翻译（携带目标语言）
+    StartTranslate(TranslationTarget),
+    /// 切换命名快捷键方案的录音（携带方案 id）
+    ToggleProfile(String),
+    /// 按命名快捷键方案开始录音
+    StartProfile(String),
+    /// 按住说话快捷键按下，紧随其后发送对应的 Toggle 事件
+    HoldPressed,
+    /// 按住说话快捷键松开
+    HoldReleased,
+    /// 确认输出待确认的识别结果
+    AcceptOutput,
+    /// 撤销上一次听写输出
+    UndoOutput,
 }
 
-/// 普通键超时时间（秒）：超过此时间未收到 KeyUp 则自动清除
-const KEY_EXPIRE_SECS: u64 = 5;
-
-/// 判断虚拟键码是否为已知的普通键（字母、数字、标点、功能键等）
-/// 过滤掉未知/特殊键码，防止因 KeyUp 丢失导致 pressed_keys 永久卡住
-fn is_known_key(vk: u32) -> bool {
-    matches!(vk,
-        0x08..=0x09 |       // Backspace, Tab
-        0x0D |              // Enter
-        0x1B |              // Escape
-        0x20 |              // Space
-        0x21..=0x2E |       // PageUp ~ Delete
-        0x30..=0x39 |       // 0-9
-        0x41..=0x5A |       // A-Z
-        0x60..=0x6F |       // Numpad 0-9, *, +, -, ., /
-        0x70..=0x87 |       // F1-F24
-        0xBA..=0xC0 |       // ;=,-./`
-        0xDB..=0xDF |       // [\]'
-        0xE2                // OEM_102
-    )
+impl HotkeyEvent {
+    /// 快捷键配置对应的触发事件；翻译缺少目标语言时退化为普通听写
+    pub(crate) fn for_config(config: &HotkeyConfig) -> Self {
+        match config.action {
+            HotkeyAction::AcceptOutput => return HotkeyEvent::AcceptOutput,
+            HotkeyAction::UndoOutput => return HotkeyEvent::UndoOutput,
+            _ => {}
+        }
+        if let Some(id) = &config.profile_id {
+            return HotkeyEvent::ToggleProfile(id.clone());
+        }
+        match (config.action, &config.translation) {
+            (HotkeyAction::TransformSelection, _) => HotkeyEvent::ToggleTransform,
+            (HotkeyAction::Translate, Some(target)) => HotkeyEvent::ToggleTranslate(target.clone()),
+            _ => HotkeyEvent::ToggleRecording,
+        }
+    }
 }
 
 #[cfg(windows)]
@@ -64,143 +87,63 @@ fn is_physical_key_down(vk: u32) -> bool {
     (unsafe { GetAsyncKeyState(vk as i32) } as u16 & 0x8000) != 0
 }
 
-/// 按键状态追踪
-#[derive(Debug)]
-struct KeyState {
-    pressed_modifiers: HashSet<u32>,
-    pressed_keys: HashSet<u32>,
-    /// 修饰键按下时间戳，用于 KeyUp 丢失时恢复热键状态
-    modifier_press_times: HashMap<u32, Instant>,
-    /// 普通键按下时间戳，用于超时清理
-    key_press_times: HashMap<u32, Instant>,
-}
-
-impl Default for KeyState {
-    fn default() -> Self {
-        Self {
-            pressed_modifiers: HashSet::new(),
-            pressed_keys: HashSet::new(),
-            modifier_press_times: HashMap::new(),
-            key_press_times: HashMap::new(),
-        }
-    }
+/// Linux 向正在使用的监听后端查询真实按键状态（XQueryKeymap / EVIOCGKEY）
+#[cfg(target_os = "linux")]
+fn is_physical_key_down(vk: u32) -> bool {
+    super::linux_hook::is_key_down(vk)
 }
 
-impl KeyState {
-    fn modifier_from_vk(vk: u32) -> Option<Modifier> {
-        match vk {
-            vk if vk == VK_LCONTROL.0 as u32 => Some(Modifier::LeftCtrl),
-            vk if vk == VK_RCONTROL.0 as u32 => Some(Modifier::RightCtrl),
-            vk if vk == VK_LMENU.0 as u32 => Some(Modifier::LeftAlt),
-            vk if vk == VK_RMENU.0 as u32 => Some(Modifier::RightAlt),
-            vk if vk == VK_LSHIFT.0 as u32 => Some(Modifier::LeftShift),
-            vk if vk == VK_RSHIFT.0 as u32 => Some(Modifier::RightShift),
-            _ => None,
-        }
-    }
-
-    #[allow(dead_code)]
-    pub fn vk_from_modifier(modifier: &Modifier) -> u32 {
-        match modifier {
-            Modifier::LeftCtrl => VK_LCONTROL.0 as u32,
-            Modifier::RightCtrl => VK_RCONTROL.0 as u32,
-            Modifier::LeftAlt => VK_LMENU.0 as u32,
-            Modifier::RightAlt => VK_RMENU.0 as u32,
-            Modifier::LeftShift => VK_LSHIFT.0 as u32,
-            Modifier::RightShift => VK_RSHIFT.0 as u32,
-        }
-    }
-
-    fn is_modifier(vk: u32) -> bool {
-        Self::modifier_from_vk(vk).is_some()
-    }
-
-    /// 清除超时按键（KeyUp 丢失保护）
-    fn expire_stale_keys(&mut self) {
-        let now = Instant::now();
-        let expired_modifiers: Vec<u32> = self
-            .modifier_press_times
-            .iter()
-            .filter(|(&vk, t)| {
-                now.duration_since(**t).as_secs() >= KEY_EXPIRE_SECS && !is_physical_key_down(vk)
-            })
-            .map(|(&vk, _)| vk)
-            .collect();
-        let refreshed_modifiers: Vec<u32> = self
-            .modifier_press_times
-            .iter()
-            .filter(|(&vk, t)| {
-                now.duration_since(**t).as_secs() >= KEY_EXPIRE_SECS && is_physical_key_down(vk)
-            })
-            .map(|(&vk, _)| vk)
-            .collect();
-        for vk in &refreshed_modifiers {
-            self.modifier_press_times.insert(*vk, now);
-        }
-        for vk in &expired_modifiers {
-            self.pressed_modifiers.remove(vk);
-            self.modifier_press_times.remove(vk);
-        }
-        if !expired_modifiers.is_empty() {
-            log::debug!(
-                "[hotkey] expired stale modifiers: {:?}, remaining pressed_modifiers={:?}",
-                expired_modifiers,
-                self.pressed_modifiers
-            );
-        }
-
-        let expired: Vec<u32> = self
-            .key_press_times
-            .iter()
-            .filter(|(_, t)| now.duration_since(**t).as_secs() >= KEY_EXPIRE_SECS)
-            .map(|(&vk, _)| vk)
-            .collect();
-        for vk in &expired {
-            self.pressed_keys.remove(vk);
-            self.key_press_times.remove(vk);
-        }
-        if !expired.is_empty() {
-            log::debug!(
-                "[hotkey] expired stale keys: {:?}, remaining pressed_keys={:?}",
-                expired,
-                self.pressed_keys
-            );
-        }
-    }
-
-    fn active_modifiers(&self) -> HashSet<Modifier> {
-        self.pressed_modifiers
-            .iter()
-            .filter_map(|&vk| Self::modifier_from_vk(vk))
-            .collect()
-    }
-
-    fn matches_binding(&self, binding: &HotkeyBinding) -> bool {
-        let active = self.active_modifiers();
-        let required: HashSet<Modifier> = binding.modifiers.iter().cloned().collect();
-
-        if active != required {
-            return false;
-        }
-
-        if binding.key == 0 {
-            // 仅修饰键模式：不能有其他普通键同时按下
-            self.pressed_keys.is_empty()
-        } else {
-            self.pressed_keys.contains(&binding.key)
+/// 按键状态处理循环：消费平台钩子产生的原始按键事件，匹配快捷键后发出 HotkeyEvent
+#[cfg(any(windows, target_os = "linux"))]
+pub(super) fn process_raw_events(
+    raw_rx: mpsc::Receiver<RawKeyEvent>,
+    configs: Arc<Mutex<Vec<HotkeyConfig>>>,
+    running: Arc<Mutex<bool>>,
+    modifiers_held: Arc<AtomicBool>,
+    event_tx: mpsc::Sender<HotkeyEvent>,
+) {
+    let mut key_state = KeyState::default();
+
+    log::info!(
+        "[hotkey] state processing loop started, configs count: {}",
+        configs.lock().map(|c| c.len()).unwrap_or(0)
+    );
+
+    while *running.lock().unwrap_or_else(|e| e.into_inner()) {
+        match raw_rx.recv_timeout(std::time::Duration::from_millis(100)) {
+            Ok(raw_event) => {
+                let configs = configs.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                let events = key_state.handle_event(
+                    raw_event,
+                    &configs,
+                    Instant::now(),
+                    is_physical_key_down,
+                );
+                for event in events {
+                    let _ = event_tx.send(event);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // 每次超时（100ms）时清理过期的 stale keys
+                key_state.expire_stale_keys(Instant::now(), is_physical_key_down);
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
         }
+        modifiers_held.store(!key_state.pressed_modifiers.is_empty(), Ordering::SeqCst);
     }
+    modifiers_held.store(false, Ordering::SeqCst);
 }
 
 pub struct HotkeyManager {
-    event_rx: Option<mpsc::Receiver<HotkeyEvent>>,
-    configs: Arc<Mutex<Vec<HotkeyConfig>>>,
-    running: Arc<Mutex<bool>>,
+    pub(super) event_rx: Option<mpsc::Receiver<HotkeyEvent>>,
+    pub(super) configs: Arc<Mutex<Vec<HotkeyConfig>>>,
+    pub(super) running: Arc<Mutex<bool>>,
+    /// 是否有修饰键仍被按住，模拟快捷键前据此等待用户松开
+    pub(super) modifiers_held: Arc<AtomicBool>,
     #[cfg(windows)]
     hook_thread_id: Arc<Mutex<Option<u32>>>,
     /// 注入通道：前端 JS 层通过此通道补偿 WebView2 焦点时钩子收不到的按键事件
-    #[cfg(windows)]
-    inject_tx: Arc<Mutex<Option<mpsc::Sender<RawKeyEvent>>>>,
+    pub(super) inject_tx: Arc<Mutex<Option<mpsc::Sender<RawKeyEvent>>>>,
 }
 
 #[cfg(windows)]
@@ -211,22 +154,15 @@ struct HookCallbackData {
 #[cfg(windows)]
 static GLOBAL_HOOK_DATA: AtomicPtr<HookCallbackData> = AtomicPtr::new(std::ptr::null_mut());
 
-#[cfg(windows)]
-#[derive(Debug)]
-enum RawKeyEvent {
-    KeyDown(u32),
-    KeyUp(u32),
-}
-
 impl HotkeyManager {
     pub fn new(configs: Vec<HotkeyConfig>) -> Self {
         Self {
             event_rx: None,
             configs: Arc::new(Mutex::new(configs)),
             running: Arc::new(Mutex::new(false)),
+            modifiers_held: Arc::new(AtomicBool::new(false)),
             #[cfg(windows)]
             hook_thread_id: Arc::new(Mutex::new(None)),
-            #[cfg(windows)]
             inject_tx: Arc::new(Mutex::new(None)),
         }
     }
@@ -248,6 +184,7 @@ impl HotkeyManager {
 
         let configs = self.configs.clone();
         let running = self.running.clone();
+        let modifiers_held = self.modifiers_held.clone();
         let hook_thread_id = self.hook_thread_id.clone();
         let inject_tx = self.inject_tx.clone();
 
@@ -309,83 +246,7 @@ impl HotkeyManager {
             });
 
             // 状态处理线程
-            let mut key_state = KeyState::default();
-
-            log::info!(
-                "[hotkey] state processing loop started, configs count: {}",
-                configs.lock().map(|c| c.len()).unwrap_or(0)
-            );
-
-            while *running.lock().unwrap_or_else(|e| e.into_inner()) {
-                match raw_rx.recv_timeout(std::time::Duration::from_millis(100)) {
-                    Ok(raw_event) => {
-                        let configs = configs.lock().unwrap_or_else(|e| e.into_inner()).clone();
-
-                        match raw_event {
-                            RawKeyEvent::KeyDown(vk) => {
-                                // 过滤未知键码：非修饰键且不在已知键码范围内，直接忽略
-                                if !KeyState::is_modifier(vk) && !is_known_key(vk) {
-                                    log::debug!("[hotkey] ignoring unknown vk=0x{:X}", vk);
-                                    continue;
-                                }
-
-                                // 每次 KeyDown 时顺便清理超时的 stale keys
-                                key_state.expire_stale_keys();
-
-                                // 去重：忽略 Windows 按键重复（键已在 pressed 集合中）
-                                let is_repeat = if KeyState::is_modifier(vk) {
-                                    let inserted = key_state.pressed_modifiers.insert(vk);
-                                    if inserted {
-                                        key_state.modifier_press_times.insert(vk, Instant::now());
-                                    }
-                                    !inserted
-                                } else {
-                                    let inserted = key_state.pressed_keys.insert(vk);
-                                    if inserted {
-                                        key_state.key_press_times.insert(vk, Instant::now());
-                                    }
-                                    !inserted
-                                };
-                                if is_repeat {
-                                    continue;
-                                }
-
-                                log::debug!("[hotkey] KeyDown vk=0x{:X}, is_modifier={}, active_modifiers={:?}, pressed_keys={:?}",
-                                    vk, KeyState::is_modifier(vk), key_state.active_modifiers(), key_state.pressed_keys);
-
-                                // 检查 ESC 键取消（始终发送，由前端判断是否在录音）
-                                if vk == 0x1B {
-                                    // VK_ESCAPE
-                                    log::info!("[hotkey] ESC cancel");
-                                    let _ = event_tx.send(HotkeyEvent::CancelRecording);
-                                    continue;
-                                }
-
-                                for config in &configs {
-                                    if key_state.matches_binding(&config.binding) {
-                                        log::info!("[hotkey] Toggle => sending ToggleRecording");
-                                        let _ = event_tx.send(HotkeyEvent::ToggleRecording);
-                                    }
-                                }
-                            }
-                            RawKeyEvent::KeyUp(vk) => {
-                                if KeyState::is_modifier(vk) {
-                                    key_state.pressed_modifiers.remove(&vk);
-                                    key_state.modifier_press_times.remove(&vk);
-                                } else {
-                                    key_state.pressed_keys.remove(&vk);
-                                    key_state.key_press_times.remove(&vk);
-                                }
-                            }
-                        }
-                    }
-                    Err(mpsc::RecvTimeoutError::Timeout) => {
-                        // 每次超时（100ms）时清理过期的 stale keys
-                        key_state.expire_stale_keys();
-                    }
-                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
-                }
-            }
+            process_raw_events(raw_rx, configs, running, modifiers_held, event_tx);
 
             // 优雅退出：向钩子线程发送 WM_QUIT 使 GetMessageW 返回 false
             if let Ok(id) = hook_thread_id.lock() {
@@ -401,13 +262,14 @@ impl HotkeyManager {
         Ok(())
     }
 
-    #[cfg(not(windows))]
+    // Linux 的 start 在 linux_hook.rs 中实现
+
+    #[cfg(not(any(windows, target_os = "linux")))]
     pub fn start(&mut self) -> Result<(), String> {
-        Err("Hotkey manager is only supported on Windows".to_string())
+        Err("Hotkey manager is only supported on Windows and Linux".to_string())
     }
 
     /// 从外部注入按键事件（用于 WebView2 焦点时 JS 层补偿）
-    #[cfg(windows)]
     pub fn inject_key_event(&self, vk: u32, is_down: bool) {
         if let Ok(tx) = self.inject_tx.lock() {
             if let Some(tx) = tx.as_ref() {
@@ -440,6 +302,11 @@ impl HotkeyManager {
         self.event_rx.as_ref()?.try_recv().ok()
     }
 
+    /// 修饰键按住状态，由按键状态处理线程实时更新
+    pub fn modifiers_held(&self) -> Arc<AtomicBool> {
+        self.modifiers_held.clone()
+    }
+
     pub fn is_running(&self) -> bool {
         self.running.lock().map(|r| *r).unwrap_or(false)
     }
@@ -478,145 +345,7 @@ unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam:
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_modifier_from_vk() {
-        assert_eq!(
-            KeyState::modifier_from_vk(VK_LCONTROL.0 as u32),
-            Some(Modifier::LeftCtrl)
-        );
-        assert_eq!(
-            KeyState::modifier_from_vk(VK_RCONTROL.0 as u32),
-            Some(Modifier::RightCtrl)
-        );
-        assert_eq!(
-            KeyState::modifier_from_vk(VK_LMENU.0 as u32),
-            Some(Modifier::LeftAlt)
-        );
-        assert_eq!(
-            KeyState::modifier_from_vk(VK_RMENU.0 as u32),
-            Some(Modifier::RightAlt)
-        );
-        assert_eq!(
-            KeyState::modifier_from_vk(VK_LSHIFT.0 as u32),
-            Some(Modifier::LeftShift)
-        );
-        assert_eq!(
-            KeyState::modifier_from_vk(VK_RSHIFT.0 as u32),
-            Some(Modifier::RightShift)
-        );
-        // 非修饰键
-        assert_eq!(KeyState::modifier_from_vk(0x41), None); // 'A'
-    }
-
-    #[test]
-    fn test_vk_from_modifier() {
-        assert_eq!(
-            KeyState::vk_from_modifier(&Modifier::LeftCtrl),
-            VK_LCONTROL.0 as u32
-        );
-        assert_eq!(
-            KeyState::vk_from_modifier(&Modifier::RightCtrl),
-            VK_RCONTROL.0 as u32
-        );
-    }
-
-    #[test]
-    fn test_is_modifier() {
-        assert!(KeyState::is_modifier(VK_LCONTROL.0 as u32));
-        assert!(KeyState::is_modifier(VK_RSHIFT.0 as u32));
-        assert!(!KeyState::is_modifier(0x41)); // 'A'
-        assert!(!KeyState::is_modifier(0x20)); // Space
-    }
-
-    #[test]
-    fn test_key_state_default() {
-        let state = KeyState::default();
-        assert!(state.pressed_modifiers.is_empty());
-        assert!(state.pressed_keys.is_empty());
-        assert!(state.modifier_press_times.is_empty());
-    }
-
-    #[test]
-    fn test_expire_stale_modifier() {
-        let mut state = KeyState::default();
-        let ctrl = VK_LCONTROL.0 as u32;
-        state.pressed_modifiers.insert(ctrl);
-        state.modifier_press_times.insert(
-            ctrl,
-            Instant::now() - std::time::Duration::from_secs(KEY_EXPIRE_SECS + 1),
-        );
-
-        state.expire_stale_keys();
-
-        assert!(state.pressed_modifiers.is_empty());
-        assert!(state.modifier_press_times.is_empty());
-    }
-
-    #[test]
-    fn test_active_modifiers() {
-        let mut state = KeyState::default();
-        state.pressed_modifiers.insert(VK_LCONTROL.0 as u32);
-        state.pressed_modifiers.insert(VK_LSHIFT.0 as u32);
-
-        let active = state.active_modifiers();
-        assert!(active.contains(&Modifier::LeftCtrl));
-        assert!(active.contains(&Modifier::LeftShift));
-        assert!(!active.contains(&Modifier::RightCtrl));
-        assert_eq!(active.len(), 2);
-    }
-
-    #[test]
-    fn test_matches_binding_modifier_only() {
-        let mut state = KeyState::default();
-        state.pressed_modifiers.insert(VK_RCONTROL.0 as u32);
-
-        // 仅右Ctrl
-        let binding = HotkeyBinding {
-            modifiers: vec![Modifier::RightCtrl],
-            key: 0,
-        };
-        assert!(state.matches_binding(&binding));
-
-        // 左Ctrl 不匹配
-        let binding_left = HotkeyBinding {
-            modifiers: vec![Modifier::LeftCtrl],
-            key: 0,
-        };
-        assert!(!state.matches_binding(&binding_left));
-    }
-
-    #[test]
-    fn test_matches_binding_modifier_plus_key() {
-        let mut state = KeyState::default();
-        state.pressed_modifiers.insert(VK_LCONTROL.0 as u32);
-        state.pressed_keys.insert(0x20); // Space
-
-        let binding = HotkeyBinding {
-            modifiers: vec![Modifier::LeftCtrl],
-            key: 0x20,
-        };
-        assert!(state.matches_binding(&binding));
-
-        // 缺少 Space 键
-        let mut state_no_key = KeyState::default();
-        state_no_key.pressed_modifiers.insert(VK_LCONTROL.0 as u32);
-        assert!(!state_no_key.matches_binding(&binding));
-    }
-
-    #[test]
-    fn test_matches_binding_extra_modifiers_fail() {
-        let mut state = KeyState::default();
-        state.pressed_modifiers.insert(VK_LCONTROL.0 as u32);
-        state.pressed_modifiers.insert(VK_LSHIFT.0 as u32);
-
-        // 只要求左Ctrl，但按了左Ctrl+左Shift，不应匹配
-        let binding = HotkeyBinding {
-            modifiers: vec![Modifier::LeftCtrl],
-            key: 0,
-        };
-        assert!(!state.matches_binding(&binding));
-    }
+    use crate::config::{HotkeyBinding, Modifier, TriggerMode};
 
     #[test]
     fn test_hotkey_manager_new() {
@@ -625,6 +354,10 @@ mod tests {
                 modifiers: vec![Modifier::RightCtrl],
                 key: 0,
             },
+            action: HotkeyAction::ToggleRecording,
+            translation: None,
+            profile_id: None,
+            trigger: TriggerMode::Toggle,
         }];
         let manager = HotkeyManager::new(configs);
         assert!(!manager.is_running());
@@ -638,6 +371,10 @@ mod tests {
                 modifiers: vec![Modifier::LeftCtrl],
                 key: 0x20,
             },
+            action: HotkeyAction::TransformSelection,
+            translation: None,
+            profile_id: None,
+            trigger: TriggerMode::Toggle,
         }];
         manager.update_configs(new_configs.clone());
         let configs = manager.configs.lock().unwrap();
@@ -657,4 +394,52 @@ mod tests {
         let deserialized: HotkeyEvent = serde_json::from_str(&json).unwrap();
         assert!(matches!(deserialized, HotkeyEvent::StartRecording));
     }
+
+    #[test]
+    fn test_hotkey_event_for_config() {
+        let config = |action, translation| HotkeyConfig {
+            binding: HotkeyBinding::default(),
+            action,
+            translation,
+            profile_id: None,
+            trigger: TriggerMode::Toggle,
+        };
+        assert!(matches!(
+            HotkeyEvent::for_config(&config(HotkeyAction::ToggleRecording, None)),
+            HotkeyEvent::ToggleRecording
+        ));
+        assert!(matches!(
+            HotkeyEvent::for_config(&config(HotkeyAction::TransformSelection, None)),
+            HotkeyEvent::ToggleTransform
+        ));
+        let target = TranslationTarget {
+            source_language: None,
+            target_language: "en".to_string(),
+        };
+        match HotkeyEvent::for_config(&config(HotkeyAction::Translate, Some(target.clone()))) {
+            HotkeyEvent::ToggleTranslate(t) => assert_eq!(t, target),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        // 缺少翻译目标时退化为普通听写
+        assert!(matches!(
+            HotkeyEvent::for_config(&config(HotkeyAction::Translate, None)),
+            HotkeyEvent::ToggleRecording
+        ));
+        assert!(matches!(
+            HotkeyEvent::for_config(&config(HotkeyAction::AcceptOutput, None)),
+            HotkeyEvent::AcceptOutput
+        ));
+        assert!(matches!(
+            HotkeyEvent::for_config(&config(HotkeyAction::UndoOutput, None)),
+            HotkeyEvent::UndoOutput
+        ));
+
+        // 方案快捷键只携带 id，动作在开始录音时按方案解析
+        let mut profile_config = config(HotkeyAction::TransformSelection, None);
+        profile_config.profile_id = Some("meeting".to_string());
+        match HotkeyEvent::for_config(&profile_config) {
+            HotkeyEvent::ToggleProfile(id) => assert_eq!(id, "meeting"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
 }
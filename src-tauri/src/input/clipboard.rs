@@ -1,17 +1,118 @@
-use arboard::Clipboard;
+use super::simulate::{KeyAction, SimulateOutput};
+use crate::config::PasteShortcut;
+use arboard::{Clipboard, ImageData};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 static CLIPBOARD_LOCK: Mutex<()> = Mutex::new(());
 
+/// 轮询剪贴板状态的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 剪贴板输出设置（app_settings.clipboard）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClipboardSettings {
+    /// 模拟粘贴后至少等待多久再恢复原剪贴板内容（毫秒）
+    pub restore_delay_ms: u64,
+    /// 等待剪贴板就绪、等待目标应用读完剪贴板的最长时间（毫秒）
+    pub max_wait_ms: u64,
+    /// 临时写入的识别结果不进入剪贴板历史（Windows 剪贴板历史、Klipper 等）
+    pub exclude_from_history: bool,
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        Self {
+            restore_delay_ms: 150,
+            max_wait_ms: 1000,
+            exclude_from_history: false,
+        }
+    }
+}
+
+/// 备份恢复时写回的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BackupFormat {
+    Files,
+    Image,
+    Html,
+    Text,
+    Empty,
+}
+
+/// 剪贴板原有内容的备份，覆盖 arboard 支持读取的全部格式
+#[derive(Default)]
+struct ClipboardBackup {
+    text: Option<String>,
+    html: Option<String>,
+    image: Option<ImageData<'static>>,
+    files: Vec<PathBuf>,
+}
+
+impl ClipboardBackup {
+    fn capture(clipboard: &mut Clipboard) -> Self {
+        Self {
+            text: clipboard.get_text().ok(),
+            html: clipboard.get().html().ok(),
+            image: clipboard.get_image().ok(),
+            files: clipboard.get().file_list().unwrap_or_default(),
+        }
+    }
+
+    /// arboard 每次只能写入一种格式（HTML 可附带纯文本），混合内容恢复信息量最多的部分：
+    /// 文件列表（附带的文字即路径）优先，其次 HTML 及其纯文本，再次图片，最后纯文本
+    fn preferred_format(&self) -> BackupFormat {
+        if !self.files.is_empty() {
+            BackupFormat::Files
+        } else if self.html.is_some() {
+            BackupFormat::Html
+        } else if self.image.is_some() {
+            BackupFormat::Image
+        } else if self.text.is_some() {
+            BackupFormat::Text
+        } else {
+            BackupFormat::Empty
+        }
+    }
+
+    fn restore(self, clipboard: &mut Clipboard) -> Result<(), arboard::Error> {
+        let format = self.preferred_format();
+        log::debug!("[output] restore clipboard as {:?}", format);
+        match format {
+            BackupFormat::Files => clipboard.set().file_list(&self.files),
+            BackupFormat::Image => match self.image {
+                Some(image) => clipboard.set_image(image),
+                None => Ok(()),
+            },
+            BackupFormat::Html => match self.html {
+                Some(html) => clipboard.set_html(html, self.text),
+                None => Ok(()),
+            },
+            BackupFormat::Text => match self.text {
+                Some(text) => clipboard.set_text(text),
+                None => Ok(()),
+            },
+            BackupFormat::Empty => clipboard.clear(),
+        }
+    }
+}
+
 pub struct ClipboardOutput;
 
 impl ClipboardOutput {
     /// 通过剪贴板粘贴文字到当前焦点应用
-    /// 1. 备份原剪贴板内容
-    /// 2. 写入识别结果
-    /// 3. 模拟 Ctrl+V
-    /// 4. 恢复原剪贴板内容
-    pub fn paste(text: &str) -> Result<(), String> {
+    /// 1. 备份原剪贴板全部格式的内容
+    /// 2. 写入识别结果，等待剪贴板就绪
+    /// 3. 模拟粘贴快捷键（默认 Ctrl+V）
+    /// 4. 等待目标应用读取完成后恢复原剪贴板内容（期间剪贴板被其他程序改写则不恢复）
+    pub fn paste(
+        text: &str,
+        settings: &ClipboardSettings,
+        shortcut: PasteShortcut,
+    ) -> Result<(), String> {
         log::info!("[output] clipboard paste start, text_len={}", text.len());
         let _lock = CLIPBOARD_LOCK.lock().map_err(|e| e.to_string())?;
 
@@ -20,30 +121,35 @@ impl ClipboardOutput {
             format!("Failed to access clipboard: {}", e)
         })?;
 
-        // 备份原剪贴板内容
-        let backup = clipboard.get_text().ok();
+        let max_wait = Duration::from_millis(settings.max_wait_ms);
+        let backup = ClipboardBackup::capture(&mut clipboard);
 
         // 写入识别结果
-        clipboard.set_text(text).map_err(|e| {
+        set_transient_text(&mut clipboard, text, settings.exclude_from_history).map_err(|e| {
             log::error!("[output] failed to set clipboard text: {}", e);
             format!("Failed to set clipboard: {}", e)
         })?;
+        let sequence = clipboard_sequence();
 
-        // 短暂延迟确保剪贴板数据就绪
-        std::thread::sleep(std::time::Duration::from_millis(50));
+        // 确认剪贴板数据就绪（Linux 下由后台线程提供数据）
+        wait_until(max_wait, || {
+            clipboard.get_text().ok().as_deref() == Some(text)
+        });
 
-        // 模拟 Ctrl+V
-        simulate_ctrl_v().map_err(|e| {
-            log::error!("[output] simulate Ctrl+V failed: {}", e);
+        simulate_paste(shortcut).map_err(|e| {
+            log::error!("[output] simulate paste {:?} failed: {}", shortcut, e);
             e
         })?;
 
-        // 等待粘贴完成
-        std::thread::sleep(std::time::Duration::from_millis(100));
+        // 等待粘贴完成：先等待固定时长，目标应用仍打开着剪贴板时继续等待
+        std::thread::sleep(Duration::from_millis(settings.restore_delay_ms));
+        wait_until(max_wait, || !clipboard_in_use());
 
         // 恢复原剪贴板内容
-        if let Some(original) = backup {
-            let _ = clipboard.set_text(original);
+        if clipboard_changed(&mut clipboard, sequence, text) {
+            log::info!("[output] clipboard changed by another app, skip restore");
+        } else if let Err(e) = backup.restore(&mut clipboard) {
+            log::warn!("[output] failed to restore clipboard: {}", e);
         }
 
         log::info!("[output] clipboard paste done");
@@ -61,6 +167,51 @@ impl ClipboardOutput {
         Ok(())
     }
 
+    /// 模拟 Ctrl+C 复制当前焦点应用中选中的文本，读取后恢复原剪贴板内容
+    /// 没有选中内容时返回空字符串；modifiers_held 返回 true 时先等待快捷键的修饰键松开
+    pub fn copy_selection(modifiers_held: impl Fn() -> bool) -> Result<String, String> {
+        log::info!("[output] copy selection start");
+        // 仍按住的修饰键会与 Ctrl+C 组合成 Ctrl+Alt+C 等其他快捷键
+        if !SimulateOutput::wait_modifiers_released(modifiers_held) {
+            log::warn!("[output] modifiers still held, copying selection anyway");
+        }
+        let _lock = CLIPBOARD_LOCK.lock().map_err(|e| e.to_string())?;
+
+        let mut clipboard =
+            Clipboard::new().map_err(|e| format!("Failed to access clipboard: {}", e))?;
+
+        let backup = ClipboardBackup::capture(&mut clipboard);
+        // 先清空剪贴板，复制失败（无选中内容）时才能与旧内容区分开
+        let _ = clipboard.clear();
+
+        if let Err(e) = simulate_ctrl_chord('c') {
+            log::error!("[output] simulate Ctrl+C failed: {}", e);
+            if let Err(e) = backup.restore(&mut clipboard) {
+                log::warn!("[output] failed to restore clipboard: {}", e);
+            }
+            return Err(e);
+        }
+
+        // 目标应用处理 Ctrl+C 需要时间，轮询等待剪贴板出现内容
+        let mut selection = String::new();
+        for _ in 0..10 {
+            std::thread::sleep(std::time::Duration::from_millis(30));
+            if let Ok(text) = clipboard.get_text() {
+                if !text.is_empty() {
+                    selection = text;
+                    break;
+                }
+            }
+        }
+
+        if let Err(e) = backup.restore(&mut clipboard) {
+            log::warn!("[output] failed to restore clipboard: {}", e);
+        }
+
+        log::info!("[output] copy selection done, len={}", selection.len());
+        Ok(selection)
+    }
+
     /// 读取剪贴板内容
     pub fn get_clipboard_text() -> Result<String, String> {
         let _lock = CLIPBOARD_LOCK.lock().map_err(|e| e.to_string())?;
@@ -72,29 +223,116 @@ impl ClipboardOutput {
     }
 }
 
+/// 轮询直到条件满足或超时，返回条件是否满足
+fn wait_until(timeout: Duration, mut ready: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if ready() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// 写入临时文本，可选标记为不进入剪贴板历史
+fn set_transient_text(
+    clipboard: &mut Clipboard,
+    text: &str,
+    exclude_from_history: bool,
+) -> Result<(), arboard::Error> {
+    if exclude_from_history {
+        set_text_excluded_from_history(clipboard, text)
+    } else {
+        clipboard.set_text(text)
+    }
+}
+
 #[cfg(windows)]
-fn simulate_ctrl_v() -> Result<(), String> {
-    use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+fn set_text_excluded_from_history(
+    clipboard: &mut Clipboard,
+    text: &str,
+) -> Result<(), arboard::Error> {
+    use arboard::SetExtWindows;
+    clipboard
+        .set()
+        .exclude_from_history()
+        .exclude_from_cloud()
+        .text(text)
+}
+
+#[cfg(target_os = "linux")]
+fn set_text_excluded_from_history(
+    clipboard: &mut Clipboard,
+    text: &str,
+) -> Result<(), arboard::Error> {
+    use arboard::SetExtLinux;
+    clipboard.set().exclude_from_history().text(text)
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn set_text_excluded_from_history(
+    clipboard: &mut Clipboard,
+    text: &str,
+) -> Result<(), arboard::Error> {
+    clipboard.set_text(text)
+}
 
-    let mut enigo =
-        Enigo::new(&Settings::default()).map_err(|e| format!("Failed to create Enigo: {}", e))?;
+/// 剪贴板序列号，每次内容变化时递增；仅 Windows 提供
+#[cfg(windows)]
+fn clipboard_sequence() -> Option<u32> {
+    Some(unsafe { windows::Win32::System::DataExchange::GetClipboardSequenceNumber() })
+}
 
-    enigo
-        .key(Key::Control, Direction::Press)
-        .map_err(|e| format!("Failed to press Ctrl: {}", e))?;
-    enigo
-        .key(Key::Unicode('v'), Direction::Click)
-        .map_err(|e| format!("Failed to press V: {}", e))?;
-    enigo
-        .key(Key::Control, Direction::Release)
-        .map_err(|e| format!("Failed to release Ctrl: {}", e))?;
+#[cfg(not(windows))]
+fn clipboard_sequence() -> Option<u32> {
+    None
+}
 
-    Ok(())
+/// 其他程序（通常是正在粘贴的目标应用）是否正打开着剪贴板
+#[cfg(windows)]
+fn clipboard_in_use() -> bool {
+    unsafe { windows::Win32::System::DataExchange::GetOpenClipboardWindow().is_ok() }
 }
 
 #[cfg(not(windows))]
-fn simulate_ctrl_v() -> Result<(), String> {
-    Err("Clipboard paste simulation is only supported on Windows".to_string())
+fn clipboard_in_use() -> bool {
+    false
+}
+
+/// 写入识别结果后剪贴板是否又被其他程序改写
+/// 有序列号时比较序列号，否则比较剪贴板文本
+fn clipboard_changed(clipboard: &mut Clipboard, sequence: Option<u32>, text: &str) -> bool {
+    match sequence {
+        Some(sequence) => clipboard_sequence() != Some(sequence),
+        None => clipboard.get_text().ok().as_deref() != Some(text),
+    }
+}
+
+fn simulate_paste(shortcut: PasteShortcut) -> Result<(), String> {
+    match shortcut {
+        PasteShortcut::CtrlV => simulate_ctrl_chord('v'),
+        PasteShortcut::CtrlShiftV => SimulateOutput::press_keys(&[KeyAction::CtrlShiftV]),
+        PasteShortcut::ShiftInsert => SimulateOutput::press_keys(&[KeyAction::ShiftInsert]),
+    }
+}
+
+/// 模拟 Ctrl+<key>，key 为小写字母
+#[cfg(windows)]
+fn simulate_ctrl_chord(key: char) -> Result<(), String> {
+    super::simulate::enigo_ctrl_chord(key)
+}
+
+#[cfg(target_os = "linux")]
+fn simulate_ctrl_chord(key: char) -> Result<(), String> {
+    super::linux::ctrl_chord(key)
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn simulate_ctrl_chord(_key: char) -> Result<(), String> {
+    Err("Clipboard paste simulation is only supported on Windows and Linux".to_string())
 }
 
 #[cfg(test)]
@@ -117,6 +355,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_clipboard_settings_defaults() {
+        let settings: ClipboardSettings =
+            serde_json::from_value(serde_json::json!({"restoreDelayMs": 400})).unwrap();
+        assert_eq!(settings.restore_delay_ms, 400);
+        assert_eq!(settings.max_wait_ms, 1000);
+        assert!(!settings.exclude_from_history);
+    }
+
+    fn image() -> ImageData<'static> {
+        ImageData {
+            width: 1,
+            height: 1,
+            bytes: vec![0u8; 4].into(),
+        }
+    }
+
+    #[test]
+    fn test_backup_preferred_format() {
+        assert_eq!(
+            ClipboardBackup::default().preferred_format(),
+            BackupFormat::Empty
+        );
+
+        let files = ClipboardBackup {
+            text: Some("a.txt".into()),
+            files: vec![PathBuf::from("/tmp/a.txt")],
+            ..Default::default()
+        };
+        assert_eq!(files.preferred_format(), BackupFormat::Files);
+
+        // 截图只有图片
+        let screenshot = ClipboardBackup {
+            image: Some(image()),
+            ..Default::default()
+        };
+        assert_eq!(screenshot.preferred_format(), BackupFormat::Image);
+
+        let html = ClipboardBackup {
+            text: Some("hello".into()),
+            html: Some("<b>hello</b>".into()),
+            ..Default::default()
+        };
+        assert_eq!(html.preferred_format(), BackupFormat::Html);
+
+        let plain = ClipboardBackup {
+            text: Some("hello".into()),
+            ..Default::default()
+        };
+        assert_eq!(plain.preferred_format(), BackupFormat::Text);
+    }
+
+    #[test]
+    fn test_backup_mixed_content_keeps_richest_part() {
+        // Word、浏览器复制：HTML + 纯文本 + 图片，恢复 HTML 及其纯文本
+        let rich = ClipboardBackup {
+            text: Some("hello".into()),
+            html: Some("<b>hello</b>".into()),
+            image: Some(image()),
+            ..Default::default()
+        };
+        assert_eq!(rich.preferred_format(), BackupFormat::Html);
+
+        // 图片附带文字说明时恢复图片
+        let captioned = ClipboardBackup {
+            text: Some("caption".into()),
+            image: Some(image()),
+            ..Default::default()
+        };
+        assert_eq!(captioned.preferred_format(), BackupFormat::Image);
+
+        let files_with_html = ClipboardBackup {
+            html: Some("<a>a.txt</a>".into()),
+            image: Some(image()),
+            files: vec![PathBuf::from("/tmp/a.txt")],
+            ..Default::default()
+        };
+        assert_eq!(files_with_html.preferred_format(), BackupFormat::Files);
+    }
+
+    #[test]
+    fn test_wait_until_timeout() {
+        let mut calls = 0;
+        assert!(wait_until(Duration::from_millis(100), || {
+            calls += 1;
+            calls >= 3
+        }));
+        assert!(!wait_until(Duration::ZERO, || false));
+    }
+
     #[test]
     fn test_clipboard_lock_concurrent() {
         // 测试锁不会死锁
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+/// 当前前台应用，用于匹配按应用区分的输出规则
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForegroundApp {
+    /// 进程名，如 "WindowsTerminal.exe"、"kitty"
+    pub process_name: String,
+    /// 窗口类名（Windows 窗口类 / X11 WM_CLASS）
+    pub window_class: String,
+    /// 窗口句柄（Windows HWND / X11 窗口 id），用于确认焦点仍在同一窗口
+    #[serde(default)]
+    pub window_id: u64,
+}
+
+/// 获取前台窗口所属的应用
+#[cfg(windows)]
+pub fn foreground_app() -> Option<ForegroundApp> {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetClassNameW, GetForegroundWindow, GetWindowThreadProcessId,
+    };
+
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.0.is_null() {
+        return None;
+    }
+
+    let mut class_buf = [0u16; 256];
+    let len = unsafe { GetClassNameW(hwnd, &mut class_buf) };
+    let window_class = String::from_utf16_lossy(&class_buf[..len.max(0) as usize]);
+
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    let process_name = process_name_of(pid).unwrap_or_default();
+
+    Some(ForegroundApp {
+        process_name,
+        window_class,
+        window_id: hwnd.0 as usize as u64,
+    })
+}
+
+#[cfg(windows)]
+fn process_name_of(pid: u32) -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+    let mut buf = [0u16; 1024];
+    let mut size = buf.len() as u32;
+    let result = unsafe {
+        QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buf.as_mut_ptr()),
+            &mut size,
+        )
+    };
+    unsafe {
+        let _ = CloseHandle(handle);
+    }
+    result.ok()?;
+
+    let path = String::from_utf16_lossy(&buf[..size as usize]);
+    path.rsplit(['\\', '/']).next().map(str::to_string)
+}
+
+/// 通过 EWMH 的 _NET_ACTIVE_WINDOW 获取前台窗口
+/// Wayland 没有通用的查询方式，只能识别 XWayland 窗口
+#[cfg(target_os = "linux")]
+pub fn foreground_app() -> Option<ForegroundApp> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _};
+
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let intern = |name: &[u8]| {
+        conn.intern_atom(false, name)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.atom)
+    };
+    let active_atom = intern(b"_NET_ACTIVE_WINDOW")?;
+    let pid_atom = intern(b"_NET_WM_PID")?;
+
+    let window = conn
+        .get_property(false, root, active_atom, AtomEnum::WINDOW, 0, 1)
+        .ok()?
+        .reply()
+        .ok()?
+        .value32()?
+        .next()?;
+    if window == 0 {
+        return None;
+    }
+
+    let window_class = conn
+        .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .map(|reply| parse_wm_class(&reply.value))
+        .unwrap_or_default();
+
+    let process_name = conn
+        .get_property(false, window, pid_atom, AtomEnum::CARDINAL, 0, 1)
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .and_then(|reply| reply.value32().and_then(|mut values| values.next()))
+        .and_then(|pid| std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+        .map(|comm| comm.trim().to_string())
+        .unwrap_or_default();
+
+    Some(ForegroundApp {
+        process_name,
+        window_class,
+        window_id: window as u64,
+    })
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn foreground_app() -> Option<ForegroundApp> {
+    None
+}
+
+/// WM_CLASS 由 "instance\0Class\0" 组成，取类名，缺失时退回实例名
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_wm_class(value: &[u8]) -> String {
+    let mut parts = value
+        .split(|b| *b == 0)
+        .filter(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).into_owned());
+    let instance = parts.next().unwrap_or_default();
+    parts.next().unwrap_or(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wm_class() {
+        assert_eq!(parse_wm_class(b"kitty\0kitty\0"), "kitty");
+        assert_eq!(
+            parse_wm_class(b"gnome-terminal-server\0Gnome-terminal\0"),
+            "Gnome-terminal"
+        );
+        assert_eq!(parse_wm_class(b"xterm\0"), "xterm");
+        assert_eq!(parse_wm_class(b""), "");
+    }
+}
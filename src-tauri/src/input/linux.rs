@@ -0,0 +1,536 @@
+//! Linux 文字输出后端
+//!
+//! 按会话类型自动选择：
+//! - X11：XTest 扩展（enigo），其次 xdotool，最后 uinput
+//! - Wayland：virtual-keyboard 协议（enigo，wlroots 系合成器支持），其次 wtype，
+//!   再次 uinput 虚拟键盘（GNOME/KDE 可用，需要 /dev/uinput 写权限），最后 xdotool（仅 XWayland 窗口）
+
+use super::simulate::{enigo_ctrl_chord, enigo_press_keys, enigo_text};
+use super::simulate::{KeyAction, OutputBackend, OutputCapabilities};
+use std::process::Command;
+use std::sync::Mutex;
+
+/// uinput 虚拟键盘的设备名，快捷键监听据此忽略本程序模拟的按键
+pub(crate) const VIRTUAL_KEYBOARD_NAME: &str = "Sayble Virtual Keyboard";
+
+/// 已选择的后端缓存，capabilities 检测时刷新
+static SELECTED_BACKEND: Mutex<Option<OutputBackend>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionType {
+    X11,
+    Wayland,
+    Unknown,
+}
+
+impl SessionType {
+    fn as_str(self) -> &'static str {
+        match self {
+            SessionType::X11 => "x11",
+            SessionType::Wayland => "wayland",
+            SessionType::Unknown => "unknown",
+        }
+    }
+}
+
+fn session_type_from(
+    xdg_session_type: Option<&str>,
+    wayland_display: Option<&str>,
+    display: Option<&str>,
+) -> SessionType {
+    match xdg_session_type {
+        Some("wayland") => return SessionType::Wayland,
+        Some("x11") => return SessionType::X11,
+        _ => {}
+    }
+    if wayland_display.is_some_and(|d| !d.is_empty()) {
+        SessionType::Wayland
+    } else if display.is_some_and(|d| !d.is_empty()) {
+        SessionType::X11
+    } else {
+        SessionType::Unknown
+    }
+}
+
+fn session_type() -> SessionType {
+    session_type_from(
+        std::env::var("XDG_SESSION_TYPE").ok().as_deref(),
+        std::env::var("WAYLAND_DISPLAY").ok().as_deref(),
+        std::env::var("DISPLAY").ok().as_deref(),
+    )
+}
+
+/// 各会话类型下的候选后端（按优先级排序）
+fn candidates(session: SessionType) -> &'static [OutputBackend] {
+    match session {
+        SessionType::X11 => &[
+            OutputBackend::XTest,
+            OutputBackend::Xdotool,
+            OutputBackend::Uinput,
+        ],
+        SessionType::Wayland => &[
+            OutputBackend::VirtualKeyboard,
+            OutputBackend::Wtype,
+            OutputBackend::Uinput,
+            OutputBackend::Xdotool,
+        ],
+        SessionType::Unknown => &[OutputBackend::Uinput],
+    }
+}
+
+/// 在 PATH 中查找可执行文件
+fn command_exists(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+fn has_xtest() -> bool {
+    use x11rb::protocol::xtest::ConnectionExt as _;
+
+    let Ok((conn, _)) = x11rb::connect(None) else {
+        return false;
+    };
+    conn.xtest_get_version(2, 2)
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .is_some()
+}
+
+/// 查询 Wayland 合成器是否提供 zwp_virtual_keyboard_manager_v1
+fn has_virtual_keyboard() -> bool {
+    use wayland_client::protocol::wl_registry;
+    use wayland_client::{Connection, Dispatch, QueueHandle};
+
+    struct Globals(Vec<String>);
+
+    impl Dispatch<wl_registry::WlRegistry, ()> for Globals {
+        fn event(
+            state: &mut Self,
+            _: &wl_registry::WlRegistry,
+            event: wl_registry::Event,
+            _: &(),
+            _: &Connection,
+            _: &QueueHandle<Self>,
+        ) {
+            if let wl_registry::Event::Global { interface, .. } = event {
+                state.0.push(interface);
+            }
+        }
+    }
+
+    let Ok(conn) = Connection::connect_to_env() else {
+        return false;
+    };
+    let mut queue = conn.new_event_queue();
+    let _registry = conn.display().get_registry(&queue.handle(), ());
+    let mut globals = Globals(Vec::new());
+    if queue.roundtrip(&mut globals).is_err() {
+        return false;
+    }
+    globals
+        .0
+        .iter()
+        .any(|name| name == "zwp_virtual_keyboard_manager_v1")
+}
+
+fn uinput_writable() -> bool {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/uinput")
+        .is_ok()
+}
+
+fn is_available(backend: OutputBackend) -> bool {
+    match backend {
+        OutputBackend::XTest => has_xtest(),
+        OutputBackend::VirtualKeyboard => has_virtual_keyboard(),
+        OutputBackend::Uinput => uinput_writable(),
+        OutputBackend::Wtype => command_exists("wtype"),
+        OutputBackend::Xdotool => {
+            command_exists("xdotool") && std::env::var("DISPLAY").is_ok_and(|d| !d.is_empty())
+        }
+        OutputBackend::SendInput => false,
+    }
+}
+
+/// 检测当前会话的输出能力，并刷新后端选择
+pub fn check_capabilities() -> OutputCapabilities {
+    let session = session_type();
+    let available: Vec<OutputBackend> = candidates(session)
+        .iter()
+        .copied()
+        .filter(|backend| is_available(*backend))
+        .collect();
+    let backend = available.first().copied();
+    *SELECTED_BACKEND.lock().unwrap_or_else(|e| e.into_inner()) = backend;
+
+    let hint = match (session, backend) {
+        (SessionType::Wayland, None) => Some(
+            "未找到可用的键盘模拟方式，请安装 wtype，或将当前用户加入 input 组以使用 /dev/uinput"
+                .to_string(),
+        ),
+        (_, None) => Some("未找到可用的键盘模拟方式，请安装 xdotool".to_string()),
+        (_, Some(OutputBackend::Uinput)) => Some(
+            "当前使用 uinput 虚拟键盘，模拟输入仅支持英文字符，中文请使用剪贴板粘贴".to_string(),
+        ),
+        _ => None,
+    };
+
+    log::info!(
+        "[output] linux session={}, backend={:?}, available={:?}",
+        session.as_str(),
+        backend,
+        available
+    );
+    OutputCapabilities {
+        session_type: session.as_str().to_string(),
+        backend,
+        available,
+        hint,
+    }
+}
+
+fn backend() -> Result<OutputBackend, String> {
+    let cached = *SELECTED_BACKEND.lock().unwrap_or_else(|e| e.into_inner());
+    cached
+        .or_else(|| check_capabilities().backend)
+        .ok_or_else(|| "No keyboard simulation backend available".to_string())
+}
+
+/// 输入文本
+pub fn type_text(text: &str) -> Result<(), String> {
+    match backend()? {
+        OutputBackend::XTest | OutputBackend::VirtualKeyboard => enigo_text(text),
+        OutputBackend::Uinput => uinput::type_text(text),
+        tool => run_tool(tool, &text_args(tool, text)),
+    }
+}
+
+/// 依次执行按键动作
+pub fn press_keys(actions: &[KeyAction]) -> Result<(), String> {
+    match backend()? {
+        OutputBackend::XTest | OutputBackend::VirtualKeyboard => enigo_press_keys(actions),
+        OutputBackend::Uinput => uinput::press_keys(actions),
+        tool => {
+            for action in actions {
+                run_tool(tool, &key_action_args(tool, *action))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 模拟 Ctrl+<key>，key 为小写字母
+pub fn ctrl_chord(key: char) -> Result<(), String> {
+    match backend()? {
+        OutputBackend::XTest | OutputBackend::VirtualKeyboard => enigo_ctrl_chord(key),
+        OutputBackend::Uinput => uinput::ctrl_chord(key),
+        tool => run_tool(tool, &ctrl_chord_args(tool, key)),
+    }
+}
+
+fn tool_name(tool: OutputBackend) -> &'static str {
+    match tool {
+        OutputBackend::Wtype => "wtype",
+        _ => "xdotool",
+    }
+}
+
+fn run_tool(tool: OutputBackend, args: &[String]) -> Result<(), String> {
+    let name = tool_name(tool);
+    let output = Command::new(name)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", name, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn text_args(tool: OutputBackend, text: &str) -> Vec<String> {
+    match tool {
+        OutputBackend::Wtype => vec!["--".to_string(), text.to_string()],
+        _ => vec![
+            "type".to_string(),
+            "--clearmodifiers".to_string(),
+            "--".to_string(),
+            text.to_string(),
+        ],
+    }
+}
+
+fn key_action_args(tool: OutputBackend, action: KeyAction) -> Vec<String> {
+    let args: &[&str] = match (tool, action) {
+        (OutputBackend::Wtype, KeyAction::Enter) => &["-k", "Return"],
+        (OutputBackend::Wtype, KeyAction::ShiftEnter) => {
+            &["-M", "shift", "-k", "Return", "-m", "shift"]
+        }
+        (OutputBackend::Wtype, KeyAction::Tab) => &["-k", "Tab"],
+        (OutputBackend::Wtype, KeyAction::Backspace) => &["-k", "BackSpace"],
+        (OutputBackend::Wtype, KeyAction::Undo) => &["-M", "ctrl", "-k", "z", "-m", "ctrl"],
+        (OutputBackend::Wtype, KeyAction::CtrlShiftV) => &[
+            "-M", "ctrl", "-M", "shift", "-k", "v", "-m", "shift", "-m", "ctrl",
+        ],
+        (OutputBackend::Wtype, KeyAction::ShiftInsert) => {
+            &["-M", "shift", "-k", "Insert", "-m", "shift"]
+        }
+        (_, KeyAction::Enter) => &["key", "--clearmodifiers", "Return"],
+        (_, KeyAction::ShiftEnter) => &["key", "--clearmodifiers", "shift+Return"],
+        (_, KeyAction::Tab) => &["key", "--clearmodifiers", "Tab"],
+        (_, KeyAction::Backspace) => &["key", "--clearmodifiers", "BackSpace"],
+        (_, KeyAction::Undo) => &["key", "--clearmodifiers", "ctrl+z"],
+        (_, KeyAction::CtrlShiftV) => &["key", "--clearmodifiers", "ctrl+shift+v"],
+        (_, KeyAction::ShiftInsert) => &["key", "--clearmodifiers", "shift+Insert"],
+    };
+    args.iter().map(|s| s.to_string()).collect()
+}
+
+fn ctrl_chord_args(tool: OutputBackend, key: char) -> Vec<String> {
+    match tool {
+        OutputBackend::Wtype => vec![
+            "-M".to_string(),
+            "ctrl".to_string(),
+            "-k".to_string(),
+            key.to_string(),
+            "-m".to_string(),
+            "ctrl".to_string(),
+        ],
+        _ => vec![
+            "key".to_string(),
+            "--clearmodifiers".to_string(),
+            format!("ctrl+{}", key),
+        ],
+    }
+}
+
+/// 将 ASCII 字符映射为美式键盘布局下的 evdev 键码和是否需要 Shift
+fn ascii_key(c: char) -> Option<(u16, bool)> {
+    const LETTERS: &[u16; 26] = &[
+        30, 48, 46, 32, 18, 33, 34, 35, 23, 36, 37, 38, 50, 49, 24, 25, 16, 19, 31, 20, 22, 47, 17,
+        45, 21, 44,
+    ];
+    const DIGITS: &[u16; 10] = &[11, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    let key = match c {
+        'a'..='z' => (LETTERS[(c as u8 - b'a') as usize], false),
+        'A'..='Z' => (LETTERS[(c as u8 - b'A') as usize], true),
+        '0'..='9' => (DIGITS[(c as u8 - b'0') as usize], false),
+        ')' => (11, true),
+        '!' => (2, true),
+        '@' => (3, true),
+        '#' => (4, true),
+        '$' => (5, true),
+        '%' => (6, true),
+        '^' => (7, true),
+        '&' => (8, true),
+        '*' => (9, true),
+        '(' => (10, true),
+        '-' => (12, false),
+        '_' => (12, true),
+        '=' => (13, false),
+        '+' => (13, true),
+        '\t' => (15, false),
+        '[' => (26, false),
+        '{' => (26, true),
+        ']' => (27, false),
+        '}' => (27, true),
+        '\n' => (28, false),
+        ';' => (39, false),
+        ':' => (39, true),
+        '\'' => (40, false),
+        '"' => (40, true),
+        '`' => (41, false),
+        '~' => (41, true),
+        '\\' => (43, false),
+        '|' => (43, true),
+        ',' => (51, false),
+        '<' => (51, true),
+        '.' => (52, false),
+        '>' => (52, true),
+        '/' => (53, false),
+        '?' => (53, true),
+        ' ' => (57, false),
+        _ => return None,
+    };
+    Some(key)
+}
+
+mod uinput {
+    use super::*;
+    use evdev::uinput::{VirtualDevice, VirtualDeviceBuilder};
+    use evdev::{AttributeSet, EventType, InputEvent, Key};
+
+    const KEY_LEFTCTRL: u16 = 29;
+    const KEY_LEFTSHIFT: u16 = 42;
+    const KEY_ENTER: u16 = 28;
+    const KEY_TAB: u16 = 15;
+    const KEY_BACKSPACE: u16 = 14;
+    const KEY_Z: u16 = 44;
+    const KEY_V: u16 = 47;
+    const KEY_INSERT: u16 = 110;
+
+    /// 虚拟设备创建后需要等合成器识别，复用同一个设备避免每次输出都等待
+    static DEVICE: Mutex<Option<VirtualDevice>> = Mutex::new(None);
+
+    fn with_device<T>(
+        f: impl FnOnce(&mut VirtualDevice) -> std::io::Result<T>,
+    ) -> Result<T, String> {
+        let mut guard = DEVICE.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_none() {
+            let mut keys = AttributeSet::<Key>::new();
+            for code in 1..=127u16 {
+                keys.insert(Key::new(code));
+            }
+            let device = VirtualDeviceBuilder::new()
+                .and_then(|builder| builder.name(VIRTUAL_KEYBOARD_NAME).with_keys(&keys))
+                .and_then(|builder| builder.build())
+                .map_err(|e| format!("Failed to create uinput device: {}", e))?;
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            *guard = Some(device);
+        }
+        let device = guard.as_mut().expect("device initialized above");
+        f(device).map_err(|e| format!("uinput write failed: {}", e))
+    }
+
+    fn key_event(code: u16, value: i32) -> InputEvent {
+        InputEvent::new(EventType::KEY, code, value)
+    }
+
+    fn tap(device: &mut VirtualDevice, code: u16, modifiers: &[u16]) -> std::io::Result<()> {
+        for &modifier in modifiers {
+            device.emit(&[key_event(modifier, 1)])?;
+        }
+        device.emit(&[key_event(code, 1)])?;
+        device.emit(&[key_event(code, 0)])?;
+        for &modifier in modifiers.iter().rev() {
+            device.emit(&[key_event(modifier, 0)])?;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        Ok(())
+    }
+
+    pub(super) fn type_text(text: &str) -> Result<(), String> {
+        let keys: Vec<(u16, bool)> = text
+            .chars()
+            .map(|c| ascii_key(c).ok_or(c))
+            .collect::<Result<_, char>>()
+            .map_err(|c| format!("uinput 虚拟键盘无法输入字符 {:?}，请改用剪贴板粘贴", c))?;
+        with_device(|device| {
+            for (code, shift) in keys {
+                tap(device, code, if shift { &[KEY_LEFTSHIFT] } else { &[] })?;
+            }
+            Ok(())
+        })
+    }
+
+    pub(super) fn press_keys(actions: &[KeyAction]) -> Result<(), String> {
+        with_device(|device| {
+            for action in actions {
+                match action {
+                    KeyAction::Enter => tap(device, KEY_ENTER, &[])?,
+                    KeyAction::ShiftEnter => tap(device, KEY_ENTER, &[KEY_LEFTSHIFT])?,
+                    KeyAction::Tab => tap(device, KEY_TAB, &[])?,
+                    KeyAction::Backspace => tap(device, KEY_BACKSPACE, &[])?,
+                    KeyAction::Undo => tap(device, KEY_Z, &[KEY_LEFTCTRL])?,
+                    KeyAction::CtrlShiftV => tap(device, KEY_V, &[KEY_LEFTCTRL, KEY_LEFTSHIFT])?,
+                    KeyAction::ShiftInsert => tap(device, KEY_INSERT, &[KEY_LEFTSHIFT])?,
+                }
+            }
+            Ok(())
+        })
+    }
+
+    pub(super) fn ctrl_chord(key: char) -> Result<(), String> {
+        let (code, _) = ascii_key(key).ok_or_else(|| format!("Unsupported key: {}", key))?;
+        with_device(|device| tap(device, code, &[KEY_LEFTCTRL]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_type_from_env() {
+        assert_eq!(
+            session_type_from(Some("wayland"), None, Some(":0")),
+            SessionType::Wayland
+        );
+        assert_eq!(
+            session_type_from(Some("x11"), None, Some(":0")),
+            SessionType::X11
+        );
+        assert_eq!(
+            session_type_from(None, Some("wayland-0"), Some(":0")),
+            SessionType::Wayland
+        );
+        assert_eq!(
+            session_type_from(Some("tty"), None, Some(":1")),
+            SessionType::X11
+        );
+        assert_eq!(session_type_from(None, None, None), SessionType::Unknown);
+    }
+
+    #[test]
+    fn test_candidates_prefer_native_protocol() {
+        assert_eq!(candidates(SessionType::X11)[0], OutputBackend::XTest);
+        assert_eq!(
+            candidates(SessionType::Wayland)[0],
+            OutputBackend::VirtualKeyboard
+        );
+        assert!(!candidates(SessionType::Wayland).contains(&OutputBackend::XTest));
+        assert_eq!(candidates(SessionType::Unknown), &[OutputBackend::Uinput]);
+    }
+
+    #[test]
+    fn test_tool_args() {
+        assert_eq!(
+            text_args(OutputBackend::Wtype, "-n 你好"),
+            vec!["--", "-n 你好"]
+        );
+        assert_eq!(
+            text_args(OutputBackend::Xdotool, "hi"),
+            vec!["type", "--clearmodifiers", "--", "hi"]
+        );
+        assert_eq!(
+            key_action_args(OutputBackend::Wtype, KeyAction::ShiftEnter),
+            vec!["-M", "shift", "-k", "Return", "-m", "shift"]
+        );
+        assert_eq!(
+            key_action_args(OutputBackend::Xdotool, KeyAction::Undo),
+            vec!["key", "--clearmodifiers", "ctrl+z"]
+        );
+        assert_eq!(
+            key_action_args(OutputBackend::Xdotool, KeyAction::ShiftInsert),
+            vec!["key", "--clearmodifiers", "shift+Insert"]
+        );
+        assert_eq!(
+            ctrl_chord_args(OutputBackend::Xdotool, 'v'),
+            vec!["key", "--clearmodifiers", "ctrl+v"]
+        );
+        assert_eq!(
+            ctrl_chord_args(OutputBackend::Wtype, 'c'),
+            vec!["-M", "ctrl", "-k", "c", "-m", "ctrl"]
+        );
+    }
+
+    #[test]
+    fn test_ascii_key_us_layout() {
+        assert_eq!(ascii_key('a'), Some((30, false)));
+        assert_eq!(ascii_key('Z'), Some((44, true)));
+        assert_eq!(ascii_key('v'), Some((47, false)));
+        assert_eq!(ascii_key('0'), Some((11, false)));
+        assert_eq!(ascii_key('!'), Some((2, true)));
+        assert_eq!(ascii_key('?'), Some((53, true)));
+        assert_eq!(ascii_key(' '), Some((57, false)));
+        assert_eq!(ascii_key('你'), None);
+        assert_eq!(ascii_key('é'), None);
+    }
+}
@@ -1,5 +1,11 @@
 pub mod clipboard;
+pub mod foreground;
+#[cfg(target_os = "linux")]
+pub mod linux;
 pub mod simulate;
+pub mod typist;
 
-pub use clipboard::ClipboardOutput;
-pub use simulate::SimulateOutput;
+pub use clipboard::{ClipboardOutput, ClipboardSettings};
+pub use foreground::{foreground_app, ForegroundApp};
+pub use simulate::{KeyAction, OutputBackend, OutputCapabilities, SimulateOutput};
+pub use typist::{TypingProgress, TypingSettings};
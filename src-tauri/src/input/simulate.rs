@@ -1,4 +1,10 @@
+use super::typist::{self, TypingProgress, TypingSettings};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+/// 模拟按键前等待快捷键修饰键松开的最长时间
+const MODIFIER_RELEASE_TIMEOUT: Duration = Duration::from_millis(1000);
 
 /// 模拟键盘输入模式（逐字符输入，适合英文场景）
 pub struct SimulateOutput;
@@ -7,41 +13,271 @@ pub struct SimulateOutput;
 pub struct SimulateResult {
     pub chars_sent: usize,
     pub success: bool,
+    /// 分段输入被用户中止
+    #[serde(default)]
+    pub aborted: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 输出层可执行的按键动作（语音命令、尾随动作等共用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyAction {
+    Enter,
+    ShiftEnter,
+    Tab,
+    Backspace,
+    /// Ctrl+Z
+    Undo,
+    /// Ctrl+Shift+V，终端中的粘贴
+    CtrlShiftV,
+    /// Shift+Insert
+    ShiftInsert,
+}
+
+/// 键盘模拟后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputBackend {
+    /// Windows SendInput
+    SendInput,
+    /// X11 XTest 扩展
+    XTest,
+    /// Wayland virtual-keyboard 协议
+    VirtualKeyboard,
+    /// /dev/uinput 虚拟键盘设备（按美式布局输入，仅支持 ASCII 文本）
+    Uinput,
+    /// wtype 命令行工具（Wayland）
+    Wtype,
+    /// xdotool 命令行工具（X11 / XWayland）
+    Xdotool,
+}
+
+impl OutputBackend {
+    /// 是否能直接输入任意 Unicode 文本
+    pub fn supports_unicode(self) -> bool {
+        !matches!(self, OutputBackend::Uinput)
+    }
+}
+
+/// 当前系统的文字输出能力，供设置界面检测展示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputCapabilities {
+    /// 会话类型: "windows", "x11", "wayland", "unknown"
+    pub session_type: String,
+    /// 自动选择的后端，None 表示无法模拟键盘
+    pub backend: Option<OutputBackend>,
+    /// 当前会话下可用的全部后端（按优先级排序）
+    pub available: Vec<OutputBackend>,
+    /// 不可用或功能受限时给用户的提示
+    pub hint: Option<String>,
 }
 
 impl SimulateOutput {
-    /// 通过模拟键盘逐字符输入文字
-    #[cfg(windows)]
-    pub fn type_text(text: &str) -> Result<SimulateResult, String> {
-        use enigo::{Enigo, Keyboard, Settings};
+    /// 等待快捷键的修饰键松开，避免与模拟的按键组合成其他快捷键；超时返回 false
+    pub fn wait_modifiers_released(modifiers_held: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + MODIFIER_RELEASE_TIMEOUT;
+        while modifiers_held() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        true
+    }
 
+    /// 通过模拟键盘逐字符输入文字，Linux 下后端按会话类型自动选择
+    pub fn type_text(text: &str) -> Result<SimulateResult, String> {
         let char_count = text.chars().count();
         log::info!("[output] simulate keyboard start, chars={}", char_count);
 
-        let mut enigo = Enigo::new(&Settings::default()).map_err(|e| {
-            log::error!("[output] failed to create Enigo: {}", e);
-            format!("Failed to create Enigo: {}", e)
-        })?;
-
         // 短暂延迟让目标窗口获取焦点
         std::thread::sleep(std::time::Duration::from_millis(50));
-
-        enigo.text(text).map_err(|e| {
-            log::error!("[output] failed to type text: {}", e);
-            format!("Failed to type text: {}", e)
-        })?;
+        send_text(text)?;
 
         log::info!("[output] simulate keyboard done, chars_sent={}", char_count);
         Ok(SimulateResult {
             chars_sent: char_count,
             success: true,
+            aborted: false,
+            error: None,
         })
     }
 
-    #[cfg(not(windows))]
-    pub fn type_text(_text: &str) -> Result<SimulateResult, String> {
-        Err("Keyboard simulation is only supported on Windows".to_string())
+    /// 按设置的速度分段输入，abort 置位（如按下 Esc）时中止
+    pub fn type_text_paced(
+        text: &str,
+        settings: &TypingSettings,
+        abort: &AtomicBool,
+        on_progress: impl FnMut(TypingProgress),
+    ) -> SimulateResult {
+        log::info!(
+            "[output] paced keyboard start, chars={}, settings={:?}",
+            text.chars().count(),
+            settings
+        );
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let result = typist::type_chunked(text, settings, abort, send_text, on_progress);
+        log::info!(
+            "[output] paced keyboard done, chars_sent={}, success={}",
+            result.chars_sent,
+            result.success
+        );
+        result
+    }
+
+    /// 依次执行一组按键动作
+    #[cfg(windows)]
+    pub fn press_keys(actions: &[KeyAction]) -> Result<(), String> {
+        if actions.is_empty() {
+            return Ok(());
+        }
+        log::info!("[output] press keys: {:?}", actions);
+        enigo_press_keys(actions)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn press_keys(actions: &[KeyAction]) -> Result<(), String> {
+        if actions.is_empty() {
+            return Ok(());
+        }
+        log::info!("[output] press keys: {:?}", actions);
+        super::linux::press_keys(actions)
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    pub fn press_keys(_actions: &[KeyAction]) -> Result<(), String> {
+        Err("Keyboard simulation is only supported on Windows and Linux".to_string())
+    }
+
+    /// 检测当前系统的键盘模拟能力
+    #[cfg(windows)]
+    pub fn capabilities() -> OutputCapabilities {
+        OutputCapabilities {
+            session_type: "windows".to_string(),
+            backend: Some(OutputBackend::SendInput),
+            available: vec![OutputBackend::SendInput],
+            hint: None,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn capabilities() -> OutputCapabilities {
+        super::linux::check_capabilities()
     }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    pub fn capabilities() -> OutputCapabilities {
+        OutputCapabilities {
+            session_type: "unknown".to_string(),
+            backend: None,
+            available: Vec::new(),
+            hint: Some("当前系统暂不支持模拟键盘输入".to_string()),
+        }
+    }
+}
+
+/// 输入一段文字，不做额外等待
+#[cfg(windows)]
+fn send_text(text: &str) -> Result<(), String> {
+    enigo_text(text)
+}
+
+#[cfg(target_os = "linux")]
+fn send_text(text: &str) -> Result<(), String> {
+    super::linux::type_text(text)
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn send_text(_text: &str) -> Result<(), String> {
+    Err("Keyboard simulation is only supported on Windows and Linux".to_string())
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+fn new_enigo() -> Result<enigo::Enigo, String> {
+    enigo::Enigo::new(&enigo::Settings::default()).map_err(|e| {
+        log::error!("[output] failed to create Enigo: {}", e);
+        format!("Failed to create Enigo: {}", e)
+    })
+}
+
+/// 通过 enigo 输入文本（Windows SendInput / X11 XTest / Wayland virtual-keyboard）
+#[cfg(any(windows, target_os = "linux"))]
+pub(crate) fn enigo_text(text: &str) -> Result<(), String> {
+    use enigo::Keyboard;
+
+    let mut enigo = new_enigo()?;
+    enigo.text(text).map_err(|e| {
+        log::error!("[output] failed to type text: {}", e);
+        format!("Failed to type text: {}", e)
+    })
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+fn enigo_chord(
+    enigo: &mut enigo::Enigo,
+    modifier: enigo::Key,
+    key: enigo::Key,
+) -> Result<(), String> {
+    use enigo::{Direction, Keyboard};
+
+    enigo
+        .key(modifier, Direction::Press)
+        .map_err(|e| format!("Failed to press {:?}: {}", modifier, e))?;
+    let result = enigo
+        .key(key, Direction::Click)
+        .map_err(|e| format!("Failed to press {:?}: {}", key, e));
+    enigo
+        .key(modifier, Direction::Release)
+        .map_err(|e| format!("Failed to release {:?}: {}", modifier, e))?;
+    result
+}
+
+/// 通过 enigo 依次执行按键动作
+#[cfg(any(windows, target_os = "linux"))]
+pub(crate) fn enigo_press_keys(actions: &[KeyAction]) -> Result<(), String> {
+    use enigo::{Direction, Key, Keyboard};
+
+    let mut enigo = new_enigo()?;
+
+    for action in actions {
+        match action {
+            KeyAction::Enter => enigo
+                .key(Key::Return, Direction::Click)
+                .map_err(|e| format!("Failed to press Enter: {}", e))?,
+            KeyAction::ShiftEnter => enigo_chord(&mut enigo, Key::Shift, Key::Return)?,
+            KeyAction::Tab => enigo
+                .key(Key::Tab, Direction::Click)
+                .map_err(|e| format!("Failed to press Tab: {}", e))?,
+            KeyAction::Backspace => enigo
+                .key(Key::Backspace, Direction::Click)
+                .map_err(|e| format!("Failed to press Backspace: {}", e))?,
+            KeyAction::Undo => enigo_chord(&mut enigo, Key::Control, Key::Unicode('z'))?,
+            KeyAction::CtrlShiftV => {
+                enigo
+                    .key(Key::Control, Direction::Press)
+                    .map_err(|e| format!("Failed to press Control: {}", e))?;
+                let result = enigo_chord(&mut enigo, Key::Shift, Key::Unicode('v'));
+                enigo
+                    .key(Key::Control, Direction::Release)
+                    .map_err(|e| format!("Failed to release Control: {}", e))?;
+                result?
+            }
+            KeyAction::ShiftInsert => enigo_chord(&mut enigo, Key::Shift, Key::Insert)?,
+        }
+        // 给目标应用处理按键的时间，避免连续按键被合并或丢失
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    Ok(())
+}
+
+/// 通过 enigo 模拟 Ctrl+<key>，key 为小写字母
+#[cfg(any(windows, target_os = "linux"))]
+pub(crate) fn enigo_ctrl_chord(key: char) -> Result<(), String> {
+    let mut enigo = new_enigo()?;
+    enigo_chord(&mut enigo, enigo::Key::Control, enigo::Key::Unicode(key))
 }
 
 #[cfg(test)]
@@ -53,10 +289,36 @@ mod tests {
         let result = SimulateResult {
             chars_sent: 5,
             success: true,
+            aborted: false,
+            error: None,
         };
         let json = serde_json::to_string(&result).unwrap();
         let deserialized: SimulateResult = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.chars_sent, 5);
         assert!(deserialized.success);
     }
+
+    #[test]
+    fn test_output_capabilities_serialization() {
+        let caps = OutputCapabilities {
+            session_type: "wayland".to_string(),
+            backend: Some(OutputBackend::VirtualKeyboard),
+            available: vec![OutputBackend::VirtualKeyboard, OutputBackend::Uinput],
+            hint: None,
+        };
+        let json = serde_json::to_value(&caps).unwrap();
+        assert_eq!(json["sessionType"], "wayland");
+        assert_eq!(json["backend"], "virtualKeyboard");
+        assert_eq!(json["available"][1], "uinput");
+        assert!(!OutputBackend::Uinput.supports_unicode());
+        assert!(OutputBackend::Wtype.supports_unicode());
+    }
+
+    #[test]
+    fn test_key_action_serialization() {
+        let json = serde_json::to_string(&KeyAction::ShiftEnter).unwrap();
+        assert_eq!(json, "\"shiftEnter\"");
+        let action: KeyAction = serde_json::from_str("\"undo\"").unwrap();
+        assert_eq!(action, KeyAction::Undo);
+    }
 }
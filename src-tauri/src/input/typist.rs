@@ -0,0 +1,211 @@
+use super::simulate::SimulateResult;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 分段输入设置（app_settings.typing）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TypingSettings {
+    /// 每秒输入的字符数，0 表示不限速
+    pub chars_per_second: u32,
+    /// 每段的字素数（表情、组合字符不会被拆开）
+    pub chunk_graphemes: usize,
+}
+
+impl Default for TypingSettings {
+    fn default() -> Self {
+        Self {
+            chars_per_second: 40,
+            chunk_graphemes: 4,
+        }
+    }
+}
+
+/// 分段输入进度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypingProgress {
+    pub chars_sent: usize,
+    pub total_chars: usize,
+}
+
+/// 按字素边界切分文本，每段最多 chunk_graphemes 个字素
+pub fn split_chunks(text: &str, chunk_graphemes: usize) -> Vec<&str> {
+    let chunk_graphemes = chunk_graphemes.max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    for (count, (index, _)) in text.grapheme_indices(true).enumerate() {
+        if count > 0 && count % chunk_graphemes == 0 {
+            chunks.push(&text[start..index]);
+            start = index;
+        }
+    }
+    if start < text.len() {
+        chunks.push(&text[start..]);
+    }
+    chunks
+}
+
+/// 输入一段文字后的等待时间，使整体速度不超过 chars_per_second
+fn chunk_delay(chunk_chars: usize, chars_per_second: u32) -> Duration {
+    if chars_per_second == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_secs_f64(chunk_chars as f64 / chars_per_second as f64)
+}
+
+/// 分段输入文本
+/// send 负责实际输入一段文字；abort 被置位时在下一段开始前停止
+/// 返回实际输入的字符数，失败或中止时 success 为 false
+pub fn type_chunked(
+    text: &str,
+    settings: &TypingSettings,
+    abort: &AtomicBool,
+    mut send: impl FnMut(&str) -> Result<(), String>,
+    mut on_progress: impl FnMut(TypingProgress),
+) -> SimulateResult {
+    let total_chars = text.chars().count();
+    let mut chars_sent = 0;
+
+    for (i, chunk) in split_chunks(text, settings.chunk_graphemes)
+        .into_iter()
+        .enumerate()
+    {
+        if abort.load(Ordering::SeqCst) {
+            log::info!(
+                "[output] typing aborted, chars_sent={}/{}",
+                chars_sent,
+                total_chars
+            );
+            return SimulateResult {
+                chars_sent,
+                success: false,
+                aborted: true,
+                error: None,
+            };
+        }
+
+        let chunk_chars = chunk.chars().count();
+        if i > 0 {
+            std::thread::sleep(chunk_delay(chunk_chars, settings.chars_per_second));
+        }
+        if let Err(e) = send(chunk) {
+            log::error!(
+                "[output] typing failed, chars_sent={}/{}: {}",
+                chars_sent,
+                total_chars,
+                e
+            );
+            return SimulateResult {
+                chars_sent,
+                success: false,
+                aborted: false,
+                error: Some(e),
+            };
+        }
+        chars_sent += chunk_chars;
+        on_progress(TypingProgress {
+            chars_sent,
+            total_chars,
+        });
+    }
+
+    SimulateResult {
+        chars_sent,
+        success: true,
+        aborted: false,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_DELAY: TypingSettings = TypingSettings {
+        chars_per_second: 0,
+        chunk_graphemes: 2,
+    };
+
+    #[test]
+    fn test_split_chunks_keeps_graphemes() {
+        assert_eq!(split_chunks("abcde", 2), vec!["ab", "cd", "e"]);
+        assert_eq!(split_chunks("你好世界", 3), vec!["你好世", "界"]);
+        // 组合字符和家庭表情不被拆开
+        assert_eq!(split_chunks("e\u{301}x", 1), vec!["e\u{301}", "x"]);
+        assert_eq!(split_chunks("👨‍👩‍👧a", 1), vec!["👨‍👩‍👧", "a"]);
+        assert!(split_chunks("", 4).is_empty());
+        assert_eq!(split_chunks("ab", 0), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_chunk_delay() {
+        assert_eq!(chunk_delay(4, 40), Duration::from_millis(100));
+        assert_eq!(chunk_delay(4, 0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_type_chunked_reports_progress() {
+        let abort = AtomicBool::new(false);
+        let mut typed = String::new();
+        let mut progress = Vec::new();
+        let result = type_chunked(
+            "你好abc",
+            &NO_DELAY,
+            &abort,
+            |chunk| {
+                typed.push_str(chunk);
+                Ok(())
+            },
+            |p| progress.push(p.chars_sent),
+        );
+        assert!(result.success);
+        assert_eq!(result.chars_sent, 5);
+        assert_eq!(typed, "你好abc");
+        assert_eq!(progress, vec![2, 4, 5]);
+    }
+
+    #[test]
+    fn test_type_chunked_failure_reports_chars_sent() {
+        let abort = AtomicBool::new(false);
+        let mut calls = 0;
+        let result = type_chunked(
+            "abcdef",
+            &NO_DELAY,
+            &abort,
+            |_| {
+                calls += 1;
+                if calls == 2 {
+                    Err("lost focus".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            |_| {},
+        );
+        assert!(!result.success);
+        assert!(!result.aborted);
+        assert_eq!(result.chars_sent, 2);
+        assert_eq!(result.error.as_deref(), Some("lost focus"));
+    }
+
+    #[test]
+    fn test_type_chunked_abort() {
+        let abort = AtomicBool::new(false);
+        let result = type_chunked(
+            "abcdef",
+            &NO_DELAY,
+            &abort,
+            |_| {
+                // 输入第一段后按下 Esc
+                abort.store(true, Ordering::SeqCst);
+                Ok(())
+            },
+            |_| {},
+        );
+        assert!(result.aborted);
+        assert_eq!(result.chars_sent, 2);
+    }
+}
@@ -1,40 +1,71 @@
 pub mod asr;
 pub mod audio;
+pub mod backup;
 pub mod config;
+pub mod control;
+pub mod corrections;
+pub mod export;
+pub mod history;
 pub mod hotkey;
 pub mod input;
 pub mod live_window;
+pub mod normalize;
+pub mod pending;
 pub mod polish;
+pub mod secrets;
+pub mod settings;
 pub mod share;
+pub mod spacing;
+pub mod stats;
 pub mod store;
 pub mod tray;
 pub mod tunnel;
+pub mod voice_command;
 
 use asr::volcengine::VolcEngineAsr;
-use asr::AsrEvent;
+use asr::{AsrEvent, Utterance};
 use audio::AudioCapture;
-use config::{AppConfig, AsrConfig, HotkeyBinding, HotkeyConfig, OutputMode};
+use backup::{Backup, ImportMode, ImportSummary};
+use config::{
+    AppConfig, AsrConfig, HotkeyAction, HotkeyBinding, HotkeyConfig, HotkeyProfile, OutputMode,
+    PasteShortcut, ProfilePolish, TrailingAction, TranslationTarget, TriggerMode,
+};
+use control::{ControlApi, ControlRequest, ControlStatus};
+use history::HistoryQuery;
 use hotkey::HotkeyManager;
-use input::{ClipboardOutput, SimulateOutput};
+use input::{ClipboardOutput, ForegroundApp, KeyAction, OutputCapabilities, SimulateOutput};
+use pending::{PendingOutput, PendingOutputInfo, PendingOutputs};
+use secrets::SecretStore;
+use settings::{AppSettings, PolishSettings};
 use share::ShareManager;
+use spacing::SpacingLanguage;
 use store::AppStore;
 use tray::TrayManager;
 use tunnel::TunnelManager;
+use voice_command::{CommandGrammar, Segment};
 
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex,
 };
-use tauri::{Emitter, Manager};
+use tauri::{Emitter, Listener, Manager};
 use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_notification::NotificationExt;
 
 const FLOATING_WINDOW_WIDTH: f64 = 300.0;
 const FLOATING_WINDOW_HEIGHT: f64 = 52.0;
+/// 待确认输出的预览需要显示可编辑的文本框和按钮
+const FLOATING_PREVIEW_WIDTH: f64 = 360.0;
+const FLOATING_PREVIEW_HEIGHT: f64 = 148.0;
 const FLOATING_WINDOW_BOTTOM_GAP: f64 = 16.0;
 const RECORDING_SILENCE_AUTO_STOP_SECS: u64 = 30;
 const RECORDING_MAX_DURATION_SECS: u64 = 120;
 const SILENCE_RMS_THRESHOLD: f64 = 450.0;
 const AUTOSTART_ARG: &str = "--autostart";
+const TRANSCRIBE_SAMPLE_RATE: usize = 16000;
+const TRANSCRIBE_CHUNK_MS: u64 = 200;
+/// 文件转写在音频发送完后等待最终结果的时间
+const TRANSCRIBE_RESULT_TIMEOUT_SECS: u64 = 30;
 
 #[derive(Clone, Copy, Debug)]
 struct WindowFrame {
@@ -137,7 +168,12 @@ fn clamp_to_range(value: f64, min: f64, max: f64) -> f64 {
     }
 }
 
-fn position_floating_window(app: &tauri::AppHandle, window: &tauri::WebviewWindow) {
+fn position_floating_window(
+    app: &tauri::AppHandle,
+    window: &tauri::WebviewWindow,
+    logical_width: f64,
+    logical_height: f64,
+) {
     let target_frame = foreground_window_frame(window);
     let target_monitor = target_frame.and_then(|frame| {
         let (x, y) = frame.center();
@@ -151,8 +187,8 @@ fn position_floating_window(app: &tauri::AppHandle, window: &tauri::WebviewWindo
     if let Some(monitor) = monitor {
         let scale = monitor.scale_factor();
         let work_area = monitor.work_area();
-        let width = FLOATING_WINDOW_WIDTH * scale;
-        let height = FLOATING_WINDOW_HEIGHT * scale;
+        let width = logical_width * scale;
+        let height = logical_height * scale;
         let gap = FLOATING_WINDOW_BOTTOM_GAP * scale;
         let work_left = work_area.position.x as f64;
         let work_top = work_area.position.y as f64;
@@ -193,13 +229,25 @@ fn position_floating_window(app: &tauri::AppHandle, window: &tauri::WebviewWindo
     }
 }
 
-fn show_floating_window(app: &tauri::AppHandle) -> Result<(), String> {
+/// 显示悬浮窗；preview 为 true 时放大并允许获得焦点，用于编辑待确认的输出
+fn show_floating_window(app: &tauri::AppHandle, preview: bool) -> Result<(), String> {
     let Some(window) = app.get_webview_window("floating") else {
         log::warn!("[floating] window not found");
         return Err("floating window not found".to_string());
     };
 
-    position_floating_window(app, &window);
+    let (width, height) = if preview {
+        (FLOATING_PREVIEW_WIDTH, FLOATING_PREVIEW_HEIGHT)
+    } else {
+        (FLOATING_WINDOW_WIDTH, FLOATING_WINDOW_HEIGHT)
+    };
+    if let Err(e) = window.set_size(tauri::LogicalSize::new(width, height)) {
+        log::warn!("[floating] failed to resize window: {}", e);
+    }
+    if let Err(e) = window.set_focusable(preview) {
+        log::warn!("[floating] failed to set focusable: {}", e);
+    }
+    position_floating_window(app, &window, width, height);
 
     if let Err(e) = window.set_always_on_top(true) {
         log::warn!("[floating] failed to keep window on top: {}", e);
@@ -462,6 +510,28 @@ enum RecordingConfig {
     Sapi,
 }
 
+impl RecordingConfig {
+    /// 统计用量时使用的 ASR 服务商 id
+    fn provider_id(&self) -> &'static str {
+        match self {
+            RecordingConfig::Volcengine { .. } => "volcengine",
+            #[cfg(target_os = "windows")]
+            RecordingConfig::Sapi => "sapi",
+        }
+    }
+}
+
+/// 录音会话的用途
+#[derive(Debug, Clone)]
+enum SessionMode {
+    /// 普通听写：识别结果（可选润色）输出到焦点应用
+    Dictation,
+    /// 选中文本改写：识别结果作为指令，改写 selection 后粘贴回原处
+    Transform { selection: String },
+    /// 听写翻译：识别结果翻译成目标语言后输出
+    Translate(TranslationTarget),
+}
+
 fn rms_amplitude(samples: &[i16]) -> f64 {
     if samples.is_empty() {
         return 0.0;
@@ -497,144 +567,765 @@ fn recording_auto_stop_reason(
     None
 }
 
-/// 从前端快捷键标签字符串解析为 HotkeyConfig 列表
-fn parse_hotkey_configs(toggle_label: &str) -> Vec<HotkeyConfig> {
+/// 从 app_settings 中的快捷键标签字符串解析为 HotkeyConfig 列表
+fn parse_hotkey_configs(app_settings: &AppSettings) -> Vec<HotkeyConfig> {
     let mut configs = Vec::new();
+    let toggle_label = &app_settings.toggle_hotkey;
     if let Some(binding) = HotkeyBinding::parse_from_label(toggle_label) {
+        // 主快捷键触发方式："toggle"（默认）或 "hold" 按住说话
+        let trigger = app_settings.toggle_hotkey_mode;
         log::info!(
-            "[hotkey] parsed toggle binding: {:?} from \"{}\"",
+            "[hotkey] parsed toggle binding: {:?} ({:?}) from \"{}\"",
             binding,
+            trigger,
             toggle_label
         );
-        configs.push(HotkeyConfig { binding });
+        configs.push(HotkeyConfig {
+            binding,
+            action: HotkeyAction::ToggleRecording,
+            translation: None,
+            profile_id: None,
+            trigger,
+        });
+    }
+
+    // 确认输出快捷键：仅在关闭自动输出、有待确认结果时生效
+    let accept_label = &app_settings.accept_output_hotkey;
+    if let Some(binding) = HotkeyBinding::parse_from_label(accept_label) {
+        log::info!(
+            "[hotkey] parsed accept output binding: {:?} from \"{}\"",
+            binding,
+            accept_label
+        );
+        configs.push(HotkeyConfig {
+            binding,
+            action: HotkeyAction::AcceptOutput,
+            translation: None,
+            profile_id: None,
+            trigger: TriggerMode::Toggle,
+        });
+    }
+
+    // 撤销快捷键：删除上一次听写输出，目标窗口已切换时不生效
+    // 快捷键本身也会传给目标应用，建议使用不产生输入的功能键（如 F9）
+    let undo_label = &app_settings.undo_output_hotkey;
+    if let Some(binding) = HotkeyBinding::parse_from_label(undo_label) {
+        log::info!(
+            "[hotkey] parsed undo output binding: {:?} from \"{}\"",
+            binding,
+            undo_label
+        );
+        configs.push(HotkeyConfig {
+            binding,
+            action: HotkeyAction::UndoOutput,
+            translation: None,
+            profile_id: None,
+            trigger: TriggerMode::Toggle,
+        });
+    }
+
+    let transform_label = &app_settings.transform_hotkey;
+    if let Some(binding) = HotkeyBinding::parse_from_label(transform_label) {
+        log::info!(
+            "[hotkey] parsed transform binding: {:?} from \"{}\"",
+            binding,
+            transform_label
+        );
+        configs.push(HotkeyConfig {
+            binding,
+            action: HotkeyAction::TransformSelection,
+            translation: None,
+            profile_id: None,
+            trigger: TriggerMode::Toggle,
+        });
+    }
+
+    // 翻译快捷键：每个绑定对应一个目标语言
+    for item in &app_settings.translation_hotkeys {
+        let Some(binding) = HotkeyBinding::parse_from_label(&item.hotkey) else {
+            continue;
+        };
+        if item.target.target_language.trim().is_empty() {
+            continue;
+        }
+        log::info!(
+            "[hotkey] parsed translation binding: {:?} from \"{}\" => {}",
+            binding,
+            item.hotkey,
+            item.target.target_language
+        );
+        configs.push(HotkeyConfig {
+            binding,
+            action: HotkeyAction::Translate,
+            translation: Some(item.target.clone()),
+            profile_id: None,
+            trigger: TriggerMode::Toggle,
+        });
+    }
+
+    // 命名快捷键方案
+    for profile in load_hotkey_profiles(app_settings) {
+        let Some(binding) = HotkeyBinding::parse_from_label(&profile.hotkey) else {
+            continue;
+        };
+        log::info!(
+            "[hotkey] parsed profile binding: {:?} from \"{}\" => {}",
+            binding,
+            profile.hotkey,
+            profile.id
+        );
+        configs.push(HotkeyConfig {
+            binding,
+            action: profile.action,
+            translation: profile.translation,
+            profile_id: Some(profile.id),
+            trigger: profile.trigger,
+        });
     }
     configs
 }
 
-/// 从持久化 store 中读取输出配置，直接执行文字输出
-fn output_text_from_store(app: &tauri::AppHandle, text: &str) {
-    let store = app.state::<AppStore>();
-    let settings = store.settings().get("app_settings");
+/// 读取 app_settings.hotkeyProfiles，跳过 id 为空的方案
+fn load_hotkey_profiles(app_settings: &AppSettings) -> Vec<HotkeyProfile> {
+    app_settings
+        .hotkey_profiles
+        .iter()
+        .filter(|profile| !profile.id.is_empty())
+        .cloned()
+        .collect()
+}
 
-    let auto_output = settings
-        .as_ref()
-        .and_then(|s| s.get("autoOutput"))
-        .and_then(|v| v.as_bool())
-        .unwrap_or(true);
+/// 按 id 查找快捷键方案
+fn find_hotkey_profile(app: &tauri::AppHandle, id: &str) -> Option<HotkeyProfile> {
+    let settings = app.state::<AppStore>().app_settings();
+    load_hotkey_profiles(&settings)
+        .into_iter()
+        .find(|profile| profile.id == id)
+}
 
-    if !auto_output {
-        log::info!("[output] autoOutput is off, skipping");
-        return;
+/// 最近一次输出到目标应用的文本，供"删除上一句"等语音命令使用
+#[derive(Default)]
+struct LastOutput {
+    text: Mutex<String>,
+    /// 最近一次听写的输出记录，供智能空格和撤销使用
+    record: Mutex<Option<OutputRecord>>,
+}
+
+/// 一次听写实际输出的内容、方式、目标窗口和时间
+struct OutputRecord {
+    session_id: u64,
+    text: String,
+    plan: OutputPlan,
+    app: Option<ForegroundApp>,
+    at: std::time::Instant,
+    profile: Option<HotkeyProfile>,
+    /// 对应历史记录的 id，写入历史后补充
+    history_id: Option<String>,
+    /// 执行过语音命令或追加按键时无法准确撤销
+    undoable: bool,
+}
+
+/// 记录最近一次输出对应的历史记录，撤销时据此更新状态
+fn link_last_output_history(app: &tauri::AppHandle, session_id: u64, history_id: &str) {
+    let last_output = app.state::<LastOutput>();
+    let mut record = last_output.record.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(record) = record.as_mut().filter(|r| r.session_id == session_id) {
+        record.history_id = Some(history_id.to_string());
     }
+}
 
-    let output_mode_str = settings
-        .as_ref()
-        .and_then(|s| s.get("outputMode"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("Clipboard");
+/// 删除一段已输入文本所需的退格次数：编辑器按字素删除，组合符号和 emoji 各只需一次
+fn backspace_count(text: &str) -> usize {
+    use unicode_segmentation::UnicodeSegmentation;
+    text.graphemes(true).count()
+}
 
-    let mode = match output_mode_str {
-        "SimulateKeyboard" => OutputMode::SimulateKeyboard,
-        _ => OutputMode::Clipboard,
+/// 撤销最近一次听写输出：模拟输入按字素数退格，Ctrl+V 粘贴发送 Ctrl+Z
+/// 仅在目标窗口仍是前台窗口时执行；reopen 为 true 时把文本放回预览供编辑后重新输出
+fn undo_last_output(app: &tauri::AppHandle, reopen: bool) -> Result<(), String> {
+    let last_output = app.state::<LastOutput>();
+    let mut guard = last_output.record.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(record) = guard.as_ref() else {
+        return Err("没有可撤销的输出".to_string());
     };
+    if !record.undoable || record.text.is_empty() {
+        return Err("上一次输出包含语音命令或追加按键，无法撤销".to_string());
+    }
+    let foreground = input::foreground_app();
+    if record.app.is_none() || foreground != record.app {
+        return Err("目标窗口已不在前台，未执行撤销".to_string());
+    }
 
+    // 终端中 Ctrl+Z 会挂起进程，非 Ctrl+V 粘贴时同样按退格删除
+    let keys = match (record.plan.mode, record.plan.paste_shortcut) {
+        (OutputMode::Clipboard, PasteShortcut::CtrlV) => vec![KeyAction::Undo],
+        (OutputMode::CopyOnly, _) => return Err("仅复制模式没有输出到应用".to_string()),
+        _ => vec![KeyAction::Backspace; backspace_count(&record.text)],
+    };
     log::info!(
-        "[output] output_text_from_store, mode={:?}, text_len={}",
-        mode,
-        text.len()
+        "[output] undo session {} output, mode={:?}, keys={}",
+        record.session_id,
+        record.plan.mode,
+        keys.len()
     );
-    let result = match mode {
-        OutputMode::Clipboard => ClipboardOutput::paste(text),
-        OutputMode::SimulateKeyboard => SimulateOutput::type_text(text).map(|_| ()),
+    SimulateOutput::press_keys(&keys)?;
+
+    let record = guard.take().expect("checked above");
+    drop(guard);
+    last_output
+        .text
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+
+    let Some(history_id) = record.history_id else {
+        return Ok(());
+    };
+    if reopen {
+        let store = app.state::<AppStore>();
+        store.update_history(&history_id, |r| {
+            r["status"] = serde_json::json!(pending::STATUS_PENDING);
+        });
+        hold_pending_output(
+            app,
+            PendingOutput {
+                session_id: record.session_id,
+                text: record.text,
+                profile: record.profile,
+                history_id,
+                created_at: std::time::Instant::now(),
+            },
+        );
+    } else {
+        let store = app.state::<AppStore>();
+        store.update_history(&history_id, |r| {
+            r["status"] = serde_json::json!(pending::STATUS_UNDONE);
+        });
+    }
+    Ok(())
+}
+
+/// 上一次输出超过该时长后不再作为智能空格的前文
+const SMART_SPACING_CONTEXT_SECS: u64 = 300;
+
+/// 光标前的文本：仅当前台应用未变且上一次输出足够近时使用上一次输出
+/// 无法读取目标应用光标前的字符，切换窗口或间隔过久时按无前文处理
+fn spacing_context(app: &tauri::AppHandle, foreground: Option<&ForegroundApp>) -> String {
+    let last_output = app.state::<LastOutput>();
+    let same_target = last_output
+        .record
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .is_some_and(|t| {
+            t.app.as_ref() == foreground
+                && t.at.elapsed() < std::time::Duration::from_secs(SMART_SPACING_CONTEXT_SECS)
+        });
+    if !same_target {
+        return String::new();
+    }
+    let previous = last_output
+        .text
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    previous
+}
+
+/// 智能空格和大小写：调整第一段文本的开头，调整后为空时移除该段
+fn apply_smart_spacing(segments: &mut Vec<Segment>, previous: &str, asr_language: &str) {
+    let Some(Segment::Text(first)) = segments.first_mut() else {
+        return;
     };
-    if let Err(e) = result {
-        log::error!("[output] output_text_from_store failed: {}", e);
+    let language = SpacingLanguage::from_asr_language(asr_language, first);
+    *first = spacing::apply(previous, first, language);
+    if first.is_empty() {
+        segments.remove(0);
+    }
+}
+
+/// 一次输出使用的方式，由全局设置、快捷键方案和前台应用规则合成
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct OutputPlan {
+    mode: OutputMode,
+    paste_shortcut: PasteShortcut,
+    trailing_action: TrailingAction,
+}
+
+/// 合成输出方式，优先级：前台应用规则 > 快捷键方案 > 全局 outputMode
+/// 应用规则解决的是目标应用的兼容问题，因此优先于快捷键方案
+fn resolve_output_plan(
+    app_settings: &AppSettings,
+    profile: Option<&HotkeyProfile>,
+    foreground: Option<&ForegroundApp>,
+) -> OutputPlan {
+    let rule = foreground.and_then(|app| {
+        app_settings
+            .output_rules
+            .iter()
+            .find(|rule| rule.matches(&app.process_name, &app.window_class))
+    });
+    if let (Some(rule), Some(app)) = (&rule, foreground) {
+        log::info!(
+            "[output] output rule \"{}\" matched {} ({})",
+            rule.app,
+            app.process_name,
+            app.window_class
+        );
+    }
+
+    OutputPlan {
+        mode: rule
+            .as_ref()
+            .and_then(|r| r.output_mode)
+            .or_else(|| profile.and_then(|p| p.output_mode))
+            .unwrap_or(app_settings.output_mode),
+        paste_shortcut: rule
+            .as_ref()
+            .and_then(|r| r.paste_shortcut)
+            .unwrap_or_default(),
+        trailing_action: rule
+            .as_ref()
+            .and_then(|r| r.trailing_action)
+            .unwrap_or_default(),
+    }
+}
+
+/// 读取设置并识别前台应用，得到本次输出方式
+fn current_output_plan(app: &tauri::AppHandle, profile: Option<&HotkeyProfile>) -> OutputPlan {
+    let settings = app.state::<AppStore>().app_settings();
+    let foreground = input::foreground_app();
+    resolve_output_plan(&settings, profile, foreground.as_ref())
+}
+
+/// 按输出方式输出一段文字，返回实际输出的字符数（分段输入被中止时少于全文）
+fn output_with_mode(
+    app: &tauri::AppHandle,
+    session_id: u64,
+    plan: &OutputPlan,
+    text: &str,
+    cancelled: &AtomicBool,
+) -> Result<usize, String> {
+    let char_count = text.chars().count();
+    match plan.mode {
+        OutputMode::Clipboard => {
+            let clipboard = app.state::<AppStore>().app_settings().clipboard;
+            ClipboardOutput::paste(text, &clipboard, plan.paste_shortcut).map(|_| char_count)
+        }
+        OutputMode::SimulateKeyboard => SimulateOutput::type_text(text).map(|r| r.chars_sent),
+        OutputMode::PacedKeyboard => {
+            let typing = app.state::<AppStore>().app_settings().typing;
+            let result = SimulateOutput::type_text_paced(text, &typing, cancelled, |progress| {
+                let _ = app.emit(
+                    "asr-event",
+                    serde_json::json!({
+                        "sessionId": session_id,
+                        "event": {"OutputProgress": progress}
+                    }),
+                );
+            });
+            match result.error {
+                Some(e) => Err(format!(
+                    "Typing failed after {} of {} chars: {}",
+                    result.chars_sent, char_count, e
+                )),
+                None => Ok(result.chars_sent),
+            }
+        }
+        OutputMode::CopyOnly => ClipboardOutput::copy_to_clipboard(text).map(|_| char_count),
+    }
+}
+
+/// 按顺序输出语音命令解析后的片段：文本走当前输出方式，命令转换为按键
+/// 输出过程中按 Esc 取消时停止后续片段，返回实际输出的文本
+fn output_segments(
+    app: &tauri::AppHandle,
+    session_id: u64,
+    plan: &OutputPlan,
+    segments: &[Segment],
+    cancelled: &AtomicBool,
+) -> Result<String, String> {
+    let last_output = app.state::<LastOutput>();
+    let mut output_text = String::new();
+
+    for segment in segments {
+        if cancelled.load(Ordering::SeqCst) {
+            log::info!("[output] session {} cancelled during output", session_id);
+            break;
+        }
+        match segment {
+            Segment::Text(text) => {
+                let chars_sent = output_with_mode(app, session_id, plan, text, cancelled)?;
+                output_text.extend(text.chars().take(chars_sent));
+            }
+            Segment::Action(action) => {
+                let previous = if output_text.is_empty() {
+                    last_output
+                        .text
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .clone()
+                } else {
+                    output_text.clone()
+                };
+                let keys = action.key_actions(&previous);
+                log::info!("[output] voice command {:?} => {:?}", action, keys);
+                SimulateOutput::press_keys(&keys)?;
+
+                if matches!(action, voice_command::CommandAction::DeleteLastSentence) {
+                    let remaining: String = previous
+                        .chars()
+                        .take(previous.chars().count() - keys.len())
+                        .collect();
+                    if output_text.is_empty() {
+                        *last_output.text.lock().unwrap_or_else(|e| e.into_inner()) = remaining;
+                    } else {
+                        output_text = remaining;
+                    }
+                }
+            }
+        }
+    }
+
+    if !output_text.is_empty() {
+        *last_output.text.lock().unwrap_or_else(|e| e.into_inner()) = output_text.clone();
+    }
+    Ok(output_text)
+}
+
+/// 识别完成后是否自动输出，快捷键方案的设置优先于全局设置
+fn auto_output_enabled(app: &tauri::AppHandle, profile: Option<&HotkeyProfile>) -> bool {
+    profile
+        .and_then(|p| p.auto_output)
+        .unwrap_or_else(|| app.state::<AppStore>().app_settings().auto_output)
+}
+
+/// 关闭自动输出时保存识别结果，等待用户在悬浮窗中编辑后确认或丢弃
+/// 超过 app_settings.previewTimeoutSecs（0 表示不超时）未处理时自动丢弃
+fn hold_pending_output(app: &tauri::AppHandle, output: PendingOutput) {
+    let timeout_secs = app.state::<AppStore>().app_settings().preview_timeout_secs;
+    log::info!(
+        "[output] autoOutput is off, holding session {} for confirmation, timeout={}s",
+        output.session_id,
+        timeout_secs
+    );
+    let _ = app.emit(
+        "asr-event",
+        serde_json::json!({
+            "sessionId": output.session_id,
+            "event": {"PendingOutput": {"text": &output.text, "timeoutSecs": timeout_secs}}
+        }),
+    );
+    app.state::<PendingOutputs>().hold(output);
+
+    if timeout_secs == 0 {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let timeout = std::time::Duration::from_secs(timeout_secs);
+        tokio::time::sleep(timeout).await;
+        let expired = app
+            .state::<PendingOutputs>()
+            .take_expired(std::time::Instant::now(), timeout);
+        for output in expired {
+            log::info!(
+                "[output] pending output of session {} timed out",
+                output.session_id
+            );
+            resolve_pending_output(&app, &output, pending::STATUS_DISCARDED);
+        }
+    });
+}
+
+/// 通知前端会话结束；结果仍在等待确认时不发送，预览由 PendingOutputResolved 关闭
+fn emit_session_finished(app: &tauri::AppHandle, session_id: u64) {
+    if app.state::<PendingOutputs>().contains(session_id) {
+        log::info!(
+            "[asr-forward] session {} is waiting for confirmation, keeping preview open",
+            session_id
+        );
+        return;
     }
+    let _ = app.emit(
+        "asr-event",
+        serde_json::json!({
+            "sessionId": session_id,
+            "event": "Finished"
+        }),
+    );
 }
 
-/// 从持久化 store 中读取录音相关设置
-fn load_recording_settings_from_store(app: &tauri::AppHandle) -> Result<RecordingConfig, String> {
+/// 更新待确认输出对应的历史记录状态，并通知前端关闭预览
+fn resolve_pending_output(app: &tauri::AppHandle, output: &PendingOutput, status: &str) {
     let store = app.state::<AppStore>();
+    store.update_history(&output.history_id, |record| {
+        record["status"] = serde_json::json!(status);
+        if status == pending::STATUS_ACCEPTED {
+            record["outputText"] = serde_json::json!(&output.text);
+        }
+    });
+    let _ = app.emit(
+        "asr-event",
+        serde_json::json!({
+            "sessionId": output.session_id,
+            "event": {"PendingOutputResolved": status}
+        }),
+    );
+}
 
-    // 从 asr_settings 读取 ASR 配置
-    let asr_settings = store
-        .settings()
-        .get("asr_settings")
-        .ok_or("No asr_settings found in store")?;
+/// 确认输出：session_id 为空时确认最近一条，text 为用户编辑后的文本
+/// 会阻塞到输出完成，需在后台线程调用
+fn accept_pending_output(
+    app: &tauri::AppHandle,
+    session_id: Option<u64>,
+    text: Option<String>,
+) -> Result<(), String> {
+    let outputs = app.state::<PendingOutputs>();
+    let taken = match session_id {
+        Some(id) => outputs.take(id),
+        None => outputs.take_latest(),
+    };
+    let Some(mut output) = taken else {
+        return Err("没有待确认的输出".to_string());
+    };
+    if let Some(text) = text {
+        output.text = text;
+    }
+    // 编辑前的文本以历史记录为准，预览中的修改会直接写入 output.text
+    let original = app
+        .state::<AppStore>()
+        .find_history(&output.history_id)
+        .and_then(|record| history_output_text(&record).map(str::to_string));
+    log::info!(
+        "[output] pending output of session {} accepted, text_len={}",
+        output.session_id,
+        output.text.len()
+    );
 
-    let selected_provider = asr_settings
-        .get("selectedProvider")
-        .and_then(|v| v.as_str())
-        .unwrap_or("volcengine");
+    // 编辑时悬浮窗可能获得了焦点，先隐藏让焦点回到目标应用
+    if let Some(window) = app.get_webview_window("floating") {
+        if window.is_focused().unwrap_or(false) {
+            let _ = window.hide();
+            std::thread::sleep(std::time::Duration::from_millis(150));
+        }
+    }
 
-    // SAPI 引擎无需额外配置
-    #[cfg(target_os = "windows")]
-    if selected_provider == "sapi" {
-        return Ok(RecordingConfig::Sapi);
+    // 换上新的取消标志：等待确认期间在其他应用按过的 Esc 不影响本次输出，
+    // 输出过程中按 Esc 仍可中止分段输入
+    let cancelled = Arc::new(AtomicBool::new(false));
+    if let Ok(mut flag) = app.state::<Arc<Mutex<RecordingFlag>>>().lock() {
+        if flag.session_id == output.session_id {
+            flag.cancelled = cancelled.clone();
+        }
+    }
+    let result = output_text_from_store(
+        app,
+        output.session_id,
+        &output.text,
+        output.profile.as_ref(),
+        &cancelled,
+    );
+    let aborted = cancelled.load(Ordering::SeqCst);
+    let delivered = match result {
+        Ok(delivered) if !(aborted && delivered.is_empty()) => delivered,
+        result => {
+            // 输出失败，或还没输出任何内容就被取消：保留为待确认，可再次确认或丢弃
+            let error = result.err().unwrap_or_else(|| "输出已取消".to_string());
+            log::info!(
+                "[output] pending output of session {} kept: {}",
+                output.session_id,
+                error
+            );
+            output.created_at = std::time::Instant::now();
+            hold_pending_output(app, output);
+            return Err(error);
+        }
+    };
+    link_last_output_history(app, output.session_id, &output.history_id);
+    if aborted {
+        // 中途取消时只输出了一部分，历史记录保存实际输出的文本，不据此学习纠正
+        output.text = delivered;
+        resolve_pending_output(app, &output, pending::STATUS_ACCEPTED);
+        return Ok(());
     }
+    resolve_pending_output(app, &output, pending::STATUS_ACCEPTED);
+    if let Some(original) = original {
+        learn_corrections(app, &original, &output.text);
+    }
+    Ok(())
+}
 
-    // 从 app_settings 读取麦克风设备
-    let app_settings = store.settings().get("app_settings");
-    let device_name = app_settings
-        .as_ref()
-        .and_then(|s| s.get("microphoneDevice"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
+/// 从用户对输出文本的修改中学习纠正，有新的热词和词典条目时通知前端
+fn learn_corrections(app: &tauri::AppHandle, before: &str, after: &str) {
+    if before == after {
+        return;
+    }
+    let learned = app.state::<AppStore>().learn_corrections(before, after);
+    if learned.is_empty() {
+        return;
+    }
+    log::info!(
+        "[corrections] {} corrections added to hotwords and dictionary",
+        learned.len()
+    );
+    let _ = app.emit("corrections-learned", &learned);
+}
 
-    let providers = asr_settings.get("providers").and_then(|v| v.as_object());
+/// 丢弃待确认输出，会话不存在时返回 false
+fn discard_pending_output(app: &tauri::AppHandle, session_id: u64) -> bool {
+    let Some(output) = app.state::<PendingOutputs>().take(session_id) else {
+        return false;
+    };
+    log::info!(
+        "[output] pending output of session {} discarded",
+        output.session_id
+    );
+    resolve_pending_output(app, &output, pending::STATUS_DISCARDED);
+    true
+}
 
-    let credentials = providers
-        .and_then(|p| p.get(selected_provider))
-        .and_then(|v| v.as_object())
-        .ok_or("未找到所选 ASR 服务商的配置")?;
+/// 从持久化 store 中读取输出配置，直接执行文字输出
+/// 快捷键方案中设置的输出方式优先于全局设置，前台应用规则优先于两者
+/// 返回实际输出的文本（不含语音命令）；按 Esc 中止时可能只有一部分，由调用方检查 cancelled
+fn output_text_from_store(
+    app: &tauri::AppHandle,
+    session_id: u64,
+    text: &str,
+    profile: Option<&HotkeyProfile>,
+    cancelled: &AtomicBool,
+) -> Result<String, String> {
+    let settings = app.state::<AppStore>().app_settings();
 
-    let language = credentials
-        .get("language")
-        .and_then(|v| v.as_str())
-        .unwrap_or("zh")
-        .to_string();
+    let foreground = input::foreground_app();
+    let plan = resolve_output_plan(&settings, profile, foreground.as_ref());
 
-    let auto_punctuation = credentials
-        .get("autoPunctuation")
-        .and_then(|v| v.as_str())
-        .map(|v| v == "true")
-        .unwrap_or(true);
+    let voice_commands = &settings.voice_commands;
 
-    let app_id = credentials
-        .get("appId")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
-    let access_key = credentials
-        .get("accessKey")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
+    log::info!(
+        "[output] output_text_from_store, plan={:?}, text_len={}, voice_commands={}",
+        plan,
+        text.len(),
+        voice_commands.enabled
+    );
+    // 仅复制时不模拟任何按键，语音命令和追加按键都不生效
+    if plan.mode == OutputMode::CopyOnly {
+        return ClipboardOutput::copy_to_clipboard(text)
+            .map(|_| text.to_string())
+            .inspect_err(|e| log::error!("[output] output_text_from_store failed: {}", e));
+    }
+
+    let mut segments = if voice_commands.enabled {
+        CommandGrammar::new(voice_commands).parse(text)
+    } else {
+        vec![Segment::Text(text.to_string())]
+    };
+    if settings.smart_spacing {
+        let previous = spacing_context(app, foreground.as_ref());
+        apply_smart_spacing(&mut segments, &previous, &asr_language(app, profile));
+    }
+
+    let output_text = output_segments(app, session_id, &plan, &segments, cancelled)
+        .inspect_err(|e| log::error!("[output] output_text_from_store failed: {}", e))?;
+    let undoable = plan.trailing_action == TrailingAction::None
+        && segments.iter().all(|s| matches!(s, Segment::Text(_)));
+    *app.state::<LastOutput>()
+        .record
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = Some(OutputRecord {
+        session_id,
+        text: output_text.clone(),
+        plan,
+        app: foreground,
+        at: std::time::Instant::now(),
+        profile: profile.cloned(),
+        history_id: None,
+        undoable,
+    });
+    if cancelled.load(Ordering::SeqCst) {
+        return Ok(output_text);
+    }
+
+    let trailing_keys = match plan.trailing_action {
+        TrailingAction::None => vec![],
+        TrailingAction::Enter => vec![KeyAction::Enter],
+        TrailingAction::Tab => vec![KeyAction::Tab],
+    };
+    if let Err(e) = SimulateOutput::press_keys(&trailing_keys) {
+        log::error!("[output] trailing action failed: {}", e);
+    }
+    Ok(output_text)
+}
+
+/// 当前识别语言（AsrConfig.language），快捷键方案的设置优先，缺省为 "zh"
+fn asr_language(app: &tauri::AppHandle, profile: Option<&HotkeyProfile>) -> String {
+    if let Some(language) = profile
+        .and_then(|p| p.language.as_deref())
+        .filter(|l| !l.is_empty())
+    {
+        return language.to_string();
+    }
+    let asr_settings = app.state::<AppStore>().asr_settings();
+    asr_settings
+        .providers
+        .get(asr_settings.provider_id(profile))
+        .map(|provider| provider.language.clone())
+        .unwrap_or_else(|| "zh".to_string())
+}
+
+/// 从持久化 store 中读取录音相关设置，快捷键方案可覆盖 ASR 服务商和语言
+fn load_recording_settings_from_store(
+    app: &tauri::AppHandle,
+    profile: Option<&HotkeyProfile>,
+) -> Result<RecordingConfig, String> {
+    let store = app.state::<AppStore>();
+    let asr_settings = store.asr_settings();
+    let selected_provider = asr_settings.provider_id(profile);
+
+    // SAPI 引擎无需额外配置
+    #[cfg(target_os = "windows")]
+    if selected_provider == "sapi" {
+        return Ok(RecordingConfig::Sapi);
+    }
+
+    let credentials = asr_settings
+        .providers
+        .get(selected_provider)
+        .ok_or("未找到所选 ASR 服务商的配置")?;
 
-    if app_id.is_empty() || access_key.is_empty() {
+    // Access Key 保存在密钥存储中，迁移失败时 settings.json 中可能仍有明文
+    let access_key = store
+        .secrets()
+        .get(&settings::asr_secret_id(selected_provider))
+        .unwrap_or_else(|| credentials.access_key.clone());
+    if credentials.app_id.is_empty() || access_key.is_empty() {
         return Err("API 配置不完整，请先在设置中填写认证信息".to_string());
     }
 
+    let language = profile
+        .and_then(|p| p.language.as_deref())
+        .filter(|l| !l.is_empty())
+        .unwrap_or(&credentials.language)
+        .to_string();
+
     let asr_config = AsrConfig {
-        app_id,
+        app_id: credentials.app_id.clone(),
         access_key,
         language,
-        auto_punctuation,
+        auto_punctuation: credentials.auto_punctuation,
+        hotwords: asr_settings.hotwords.clone(),
     };
 
     Ok(RecordingConfig::Volcengine {
         asr_config,
-        device_name,
+        device_name: store.app_settings().microphone_device,
     })
 }
 
 /// 从持久化 store 中读取快捷键标签，解析为 HotkeyConfig 列表
 fn load_hotkey_configs_from_store(app: &tauri::AppHandle) -> Option<Vec<HotkeyConfig>> {
-    let store = app.state::<AppStore>();
-    let settings = store.settings().get("app_settings")?;
-    let toggle = settings.get("toggleHotkey")?.as_str()?;
-    let configs = parse_hotkey_configs(toggle);
+    let settings = app.state::<AppStore>().app_settings();
+    let configs = parse_hotkey_configs(&settings);
     if configs.is_empty() {
         None
     } else {
@@ -648,6 +1339,9 @@ async fn polish_and_output(
     app: &tauri::AppHandle,
     session_id: u64,
     text: &str,
+    utterances: &[Utterance],
+    mode: &SessionMode,
+    profile: Option<&HotkeyProfile>,
     cancelled: &Arc<AtomicBool>,
 ) -> bool {
     if cancelled.load(Ordering::SeqCst) {
@@ -658,7 +1352,59 @@ async fn polish_and_output(
         return false;
     }
 
-    let (final_text, polished_text, polish_failed) = match get_polish_config(app) {
+    // 本地规整规则在润色之前执行，润色关闭时同样生效；历史记录保留原始识别文本
+    let asr_text = text;
+    let normalize_settings = app.state::<AppStore>().app_settings().normalize;
+    let normalized = normalize::normalize(text, &normalize_settings);
+    if normalized != text {
+        log::info!(
+            "[asr-forward] session {} normalized, text_len {} -> {}",
+            session_id,
+            text.len(),
+            normalized.len()
+        );
+    }
+    if normalized.is_empty() {
+        log::info!(
+            "[asr-forward] session {} empty after normalization, skipping output",
+            session_id
+        );
+        return false;
+    }
+    let text = normalized.as_str();
+
+    if let SessionMode::Transform { selection } = mode {
+        return transform_and_output(app, session_id, text, selection, cancelled).await;
+    }
+
+    // 翻译模式使用内建翻译提示词，只需要润色服务商，不受润色开关影响
+    let translation = match mode {
+        SessionMode::Translate(target) => Some(target),
+        _ => None,
+    };
+    let llm_config = match translation {
+        Some(_) => {
+            let store = app.state::<AppStore>();
+            get_polish_provider(&store.polish_settings(), store.secrets())
+        }
+        None => get_profile_polish_config(app, profile),
+    };
+    // 没有润色服务商时无法翻译：直接报错，不输出未翻译的原文
+    if translation.is_some() && llm_config.is_none() {
+        let message = "未配置润色服务商，无法翻译";
+        log::error!("[asr-forward] session {} {}", session_id, message);
+        let _ = app.emit(
+            "asr-event",
+            serde_json::json!({
+                "sessionId": session_id,
+                "event": {"Error": message}
+            }),
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        return true;
+    }
+
+    let (final_text, polished_text, polish_failed) = match llm_config {
         Some(config) => {
             // 润色开启：跳过 FinalResult，直接 emit Polishing 携带原文
             let _ = app.emit(
@@ -668,7 +1414,20 @@ async fn polish_and_output(
                     "event": {"Polishing": text}
                 }),
             );
-            match polish::polish_text(&config, text).await {
+            let result = match translation {
+                Some(target) => {
+                    polish::translate_text(
+                        &config,
+                        text,
+                        target.source_language.as_deref(),
+                        &target.target_language,
+                    )
+                    .await
+                }
+                None => polish::polish_text(&config, text).await,
+            };
+            let result = finish_polish(app, &config, result);
+            match result {
                 Ok(polished) => {
                     if cancelled.load(Ordering::SeqCst) {
                         log::info!(
@@ -724,78 +1483,339 @@ async fn polish_and_output(
         return polish_failed;
     }
 
-    output_text_from_store(app, &final_text);
+    let auto_output = auto_output_enabled(app, profile);
+    if auto_output {
+        // 分段输入按节奏 sleep，放到阻塞线程执行，避免占住异步运行时
+        let output_app = app.clone();
+        let output_text = final_text.clone();
+        let output_profile = profile.cloned();
+        let output_cancelled = Arc::clone(cancelled);
+        // 输出失败已在 output_text_from_store 中记录，这里只处理任务本身的错误
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let _ = output_text_from_store(
+                &output_app,
+                session_id,
+                &output_text,
+                output_profile.as_ref(),
+                &output_cancelled,
+            );
+        })
+        .await;
+        if let Err(e) = result {
+            log::error!("[output] session {} output task failed: {}", session_id, e);
+        }
+    }
 
     // 写入历史记录
     let store = app.state::<AppStore>();
     let timestamp = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
-    store.append_history(serde_json::json!({
+    let mut record = serde_json::json!({
         "timestamp": timestamp,
-        "asrText": text,
+        "asrText": asr_text,
         "polishedText": polished_text,
         "outputText": final_text,
-    }));
-
-    let delay = if polish_failed { 3 } else { 1 };
-    tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-    polish_failed
-}
+    });
+    if !utterances.is_empty() {
+        record["utterances"] = serde_json::json!(utterances);
+    }
+    if let Some(target) = translation {
+        record["mode"] = serde_json::json!("translate");
+        record["targetLanguage"] = serde_json::json!(target.target_language);
+    }
+    if !auto_output {
+        record["status"] = serde_json::json!(pending::STATUS_PENDING);
+    }
+    tag_history_record(app, &mut record, profile);
+    if polished_text.is_some() && translation.is_none() {
+        if let Some(prompt_id) = polish_prompt_id(app, profile) {
+            record["promptId"] = serde_json::json!(prompt_id);
+        }
+    }
+    let history_id = store.append_history(record);
+    if auto_output {
+        link_last_output_history(app, session_id, &history_id);
+    } else {
+        hold_pending_output(
+            app,
+            PendingOutput {
+                session_id,
+                text: final_text,
+                profile: profile.cloned(),
+                history_id,
+                created_at: std::time::Instant::now(),
+            },
+        );
+    }
+
+    let delay = if polish_failed { 3 } else { 1 };
+    tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+    polish_failed
+}
+
+/// 选中文本改写流程：识别结果作为指令交给润色模型，结果粘贴回原选区
+/// 返回值：是否改写失败（决定 Finished 前的延迟时长）
+async fn transform_and_output(
+    app: &tauri::AppHandle,
+    session_id: u64,
+    instruction: &str,
+    selection: &str,
+    cancelled: &Arc<AtomicBool>,
+) -> bool {
+    let _ = app.emit(
+        "asr-event",
+        serde_json::json!({
+            "sessionId": session_id,
+            "event": {"Polishing": instruction}
+        }),
+    );
 
-/// 从持久化 store 中读取润色配置，匹配 provider 和 prompt
-fn get_polish_config(app: &tauri::AppHandle) -> Option<polish::PolishConfig> {
     let store = app.state::<AppStore>();
-    let settings = store.settings().get("polish_settings")?;
+    let result = match get_polish_provider(&store.polish_settings(), store.secrets()) {
+        Some(config) => finish_polish(
+            app,
+            &config,
+            polish::transform_text(&config, selection, instruction).await,
+        ),
+        None => Err("未配置润色服务商，无法改写选中文本".to_string()),
+    };
 
-    let enabled = settings.get("enabled")?.as_bool()?;
-    if !enabled {
-        return None;
+    if cancelled.load(Ordering::SeqCst) {
+        log::info!(
+            "[asr-forward] session {} cancelled during transform, skipping output",
+            session_id
+        );
+        return false;
     }
 
-    let selected_provider_id = settings.get("selectedProviderId")?.as_str()?;
-    let selected_prompt_id = settings.get("selectedPromptId")?.as_str()?;
+    let transformed = match result {
+        Ok(transformed) => transformed,
+        Err(e) => {
+            log::error!("[asr-forward] transform failed: {}", e);
+            let _ = app.emit(
+                "asr-event",
+                serde_json::json!({
+                    "sessionId": session_id,
+                    "event": "PolishError"
+                }),
+            );
+            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+            return true;
+        }
+    };
 
-    if selected_provider_id.is_empty() || selected_prompt_id.is_empty() {
-        log::warn!("[polish] enabled but provider or prompt not selected");
-        return None;
+    let _ = app.emit(
+        "asr-event",
+        serde_json::json!({
+            "sessionId": session_id,
+            "event": {"PolishResult": &transformed}
+        }),
+    );
+
+    // 选区仍处于选中状态，直接粘贴即可覆盖原文
+    if let Err(e) = ClipboardOutput::paste(
+        &transformed,
+        &app.state::<AppStore>().app_settings().clipboard,
+        current_output_plan(app, None).paste_shortcut,
+    ) {
+        log::error!("[output] transform paste failed: {}", e);
     }
 
-    let providers = settings.get("providers")?.as_array()?;
-    let provider = providers
-        .iter()
-        .find(|p| p.get("id").and_then(|v| v.as_str()) == Some(selected_provider_id))?;
+    let store = app.state::<AppStore>();
+    let timestamp = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let mut record = serde_json::json!({
+        "timestamp": timestamp,
+        "mode": "transform",
+        "sourceText": selection,
+        "asrText": instruction,
+        "polishedText": &transformed,
+        "outputText": &transformed,
+    });
+    tag_history_record(app, &mut record, None);
+    store.append_history(record);
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    false
+}
+
+/// 为历史记录补充检索字段：目标应用（前台进程名）和 ASR 服务商
+fn tag_history_record(
+    app: &tauri::AppHandle,
+    record: &mut serde_json::Value,
+    profile: Option<&HotkeyProfile>,
+) {
+    if let Some(foreground) = input::foreground_app().filter(|f| !f.process_name.is_empty()) {
+        record["app"] = serde_json::json!(foreground.process_name);
+    }
+    let asr_settings = app.state::<AppStore>().asr_settings();
+    record["engine"] = serde_json::json!(asr_settings.provider_id(profile));
+}
 
-    let prompts = settings.get("prompts")?.as_array()?;
-    let prompt = prompts
+/// 本次听写使用的润色提示词 id，与 get_profile_polish_config 的选择规则一致
+fn polish_prompt_id(app: &tauri::AppHandle, profile: Option<&HotkeyProfile>) -> Option<String> {
+    match profile.map(|p| &p.polish) {
+        Some(ProfilePolish::Off) => None,
+        Some(ProfilePolish::Prompt(prompt_id)) => Some(prompt_id.clone()),
+        Some(ProfilePolish::Global) | None => {
+            Some(app.state::<AppStore>().polish_settings().selected_prompt_id)
+                .filter(|id| !id.is_empty())
+        }
+    }
+}
+
+/// 记录一次识别的用量，并在超出月度预算时提醒
+fn record_asr_usage(app: &tauri::AppHandle, provider: &str, text: &str, duration_ms: Option<i64>) {
+    app.state::<AppStore>()
+        .accumulate_stats(provider, text.chars().count(), duration_ms);
+    notify_budget(app);
+}
+
+/// 记录润色请求的 token 用量并取出结果文本
+fn finish_polish(
+    app: &tauri::AppHandle,
+    config: &polish::PolishConfig,
+    result: Result<polish::Completion, String>,
+) -> Result<String, String> {
+    let completion = result?;
+    app.state::<AppStore>()
+        .record_polish_usage(&config.provider_id, completion.usage);
+    notify_budget(app);
+    Ok(completion.text)
+}
+
+/// 本月估算费用首次超出预算时发送系统通知
+fn notify_budget(app: &tauri::AppHandle) {
+    let store = app.state::<AppStore>();
+    let Some((spent, budget)) = store.check_budget() else {
+        return;
+    };
+    let currency = store.app_settings().cost.currency;
+    log::warn!(
+        "[stats] monthly budget exceeded: {:.2} / {:.2}",
+        spent,
+        budget
+    );
+    let body = format!(
+        "本月估算费用 {}{:.2}，已超出预算 {}{:.2}",
+        currency, spent, currency, budget
+    );
+    let _ = app.emit(
+        "budget-exceeded",
+        serde_json::json!({ "spent": spent, "budget": budget, "currency": currency }),
+    );
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Sayble 用量提醒")
+        .body(body)
+        .show()
+    {
+        log::warn!("[stats] failed to show notification: {}", e);
+    }
+}
+
+/// 启动时有数据文件损坏并已恢复，弹出系统通知；前端通过 cmd_get_store_recoveries 获取详情
+fn notify_store_recovery(app: &tauri::AppHandle) {
+    let recoveries = app.state::<AppStore>().recoveries();
+    if recoveries.is_empty() {
+        return;
+    }
+    let body = recoveries
         .iter()
-        .find(|p| p.get("id").and_then(|v| v.as_str()) == Some(selected_prompt_id))?;
-
-    let base_url = provider.get("baseUrl")?.as_str()?.to_string();
-    let api_key = provider.get("apiKey")?.as_str()?.to_string();
-    let model = provider.get("model")?.as_str()?.to_string();
-    let temperature = provider
-        .get("temperature")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.7);
-    let prompt_content = prompt.get("content")?.as_str()?.to_string();
-
-    if base_url.is_empty() || api_key.is_empty() || model.is_empty() {
+        .map(|r| match r.restored_from {
+            Some(_) => format!("{} 已损坏，已从备份恢复", r.file),
+            None => format!("{} 已损坏且没有可用备份，已重置", r.file),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    log::warn!("[store] recovered corrupt files: {}", body);
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Sayble 数据恢复")
+        .body(body)
+        .show()
+    {
+        log::warn!("[store] failed to show notification: {}", e);
+    }
+}
+
+/// 读取当前选中的润色服务商（不检查润色开关，prompt 留空）
+fn get_polish_provider(
+    polish_settings: &PolishSettings,
+    secrets: &SecretStore,
+) -> Option<polish::PolishConfig> {
+    let selected_provider_id = &polish_settings.selected_provider_id;
+    if selected_provider_id.is_empty() {
+        log::warn!("[polish] provider not selected");
+        return None;
+    }
+
+    let provider = polish_settings.provider(selected_provider_id)?;
+    let api_key = secrets
+        .get(&settings::polish_secret_id(selected_provider_id))
+        .unwrap_or_else(|| provider.api_key.clone());
+    if provider.base_url.is_empty() || api_key.is_empty() || provider.model.is_empty() {
         log::warn!("[polish] provider config incomplete");
         return None;
     }
 
+    Some(polish::PolishConfig {
+        provider_id: selected_provider_id.clone(),
+        base_url: provider.base_url.clone(),
+        api_key,
+        model: provider.model.clone(),
+        prompt: String::new(),
+        temperature: provider.temperature,
+    })
+}
+
+/// 按快捷键方案读取润色配置：跟随全局、不润色或使用指定提示词
+fn get_profile_polish_config(
+    app: &tauri::AppHandle,
+    profile: Option<&HotkeyProfile>,
+) -> Option<polish::PolishConfig> {
+    match profile.map(|p| &p.polish) {
+        Some(ProfilePolish::Off) => None,
+        Some(ProfilePolish::Prompt(prompt_id)) => {
+            let store = app.state::<AppStore>();
+            let settings = store.polish_settings();
+            let mut config = get_polish_provider(&settings, store.secrets())?;
+            config.prompt = settings.prompt_content(prompt_id)?.to_string();
+            log::info!(
+                "[polish] profile config loaded: model={}, prompt_id={}",
+                config.model,
+                prompt_id
+            );
+            Some(config)
+        }
+        Some(ProfilePolish::Global) | None => get_polish_config(app),
+    }
+}
+
+/// 从持久化 store 中读取润色配置，匹配 provider 和 prompt
+fn get_polish_config(app: &tauri::AppHandle) -> Option<polish::PolishConfig> {
+    let store = app.state::<AppStore>();
+    let settings = store.polish_settings();
+    if !settings.enabled {
+        return None;
+    }
+
+    let selected_prompt_id = &settings.selected_prompt_id;
+    if selected_prompt_id.is_empty() {
+        log::warn!("[polish] enabled but prompt not selected");
+        return None;
+    }
+
+    let mut config = get_polish_provider(&settings, store.secrets())?;
+    config.prompt = settings.prompt_content(selected_prompt_id)?.to_string();
+
     log::info!(
         "[polish] config loaded: model={}, temperature={}, prompt_id={}",
-        model,
-        temperature,
+        config.model,
+        config.temperature,
         selected_prompt_id
     );
-    Some(polish::PolishConfig {
-        base_url,
-        api_key,
-        model,
-        prompt: prompt_content,
-        temperature,
-    })
+    Some(config)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -848,13 +1868,9 @@ pub fn run() {
             // 确保 deviceId 存在：首次启动时从 MachineGuid 生成
             {
                 let store = app.state::<AppStore>();
-                let has_device_id = store.settings().get("app_settings")
-                    .and_then(|s| s.get("deviceId").cloned())
-                    .and_then(|v| v.as_str().map(|s| s.to_string()))
-                    .map(|s| !s.is_empty())
-                    .unwrap_or(false);
+                let device_id = store.app_settings().device_id;
 
-                if !has_device_id {
+                if device_id.is_empty() {
                     #[cfg(target_os = "windows")]
                     let guid = get_machine_guid();
                     #[cfg(not(target_os = "windows"))]
@@ -872,22 +1888,18 @@ pub fn run() {
                         log::warn!("[app] failed to obtain device id");
                     }
                 } else {
-                    let id = store.settings().get("app_settings")
-                        .and_then(|s| s.get("deviceId").cloned())
-                        .and_then(|v| v.as_str().map(|s| s.to_string()))
-                        .unwrap_or_default();
+                    let id = device_id;
                     log::info!("[app] device id: {}...", &id[..id.len().min(8)]);
                 }
             }
 
+            notify_store_recovery(&handle);
+
             // 同步自启动状态：用户未开启但系统中启用了，则关闭
             // 用户开启但被第三方禁用的情况，由前端主动调用 cmd_check_autostart 检测并提示
             {
                 let autolaunch = app.autolaunch();
-                let want_enabled = app.state::<AppStore>()
-                    .settings().get("app_settings")
-                    .and_then(|settings| settings.get("autoStart").and_then(|v| v.as_bool()))
-                    .unwrap_or(false);
+                let want_enabled = app.state::<AppStore>().app_settings().auto_start;
                 let currently_enabled = autolaunch.is_enabled().unwrap_or(false);
                 if !want_enabled && currently_enabled {
                     if let Err(e) = autolaunch.disable() {
@@ -932,6 +1944,8 @@ pub fn run() {
                 cancelled: Arc::new(AtomicBool::new(false)),
             }));
             app.manage(recording_flag.clone());
+            app.manage(LastOutput::default());
+            app.manage(PendingOutputs::default());
 
             // SSH 隧道模块：独立管理配置、进程、日志和自动重连
             let tunnel_manager = TunnelManager::init(handle.clone())
@@ -944,15 +1958,36 @@ pub fn run() {
                 .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
             app.manage(share_manager);
 
+            // 本地控制接口：默认关闭，启用后脚本可通过 HTTP 控制录音并订阅 asr-event
+            let control_handle = handle.clone();
+            let control_profile = Mutex::new(None);
+            let control = Arc::new(ControlApi::new(Arc::new(move |request| {
+                handle_control_request(&control_handle, &control_profile, request)
+            })));
+            if let Err(e) = control.apply(&app.state::<AppStore>().app_settings().control) {
+                log::error!("[control] {}", e);
+            }
+            let control_events = Arc::clone(&control);
+            app.listen_any("asr-event", move |event| {
+                control_events.broadcast("asr-event", event.payload());
+            });
+            app.manage(control);
+
             // 后台线程：轮询 HotkeyManager 事件，直接控制录音启停
             // 不再 emit hotkey-event 给前端，彻底绕过 WebView
             let hotkey_handle = handle.clone();
             let hotkey_flag = recording_flag.clone();
+            let modifiers_held = manager.lock().unwrap_or_else(|e| e.into_inner()).modifiers_held();
             log::info!("[hotkey] manager running: {}", manager.lock().map(|m| m.is_running()).unwrap_or(false));
             std::thread::spawn(move || {
                 log::info!("[hotkey-forward] thread started (backend-driven mode)");
                 let mut recording_start_time: Option<std::time::Instant> = None;
                 const MIN_RECORDING_MS: u128 = 800;
+                // 按住说话：松开时间不足最短录音时长视为点按，退化为切换
+                let mut push_to_talk = hotkey::PushToTalk::new(
+                    std::time::Duration::from_millis(MIN_RECORDING_MS as u64),
+                );
+                let mut hold_pressed = false;
 
                 loop {
                     let event = {
@@ -967,26 +2002,90 @@ pub fn run() {
                         use hotkey::HotkeyEvent;
                         // Toggle 时读后端真实录音状态，转换为具体指令
                         let event = match event {
-                            HotkeyEvent::ToggleRecording => {
+                            HotkeyEvent::HoldPressed => {
+                                // 紧随其后的 Toggle 事件来自按住说话快捷键
+                                hold_pressed = true;
+                                continue;
+                            }
+                            HotkeyEvent::HoldReleased => {
                                 let is_rec = hotkey_flag.lock()
                                     .map(|f| f.is_recording)
                                     .unwrap_or(false);
-                                if is_rec {
+                                if push_to_talk.on_release(std::time::Instant::now()) && is_rec {
+                                    log::info!("[hotkey-forward] hold released, stopping recording");
                                     HotkeyEvent::StopRecording
                                 } else {
-                                    HotkeyEvent::StartRecording
+                                    continue;
+                                }
+                            }
+                            HotkeyEvent::ToggleRecording
+                            | HotkeyEvent::ToggleTransform
+                            | HotkeyEvent::ToggleTranslate(_)
+                            | HotkeyEvent::ToggleProfile(_) => {
+                                let is_rec = hotkey_flag.lock()
+                                    .map(|f| f.is_recording)
+                                    .unwrap_or(false);
+                                if std::mem::take(&mut hold_pressed) {
+                                    push_to_talk.on_press(std::time::Instant::now(), !is_rec);
+                                } else {
+                                    push_to_talk.reset();
+                                }
+                                match event {
+                                    _ if is_rec => HotkeyEvent::StopRecording,
+                                    HotkeyEvent::ToggleTransform => HotkeyEvent::StartTransform,
+                                    HotkeyEvent::ToggleTranslate(target) => {
+                                        HotkeyEvent::StartTranslate(target)
+                                    }
+                                    HotkeyEvent::ToggleProfile(id) => HotkeyEvent::StartProfile(id),
+                                    _ => HotkeyEvent::StartRecording,
                                 }
                             }
                             other => other,
                         };
 
                         match event {
-                            HotkeyEvent::StartRecording => {
-                                log::info!("[hotkey-forward] StartRecording: reading settings from store");
-                                match load_recording_settings_from_store(&hotkey_handle) {
+                            HotkeyEvent::StartRecording
+                            | HotkeyEvent::StartTransform
+                            | HotkeyEvent::StartTranslate(_)
+                            | HotkeyEvent::StartProfile(_) => {
+                                log::info!("[hotkey-forward] {:?}: reading settings from store", event);
+                                // 方案快捷键：按方案中的动作决定会话模式
+                                let (action, translation, profile) = match event {
+                                    HotkeyEvent::StartTransform => (HotkeyAction::TransformSelection, None, None),
+                                    HotkeyEvent::StartTranslate(target) => (HotkeyAction::Translate, Some(target), None),
+                                    HotkeyEvent::StartProfile(id) => match find_hotkey_profile(&hotkey_handle, &id) {
+                                        Some(profile) => (profile.action, profile.translation.clone(), Some(profile)),
+                                        None => {
+                                            log::warn!("[hotkey-forward] hotkey profile {} not found", id);
+                                            continue;
+                                        }
+                                    },
+                                    _ => (HotkeyAction::ToggleRecording, None, None),
+                                };
+                                let session_mode = match (action, translation) {
+                                    (HotkeyAction::TransformSelection, _) => {
+                                        match ClipboardOutput::copy_selection(|| modifiers_held.load(Ordering::SeqCst)) {
+                                            Ok(selection) if !selection.trim().is_empty() => {
+                                                SessionMode::Transform { selection }
+                                            }
+                                            Ok(_) => {
+                                                log::warn!("[hotkey-forward] no text selected, transform ignored");
+                                                continue;
+                                            }
+                                            Err(e) => {
+                                                log::error!("[hotkey-forward] copy selection failed: {}", e);
+                                                continue;
+                                            }
+                                        }
+                                    }
+                                    (HotkeyAction::Translate, Some(target)) => SessionMode::Translate(target),
+                                    _ => SessionMode::Dictation,
+                                };
+                                match load_recording_settings_from_store(&hotkey_handle, profile.as_ref()) {
                                     Ok(recording_config) => {
                                         // 直接调用录音启动
-                                        match start_recording_inner(&hotkey_handle, &hotkey_flag, recording_config) {
+                                        let profile_id = profile.map(|p| p.id);
+                                        match start_recording_inner(&hotkey_handle, &hotkey_flag, recording_config, session_mode, profile_id) {
                                             Ok(session_id) => {
                                                 recording_start_time = Some(std::time::Instant::now());
                                                 let _ = hotkey_handle.emit("floating-control", serde_json::json!({
@@ -1034,8 +2133,34 @@ pub fn run() {
                                 }
                             }
 
+                            HotkeyEvent::AcceptOutput => {
+                                let app = hotkey_handle.clone();
+                                tauri::async_runtime::spawn_blocking(move || {
+                                    if let Err(e) = accept_pending_output(&app, None, None) {
+                                        log::info!("[hotkey-forward] accept output ignored: {}", e);
+                                    }
+                                });
+                            }
+
+                            HotkeyEvent::UndoOutput => {
+                                let app = hotkey_handle.clone();
+                                let modifiers_held = Arc::clone(&modifiers_held);
+                                tauri::async_runtime::spawn_blocking(move || {
+                                    // 等待快捷键松开，避免按住的修饰键与退格组合成其他快捷键
+                                    if !SimulateOutput::wait_modifiers_released(|| modifiers_held.load(Ordering::SeqCst)) {
+                                        log::warn!("[hotkey-forward] modifiers still held, undoing output anyway");
+                                    }
+                                    let reopen = app.state::<AppStore>().app_settings().undo_reopens_preview;
+                                    if let Err(e) = undo_last_output(&app, reopen) {
+                                        log::info!("[hotkey-forward] undo output ignored: {}", e);
+                                    }
+                                });
+                            }
+
                             HotkeyEvent::CancelRecording => {
+                                // 待确认的输出只在预览窗口获得焦点时由前端处理 Esc，这里不丢弃
                                 recording_start_time = None;
+                                push_to_talk.reset();
                                 let session_id = cancel_recording_inner(&hotkey_flag).ok().flatten();
                                 let _ = hotkey_handle.emit("floating-control", serde_json::json!({
                                     "action": "cancel",
@@ -1044,8 +2169,13 @@ pub fn run() {
                                 log::info!("[hotkey-forward] recording cancelled");
                             }
 
-                            HotkeyEvent::ToggleRecording => {
-                                unreachable!("ToggleRecording should have been converted above");
+                            HotkeyEvent::ToggleRecording
+                            | HotkeyEvent::ToggleTransform
+                            | HotkeyEvent::ToggleTranslate(_)
+                            | HotkeyEvent::ToggleProfile(_)
+                            | HotkeyEvent::HoldPressed
+                            | HotkeyEvent::HoldReleased => {
+                                unreachable!("toggle events should have been converted above");
                             }
                         }
                     } else {
@@ -1079,12 +2209,28 @@ pub fn run() {
             cmd_restore_autostart,
             cmd_check_autostart,
             cmd_get_data_dir,
+            cmd_move_data_dir,
+            cmd_get_control_status,
+            cmd_export_backup,
+            cmd_import_backup,
             cmd_save_recording_file,
+            cmd_get_store_recoveries,
             cmd_load_history,
+            cmd_search_history,
+            cmd_export_history,
+            cmd_list_corrections,
+            cmd_remove_correction,
             cmd_clear_history,
             cmd_remove_history,
             cmd_check_update,
             cmd_inject_key_event,
+            cmd_check_output_capabilities,
+            cmd_get_pending_outputs,
+            cmd_update_pending_output,
+            cmd_accept_pending_output,
+            cmd_discard_pending_output,
+            cmd_undo_last_output,
+            cmd_output_history_record,
             cmd_show_floating_window,
             live_window::cmd_open_live_window,
             live_window::cmd_live_window_navigate,
@@ -1140,12 +2286,7 @@ fn cmd_restore_autostart(app: tauri::AppHandle) -> Result<(), String> {
 fn cmd_check_autostart(app: tauri::AppHandle) -> Result<Option<String>, String> {
     log::info!("[cmd] check_autostart called");
     let autolaunch = app.autolaunch();
-    let want_enabled = app
-        .state::<AppStore>()
-        .settings()
-        .get("app_settings")
-        .and_then(|settings| settings.get("autoStart").and_then(|v| v.as_bool()))
-        .unwrap_or(false);
+    let want_enabled = app.state::<AppStore>().app_settings().auto_start;
     let currently_enabled = autolaunch.is_enabled().unwrap_or(false);
     log::info!(
         "[autostart] check: want_enabled={}, currently_enabled={}",
@@ -1254,27 +2395,44 @@ fn cmd_save_settings(
     let store = app.state::<AppStore>();
     let settings_store = store.settings();
 
-    // 按 key 写入 store
-    if let Some(obj) = settings.as_object() {
-        for (k, v) in obj {
-            settings_store.set(k, v.clone());
-        }
+    // 整体校验通过后才写入，任一字段无效时不保存并返回全部错误
+    let obj = settings.as_object().ok_or("设置格式错误")?;
+    settings::validate(obj).map_err(|errors| {
+        log::warn!("[settings] rejected save: {:?}", errors);
+        errors.join("\n")
+    })?;
+
+    // 密钥写入密钥存储，settings.json 中不保留明文
+    let mut obj = obj.clone();
+    for (id, value) in settings::take_secrets(&mut obj) {
+        store
+            .secrets()
+            .set(&id, &value)
+            .map_err(|e| format!("保存密钥失败: {}", e))?;
     }
+
+    // 按 key 写入 store，版本号由后端维护
+    for (k, v) in obj.into_iter().filter(|(k, _)| k != settings::VERSION_KEY) {
+        settings_store.set(&k, v);
+    }
+    settings_store.set(
+        settings::VERSION_KEY,
+        serde_json::json!(settings::SETTINGS_VERSION),
+    );
     settings_store
         .save()
         .map_err(|e| format!("Failed to save store: {}", e))?;
 
-    // 从 app_settings 提取副作用字段
-    let app_settings = settings
-        .get("app_settings")
-        .unwrap_or(&serde_json::Value::Null);
+    apply_saved_settings(&app, &hotkey_mgr);
+    Ok(())
+}
+
+/// 设置写入后同步副作用：快捷键配置和自启动状态
+fn apply_saved_settings(app: &tauri::AppHandle, hotkey_mgr: &Mutex<HotkeyManager>) {
+    let app_settings = app.state::<AppStore>().app_settings();
 
     // 同步快捷键配置到 HotkeyManager
-    let toggle = app_settings
-        .get("toggleHotkey")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let configs = parse_hotkey_configs(toggle);
+    let configs = parse_hotkey_configs(&app_settings);
     if !configs.is_empty() {
         if let Ok(mgr) = hotkey_mgr.lock() {
             log::info!("[hotkey] updating configs on save: {:?}", configs);
@@ -1282,13 +2440,14 @@ fn cmd_save_settings(
         }
     }
 
+    // 同步本地控制接口
+    if let Err(e) = app.state::<Arc<ControlApi>>().apply(&app_settings.control) {
+        log::error!("[control] {}", e);
+    }
+
     // 同步自启动状态
-    let auto_start = app_settings
-        .get("autoStart")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
     let autolaunch = app.autolaunch();
-    if auto_start {
+    if app_settings.auto_start {
         if let Err(e) = autolaunch.enable() {
             log::error!("[autostart] failed to enable: {}", e);
         } else {
@@ -1304,8 +2463,6 @@ fn cmd_save_settings(
             }
         }
     }
-
-    Ok(())
 }
 
 #[tauri::command]
@@ -1313,8 +2470,12 @@ fn cmd_load_settings(app: tauri::AppHandle) -> Result<serde_json::Value, String>
     let store = app.state::<AppStore>();
     let mut map = serde_json::Map::new();
     for (k, v) in store.settings().entries() {
-        map.insert(k, v);
+        if k != settings::VERSION_KEY {
+            map.insert(k, v);
+        }
     }
+    // 修正无法通过保存校验的内容，使前端原样保存时不会被拒绝
+    settings::repair(&mut map);
 
     // 确保内建零配置引擎（如 sapi）在 providers 中有条目
     if let Some(asr) = map.get_mut("asr_settings").and_then(|v| v.as_object_mut()) {
@@ -1324,6 +2485,7 @@ fn cmd_load_settings(app: tauri::AppHandle) -> Result<serde_json::Value, String>
                 .or_insert_with(|| serde_json::json!({}));
         }
     }
+    settings::fill_secrets(&mut map, |id| store.secrets().get(id));
 
     Ok(serde_json::Value::Object(map))
 }
@@ -1333,8 +2495,17 @@ fn start_recording_inner(
     app: &tauri::AppHandle,
     flag: &Arc<Mutex<RecordingFlag>>,
     config: RecordingConfig,
+    mode: SessionMode,
+    profile_id: Option<String>,
 ) -> Result<u64, String> {
-    log::info!("[recording] start_recording_inner called");
+    log::info!(
+        "[recording] start_recording_inner called, transform={}, profile={:?}",
+        matches!(mode, SessionMode::Transform { .. }),
+        profile_id
+    );
+    let profile = profile_id
+        .as_deref()
+        .and_then(|id| find_hotkey_profile(app, id));
     // 如果上一次录音还没结束，先强制停掉旧会话
     {
         let mut f = flag.lock().map_err(|e| e.to_string())?;
@@ -1380,6 +2551,7 @@ fn start_recording_inner(
     let is_streaming_engine = matches!(&config, RecordingConfig::Sapi);
     #[cfg(not(target_os = "windows"))]
     let is_streaming_engine = false;
+    let asr_provider = config.provider_id();
 
     match config {
         RecordingConfig::Volcengine {
@@ -1538,11 +2710,15 @@ fn start_recording_inner(
             session_id,
             is_streaming_engine
         );
+        // 流式引擎不返回音频时长，按会话时长统计
+        let session_started = std::time::Instant::now();
         let mut last_partial_text = String::new();
         let mut had_final = false;
         let mut terminated = false;
         // 流式引擎（SAPI）累积已确认的句子文本，等 Disconnected 时统一输出
         let mut accumulated_text = String::new();
+        // 火山引擎在最终结果前发送带时间的分句，随历史记录保存用于导出字幕
+        let mut utterances: Vec<Utterance> = Vec::new();
         loop {
             match event_rx.try_recv() {
                 Ok(event) => {
@@ -1613,23 +2789,26 @@ fn start_recording_inner(
                                         session_id
                                     );
                                 } else {
-                                    app_clone
-                                        .state::<AppStore>()
-                                        .accumulate_stats(text.chars().count(), *duration_ms);
-                                    polish_and_output(&app_clone, session_id, text, &cancelled)
-                                        .await;
+                                    record_asr_usage(&app_clone, asr_provider, text, *duration_ms);
+                                    polish_and_output(
+                                        &app_clone,
+                                        session_id,
+                                        text,
+                                        &utterances,
+                                        &mode,
+                                        profile.as_ref(),
+                                        &cancelled,
+                                    )
+                                    .await;
                                 }
-                                let _ = app_clone.emit(
-                                    "asr-event",
-                                    serde_json::json!({
-                                        "sessionId": session_id,
-                                        "event": "Finished"
-                                    }),
-                                );
+                                emit_session_finished(&app_clone, session_id);
                                 terminated = true;
                                 break;
                             }
                         }
+                        AsrEvent::Utterances(items) => {
+                            utterances = items.clone();
+                        }
                         AsrEvent::Error(_) => {
                             if !cancelled.load(Ordering::SeqCst) {
                                 let _ = app_clone.emit(
@@ -1641,13 +2820,7 @@ fn start_recording_inner(
                                 );
                                 tokio::time::sleep(std::time::Duration::from_secs(3)).await;
                             }
-                            let _ = app_clone.emit(
-                                "asr-event",
-                                serde_json::json!({
-                                    "sessionId": session_id,
-                                    "event": "Finished"
-                                }),
-                            );
+                            emit_session_finished(&app_clone, session_id);
                             terminated = true;
                             break;
                         }
@@ -1658,25 +2831,37 @@ fn start_recording_inner(
                             } else if is_streaming_engine && !accumulated_text.is_empty() {
                                 // 流式引擎：Disconnected 时统一输出累积文本
                                 log::info!("[asr-forward] session {} streaming disconnect, outputting accumulated text len={}", session_id, accumulated_text.len());
-                                app_clone
-                                    .state::<AppStore>()
-                                    .accumulate_stats(accumulated_text.chars().count(), None);
+                                record_asr_usage(
+                                    &app_clone,
+                                    asr_provider,
+                                    &accumulated_text,
+                                    Some(session_started.elapsed().as_millis() as i64),
+                                );
                                 polish_and_output(
                                     &app_clone,
                                     session_id,
                                     &accumulated_text,
+                                    &utterances,
+                                    &mode,
+                                    profile.as_ref(),
                                     &cancelled,
                                 )
                                 .await;
                             } else if !had_final {
                                 if !last_partial_text.is_empty() {
-                                    app_clone
-                                        .state::<AppStore>()
-                                        .accumulate_stats(last_partial_text.chars().count(), None);
+                                    record_asr_usage(
+                                        &app_clone,
+                                        asr_provider,
+                                        &last_partial_text,
+                                        Some(session_started.elapsed().as_millis() as i64),
+                                    );
                                     polish_and_output(
                                         &app_clone,
                                         session_id,
                                         &last_partial_text,
+                                        &utterances,
+                                        &mode,
+                                        profile.as_ref(),
                                         &cancelled,
                                     )
                                     .await;
@@ -1684,13 +2869,7 @@ fn start_recording_inner(
                             } else {
                                 tokio::time::sleep(std::time::Duration::from_secs(1)).await;
                             }
-                            let _ = app_clone.emit(
-                                "asr-event",
-                                serde_json::json!({
-                                    "sessionId": session_id,
-                                    "event": "Finished"
-                                }),
-                            );
+                            emit_session_finished(&app_clone, session_id);
                             terminated = true;
                             break;
                         }
@@ -1717,22 +2896,28 @@ fn start_recording_inner(
                     session_id,
                     accumulated_text.len()
                 );
-                app_clone
-                    .state::<AppStore>()
-                    .accumulate_stats(accumulated_text.chars().count(), None);
-                polish_and_output(&app_clone, session_id, &accumulated_text, &cancelled).await;
+                record_asr_usage(
+                    &app_clone,
+                    asr_provider,
+                    &accumulated_text,
+                    Some(session_started.elapsed().as_millis() as i64),
+                );
+                polish_and_output(
+                    &app_clone,
+                    session_id,
+                    &accumulated_text,
+                    &utterances,
+                    &mode,
+                    profile.as_ref(),
+                    &cancelled,
+                )
+                .await;
             }
             log::warn!(
                 "[asr-forward] session {} channel ended without terminal event, sending Finished",
                 session_id
             );
-            let _ = app_clone.emit(
-                "asr-event",
-                serde_json::json!({
-                    "sessionId": session_id,
-                    "event": "Finished"
-                }),
-            );
+            emit_session_finished(&app_clone, session_id);
         }
     });
 
@@ -1747,7 +2932,7 @@ fn start_recording_inner(
         );
     }
 
-    if let Err(e) = show_floating_window(app) {
+    if let Err(e) = show_floating_window(app, false) {
         log::warn!("[floating] failed to show on recording start: {}", e);
     }
 
@@ -1760,8 +2945,8 @@ async fn cmd_start_recording(
     flag: tauri::State<'_, Arc<Mutex<RecordingFlag>>>,
 ) -> Result<(), String> {
     log::info!("[cmd] start_recording called");
-    let recording_config = load_recording_settings_from_store(&app)?;
-    start_recording_inner(&app, &flag, recording_config)?;
+    let recording_config = load_recording_settings_from_store(&app, None)?;
+    start_recording_inner(&app, &flag, recording_config, SessionMode::Dictation, None)?;
     Ok(())
 }
 
@@ -1823,32 +3008,373 @@ fn cmd_cancel_recording(
     cancel_recording_inner(&flag)
 }
 
+/// 执行本地控制接口的请求，录音相关请求与前端命令和快捷键调用相同的内部逻辑
+fn handle_control_request(
+    app: &tauri::AppHandle,
+    active_profile: &Mutex<Option<String>>,
+    request: ControlRequest,
+) -> Result<serde_json::Value, String> {
+    let flag = app.state::<Arc<Mutex<RecordingFlag>>>();
+    match request {
+        ControlRequest::State => {
+            let f = flag.lock().map_err(|e| e.to_string())?;
+            let profile = active_profile.lock().unwrap_or_else(|e| e.into_inner());
+            Ok(serde_json::json!({
+                "recording": f.is_recording,
+                "sessionId": f.session_id,
+                "profile": *profile,
+            }))
+        }
+        ControlRequest::Start { profile } => {
+            let profile_id = profile.or_else(|| {
+                active_profile
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone()
+            });
+            let profile = control_profile(app, profile_id.as_deref())?;
+            let mode = profile_session_mode(profile.as_ref())?;
+            let recording_config = load_recording_settings_from_store(app, profile.as_ref())?;
+            let session_id =
+                start_recording_inner(app, &flag, recording_config, mode, profile.map(|p| p.id))?;
+            let _ = app.emit(
+                "floating-control",
+                serde_json::json!({ "action": "start", "sessionId": session_id }),
+            );
+            Ok(serde_json::json!({ "sessionId": session_id }))
+        }
+        ControlRequest::Stop => {
+            let session_id = stop_recording_inner(&flag)?;
+            let _ = app.emit(
+                "floating-control",
+                serde_json::json!({ "action": "stop", "sessionId": session_id }),
+            );
+            Ok(serde_json::json!({ "sessionId": session_id }))
+        }
+        ControlRequest::Cancel => {
+            let session_id = cancel_recording_inner(&flag)?;
+            let _ = app.emit(
+                "floating-control",
+                serde_json::json!({ "action": "cancel", "sessionId": session_id }),
+            );
+            Ok(serde_json::json!({ "sessionId": session_id }))
+        }
+        ControlRequest::LastResult => {
+            let records = app.state::<AppStore>().search_history(&HistoryQuery {
+                limit: Some(1),
+                ..Default::default()
+            })?;
+            Ok(records.into_iter().next().unwrap_or_default())
+        }
+        ControlRequest::SetProfile(id) => {
+            control_profile(app, id.as_deref())?;
+            *active_profile.lock().unwrap_or_else(|e| e.into_inner()) = id.clone();
+            Ok(serde_json::json!({ "profile": id }))
+        }
+        ControlRequest::Transcribe { path, profile } => {
+            let profile = control_profile(app, profile.as_deref())?;
+            transcribe_file(app, &path, profile.as_ref())
+        }
+    }
+}
+
+fn control_profile(
+    app: &tauri::AppHandle,
+    id: Option<&str>,
+) -> Result<Option<HotkeyProfile>, String> {
+    match id {
+        Some(id) => find_hotkey_profile(app, id)
+            .map(Some)
+            .ok_or_else(|| format!("未找到方案「{}」", id)),
+        None => Ok(None),
+    }
+}
+
+/// 按方案的动作决定会话模式，与方案快捷键一致
+fn profile_session_mode(profile: Option<&HotkeyProfile>) -> Result<SessionMode, String> {
+    match profile.map(|p| (p.action, p.translation.clone())) {
+        Some((HotkeyAction::TransformSelection, _)) => {
+            match ClipboardOutput::copy_selection(|| false) {
+                Ok(selection) if !selection.trim().is_empty() => {
+                    Ok(SessionMode::Transform { selection })
+                }
+                Ok(_) => Err("没有选中文本，无法改写".to_string()),
+                Err(e) => Err(format!("复制选中文本失败: {}", e)),
+            }
+        }
+        Some((HotkeyAction::Translate, Some(target))) => Ok(SessionMode::Translate(target)),
+        _ => Ok(SessionMode::Dictation),
+    }
+}
+
+/// 转写 WAV 文件，返回识别文本和分句；结果不输出到焦点应用，也不写入历史
+fn transcribe_file(
+    app: &tauri::AppHandle,
+    path: &std::path::Path,
+    profile: Option<&HotkeyProfile>,
+) -> Result<serde_json::Value, String> {
+    let asr_config = match load_recording_settings_from_store(app, profile)? {
+        RecordingConfig::Volcengine { asr_config, .. } => asr_config,
+        #[cfg(target_os = "windows")]
+        RecordingConfig::Sapi => return Err("系统语音识别不支持文件转写".to_string()),
+    };
+    let samples = audio::wav::read_wav(path)?;
+    if samples.is_empty() {
+        return Err("音频文件没有内容".to_string());
+    }
+    let audio_ms = (samples.len() * 1000 / TRANSCRIBE_SAMPLE_RATE) as i64;
+    log::info!("[transcribe] {} ({} ms of audio)", path.display(), audio_ms);
+
+    let (event_tx, event_rx) = std::sync::mpsc::channel::<AsrEvent>();
+    let (audio_tx, audio_rx) = tokio::sync::mpsc::channel::<Vec<i16>>(100);
+    let is_running = Arc::new(Mutex::new(true));
+    let is_running_clone = is_running.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = asr::volcengine::run_asr_session(
+            asr_config,
+            event_tx.clone(),
+            audio_rx,
+            is_running_clone,
+        )
+        .await
+        {
+            let _ = event_tx.send(AsrEvent::Error(e));
+        }
+    });
+    // 按音频时长的节奏分块发送，与麦克风录音时的发送方式一致
+    tauri::async_runtime::spawn(async move {
+        for chunk in samples.chunks(TRANSCRIBE_CHUNK_MS as usize * TRANSCRIBE_SAMPLE_RATE / 1000) {
+            if audio_tx.send(chunk.to_vec()).await.is_err() {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(TRANSCRIBE_CHUNK_MS)).await;
+        }
+    });
+
+    let deadline = std::time::Instant::now()
+        + std::time::Duration::from_millis(audio_ms as u64)
+        + std::time::Duration::from_secs(TRANSCRIBE_RESULT_TIMEOUT_SECS);
+    let mut utterances = Vec::new();
+    let result = loop {
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        match event_rx.recv_timeout(remaining) {
+            Ok(AsrEvent::Utterances(items)) => utterances = items,
+            Ok(AsrEvent::FinalResult(text, duration_ms)) => {
+                let duration_ms = duration_ms.unwrap_or(audio_ms);
+                record_asr_usage(app, "volcengine", &text, Some(duration_ms));
+                break Ok(serde_json::json!({
+                    "text": text,
+                    "utterances": utterances,
+                    "durationMs": duration_ms,
+                }));
+            }
+            Ok(AsrEvent::Error(e)) => break Err(format!("识别失败: {}", e)),
+            Ok(AsrEvent::Disconnected) | Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                break Err("识别连接已断开，未收到结果".to_string())
+            }
+            Ok(_) => {}
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                break Err("等待识别结果超时".to_string())
+            }
+        }
+    };
+    *is_running.lock().unwrap_or_else(|e| e.into_inner()) = false;
+    result
+}
+
 #[tauri::command]
-fn cmd_show_floating_window(app: tauri::AppHandle) -> Result<(), String> {
-    log::debug!("[cmd] show_floating_window called");
-    show_floating_window(&app)
+fn cmd_get_control_status(control: tauri::State<'_, Arc<ControlApi>>) -> ControlStatus {
+    control.status()
 }
 
 #[tauri::command]
-fn cmd_load_stats(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+fn cmd_show_floating_window(app: tauri::AppHandle, preview: Option<bool>) -> Result<(), String> {
+    log::debug!("[cmd] show_floating_window called, preview={:?}", preview);
+    show_floating_window(&app, preview.unwrap_or(false))
+}
+
+/// 读取累计统计，以及 [from, to]（YYYY-MM-DD，可省略）范围内按天、按服务商的用量与估算费用
+#[tauri::command]
+fn cmd_load_stats(
+    app: tauri::AppHandle,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<serde_json::Value, String> {
     let store = app.state::<AppStore>();
     let stats = store.stats();
+    let report = store.usage_report(from.as_deref(), to.as_deref());
     Ok(serde_json::json!({
         "totalDurationMs": stats.get("total_duration_ms").and_then(|v| v.as_i64()).unwrap_or(0),
         "totalChars": stats.get("total_chars").and_then(|v| v.as_i64()).unwrap_or(0),
         "totalCount": stats.get("total_count").and_then(|v| v.as_i64()).unwrap_or(0),
+        "usage": report,
     }))
 }
 
+/// 导出备份到 path；未设置密码时不导出密钥
+#[tauri::command]
+fn cmd_export_backup(
+    app: tauri::AppHandle,
+    tunnel_manager: tauri::State<'_, Arc<TunnelManager>>,
+    share_manager: tauri::State<'_, Arc<ShareManager>>,
+    path: String,
+    include_history: bool,
+    password: Option<String>,
+) -> Result<(), String> {
+    let store = app.state::<AppStore>();
+    let mut settings: serde_json::Map<String, serde_json::Value> =
+        store.settings().entries().into_iter().collect();
+    settings::fill_secrets(&mut settings, |id| store.secrets().get(id));
+    let history = include_history.then(|| store.load_history());
+    let backup = Backup::new(
+        settings,
+        password.as_deref(),
+        tunnel_manager.list_tunnels(),
+        share_manager.export_settings(),
+        history,
+    )?;
+
+    let json =
+        serde_json::to_string_pretty(&backup).map_err(|e| format!("序列化备份失败: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("保存备份失败: {}", e))?;
+    log::info!(
+        "[backup] exported to {} (secrets: {}, history: {})",
+        path,
+        backup.secrets.is_some(),
+        backup.history.as_ref().map_or(0, |h| h.len())
+    );
+    Ok(())
+}
+
+/// 从备份导入，设置校验全部通过后才写入任何数据
+#[tauri::command]
+fn cmd_import_backup(
+    app: tauri::AppHandle,
+    hotkey_mgr: tauri::State<'_, Arc<Mutex<HotkeyManager>>>,
+    tunnel_manager: tauri::State<'_, Arc<TunnelManager>>,
+    share_manager: tauri::State<'_, Arc<ShareManager>>,
+    path: String,
+    mode: ImportMode,
+    password: Option<String>,
+) -> Result<ImportSummary, String> {
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("读取备份失败: {}", e))?;
+    let mut backup = Backup::parse(&content)?;
+    let secrets = backup.open_secrets(password.as_deref())?;
+    let replace = mode == ImportMode::Replace;
+
+    let store = app.state::<AppStore>();
+    let settings_store = store.settings();
+    let mut merged: serde_json::Map<String, serde_json::Value> =
+        settings_store.entries().into_iter().collect();
+    backup::merge_settings(&mut merged, backup.settings, mode);
+    // 先校验全部内容，任何一项无效都不写入
+    settings::validate(&merged).map_err(|errors| errors.join("\n"))?;
+    TunnelManager::check_import(&mut backup.tunnels)?;
+    if let Some(share_settings) = &backup.share_settings {
+        ShareManager::check_import(share_settings)?;
+    }
+
+    let mut summary = ImportSummary {
+        secrets_skipped: secrets.is_none(),
+        ..Default::default()
+    };
+    for (id, value) in secrets.unwrap_or_default() {
+        store
+            .secrets()
+            .set(&id, &value)
+            .map_err(|e| format!("保存密钥失败: {}", e))?;
+        summary.secrets += 1;
+    }
+
+    if replace {
+        for (key, _) in settings_store.entries() {
+            if !merged.contains_key(&key) {
+                settings_store.remove(&key);
+            }
+        }
+    }
+    for (key, value) in merged {
+        settings_store.set(&key, value);
+    }
+    settings_store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    apply_saved_settings(&app, &hotkey_mgr);
+
+    summary.tunnels = tunnel_manager.import_tunnels(backup.tunnels, replace)?;
+    if let Some(share_settings) = backup.share_settings {
+        share_manager.import_settings(share_settings, replace)?;
+    }
+    if let Some(history) = backup.history {
+        summary.history = store
+            .import_history(&history, replace)
+            .map_err(|e| format!("导入历史记录失败: {}", e))?;
+    }
+
+    log::info!(
+        "[backup] imported from {} ({:?}): {:?}",
+        path,
+        mode,
+        summary
+    );
+    let _ = app.emit("settings-imported", &summary);
+    Ok(summary)
+}
+
 #[tauri::command]
 fn cmd_get_data_dir() -> serde_json::Value {
-    let base = store::base_dir();
+    let (base, source) = store::data_dir();
     serde_json::json!({
+        "dir": base.to_string_lossy().into_owned(),
+        "source": source,
         "settings": base.join("settings.json").to_string_lossy().into_owned(),
         "logs": base.join("logs").join("sayble.log").to_string_lossy().into_owned(),
     })
 }
 
+/// 把数据目录移动到 target 并让运行中的各模块改用新位置，返回新目录
+///
+/// 由命令行参数、环境变量或便携模式指定的目录不能移动。日志在重启前仍写入旧目录。
+#[tauri::command]
+async fn cmd_move_data_dir(app: tauri::AppHandle, target: String) -> Result<String, String> {
+    log::info!("[cmd] move_data_dir called, target={}", target);
+    tauri::async_runtime::spawn_blocking(move || {
+        let (current, source) = store::data_dir();
+        if source.is_fixed() {
+            return Err("数据目录由启动参数、环境变量或便携模式指定，无法在应用内移动".to_string());
+        }
+        let target = std::path::PathBuf::from(target.trim());
+        store::check_move_target(&current, &target)?;
+
+        store::copy_data_files(&current, &target)?;
+        store::set_data_dir(&target)?;
+        let relocated = app.state::<AppStore>().relocate(&target);
+        if let Err(e) = relocated {
+            log::error!("[store] relocate to {} failed: {}", target.display(), e);
+            // 退回原目录，新目录中已复制的文件保留供排查
+            let _ = store::set_data_dir(&current);
+            let _ = app.state::<AppStore>().relocate(&current);
+            return Err(format!("移动数据目录失败: {}", e));
+        }
+        // 复制后到切换前写入旧目录的文件（密钥、隧道、共享等）再补一次
+        if let Err(e) = store::copy_changed_files(&current, &target) {
+            log::warn!("[store] failed to copy files changed during move: {}", e);
+        }
+        app.state::<Arc<TunnelManager>>().reload()?;
+        app.state::<Arc<ShareManager>>().reload()?;
+
+        store::remove_old_data(&current);
+        log::info!(
+            "[store] data moved from {} to {}",
+            current.display(),
+            target.display()
+        );
+        Ok(target.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| format!("移动数据目录失败: {}", e))?
+}
+
 fn sanitize_file_name(name: &str) -> String {
     let cleaned: String = name
         .chars()
@@ -1893,12 +3419,178 @@ fn cmd_save_recording_file(
     Ok(path.to_string_lossy().into_owned())
 }
 
+/// 启动时从损坏中恢复的数据文件，前端启动后查询并提示
+#[tauri::command]
+fn cmd_get_store_recoveries(app: tauri::AppHandle) -> Vec<store::StoreRecovery> {
+    app.state::<AppStore>().recoveries()
+}
+
 #[tauri::command]
 fn cmd_load_history(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, String> {
     let store = app.state::<AppStore>();
     Ok(store.load_history())
 }
 
+/// 历史记录中可重新输出的文本：依次取 outputText、polishedText、asrText 中第一个非空值
+fn history_output_text(record: &serde_json::Value) -> Option<&str> {
+    ["outputText", "polishedText", "asrText"]
+        .iter()
+        .filter_map(|key| record.get(*key).and_then(|v| v.as_str()))
+        .find(|text| !text.is_empty())
+}
+
+/// 将一条历史记录（按 id 或 timestamp）重新输出到当前前台应用，返回输出的文本
+/// prompt_id 不为空时先用该提示词重新润色原始识别文本，结果作为新记录写入历史
+/// text 不为空时输出用户修改后的文本，更新该记录并从修改中学习纠正
+#[tauri::command]
+async fn cmd_output_history_record(
+    app: tauri::AppHandle,
+    id: String,
+    prompt_id: Option<String>,
+    text: Option<String>,
+) -> Result<String, String> {
+    log::info!(
+        "[cmd] output_history_record called, id={}, prompt_id={:?}, edited={}",
+        id,
+        prompt_id,
+        text.is_some()
+    );
+    let store = app.state::<AppStore>();
+    let record = store.find_history(&id).ok_or("未找到该历史记录")?;
+
+    let text = match prompt_id.as_deref().filter(|p| !p.is_empty()) {
+        Some(prompt_id) => {
+            let asr_text = record
+                .get("asrText")
+                .and_then(|v| v.as_str())
+                .filter(|t| !t.is_empty())
+                .ok_or("该记录没有原始识别文本")?;
+            let polish_settings = store.polish_settings();
+            let mut config =
+                get_polish_provider(&polish_settings, store.secrets()).ok_or("润色服务未配置")?;
+            config.prompt = polish_settings
+                .prompt_content(prompt_id)
+                .ok_or("提示词不存在")?
+                .to_string();
+            let polished =
+                finish_polish(&app, &config, polish::polish_text(&config, asr_text).await)?;
+
+            let timestamp = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+            store.append_history(serde_json::json!({
+                "timestamp": timestamp,
+                "asrText": asr_text,
+                "polishedText": polished,
+                "outputText": polished,
+                "promptId": prompt_id,
+            }));
+            polished
+        }
+        None => {
+            let original = history_output_text(&record).ok_or("该记录没有可输出的文本")?;
+            match text.filter(|t| !t.trim().is_empty() && t != original) {
+                Some(edited) => {
+                    store.update_history(&id, |r| {
+                        r["outputText"] = serde_json::json!(&edited);
+                    });
+                    learn_corrections(&app, original, &edited);
+                    edited
+                }
+                None => original.to_string(),
+            }
+        }
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        // 从主窗口触发时先最小化，让焦点回到目标应用
+        if let Some(window) = app.get_webview_window("main") {
+            if window.is_focused().unwrap_or(false) {
+                let _ = window.minimize();
+                std::thread::sleep(std::time::Duration::from_millis(300));
+            }
+        }
+        // 沿用当前会话的取消标志，输出过程中按 Esc 可中止分段输入
+        let (session_id, cancelled) = app
+            .state::<Arc<Mutex<RecordingFlag>>>()
+            .lock()
+            .ok()
+            .filter(|f| !f.is_recording && !f.cancelled.load(Ordering::SeqCst))
+            .map(|f| (f.session_id, f.cancelled.clone()))
+            .unwrap_or_default();
+        output_text_from_store(&app, session_id, &text, None, &cancelled)?;
+        Ok(text)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 把日期范围内的历史记录导出到 path（由前端通过文件对话框选择），返回导出的条数
+#[tauri::command]
+async fn cmd_export_history(
+    app: tauri::AppHandle,
+    path: String,
+    format: export::ExportFormat,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<usize, String> {
+    log::info!(
+        "[cmd] export_history called, format={:?}, from={:?}, to={:?}",
+        format,
+        from,
+        to
+    );
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app.state::<AppStore>();
+        let mut records = store.search_history(&HistoryQuery {
+            from,
+            to,
+            limit: Some(usize::MAX),
+            ..Default::default()
+        })?;
+        if records.is_empty() {
+            return Err("所选范围内没有历史记录".to_string());
+        }
+        records.reverse();
+
+        let file = std::fs::File::create(&path).map_err(|e| format!("创建导出文件失败: {}", e))?;
+        let mut out = std::io::BufWriter::new(file);
+        let count = export::write_history(&records, format, &mut out)?;
+        std::io::Write::flush(&mut out).map_err(|e| format!("写入导出文件失败: {}", e))?;
+        log::info!("[history] exported {} records to {}", count, path);
+        Ok(count)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 列出从用户修改中学到的纠正，按出现次数倒序
+#[tauri::command]
+fn cmd_list_corrections(app: tauri::AppHandle) -> Vec<corrections::Correction> {
+    app.state::<AppStore>().list_corrections()
+}
+
+/// 删除一条纠正记录
+#[tauri::command]
+fn cmd_remove_correction(
+    app: tauri::AppHandle,
+    original: String,
+    corrected: String,
+) -> Result<(), String> {
+    log::info!("[cmd] remove_correction called");
+    app.state::<AppStore>()
+        .remove_correction(&original, &corrected)
+}
+
+/// 按文本、日期、目标应用、ASR 服务商和提示词搜索历史记录
+#[tauri::command]
+fn cmd_search_history(
+    app: tauri::AppHandle,
+    query: HistoryQuery,
+) -> Result<Vec<serde_json::Value>, String> {
+    log::info!("[cmd] search_history called, query={:?}", query);
+    let store = app.state::<AppStore>();
+    store.search_history(&query)
+}
+
 #[tauri::command]
 fn cmd_clear_history(app: tauri::AppHandle) -> Result<(), String> {
     let store = app.state::<AppStore>();
@@ -1906,10 +3598,11 @@ fn cmd_clear_history(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// 按记录 id 删除历史记录，兼容传入 timestamp
 #[tauri::command]
-fn cmd_remove_history(app: tauri::AppHandle, timestamp: String) -> Result<(), String> {
+fn cmd_remove_history(app: tauri::AppHandle, id: String) -> Result<(), String> {
     let store = app.state::<AppStore>();
-    store.remove_history(&timestamp);
+    store.remove_history(&id);
     Ok(())
 }
 
@@ -1999,10 +3692,88 @@ fn cmd_inject_key_event(
     Ok(())
 }
 
+#[tauri::command]
+fn cmd_get_pending_outputs(app: tauri::AppHandle) -> Vec<PendingOutputInfo> {
+    app.state::<PendingOutputs>().list()
+}
+
+/// 悬浮窗中编辑待确认的文本
+#[tauri::command]
+fn cmd_update_pending_output(
+    app: tauri::AppHandle,
+    session_id: u64,
+    text: String,
+) -> Result<(), String> {
+    if app.state::<PendingOutputs>().update_text(session_id, &text) {
+        Ok(())
+    } else {
+        Err("待确认的输出不存在或已过期".to_string())
+    }
+}
+
+/// 确认输出，text 为空时输出保存的文本
+#[tauri::command]
+async fn cmd_accept_pending_output(
+    app: tauri::AppHandle,
+    session_id: u64,
+    text: Option<String>,
+) -> Result<(), String> {
+    log::info!(
+        "[cmd] accept_pending_output called, session_id={}",
+        session_id
+    );
+    tauri::async_runtime::spawn_blocking(move || {
+        accept_pending_output(&app, Some(session_id), text)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn cmd_discard_pending_output(app: tauri::AppHandle, session_id: u64) -> Result<(), String> {
+    log::info!(
+        "[cmd] discard_pending_output called, session_id={}",
+        session_id
+    );
+    if discard_pending_output(&app, session_id) {
+        Ok(())
+    } else {
+        Err("待确认的输出不存在或已过期".to_string())
+    }
+}
+
+/// 撤销上一次听写输出，reopen 为 true 时把文本放回预览
+#[tauri::command]
+async fn cmd_undo_last_output(app: tauri::AppHandle, reopen: Option<bool>) -> Result<(), String> {
+    log::info!("[cmd] undo_last_output called, reopen={:?}", reopen);
+    tauri::async_runtime::spawn_blocking(move || undo_last_output(&app, reopen.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 检测当前桌面会话可用的键盘输出方式，供前端在模拟输入不可用时提示
+#[tauri::command]
+fn cmd_check_output_capabilities() -> OutputCapabilities {
+    SimulateOutput::capabilities()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn app_settings(value: serde_json::Value) -> AppSettings {
+        settings::load_lenient(settings::APP_SETTINGS_KEY, value)
+    }
+
+    #[test]
+    fn backspace_count_uses_graphemes() {
+        assert_eq!(backspace_count("你好"), 2);
+        // e + 组合重音符、国旗、带肤色和 ZWJ 的 emoji 各算一个
+        assert_eq!(backspace_count("cafe\u{301}"), 4);
+        assert_eq!(backspace_count("🇨🇳👍🏽👨‍👩‍👧"), 3);
+        assert_eq!(backspace_count("a\r\nb"), 3);
+    }
+
     #[test]
     fn rms_amplitude_handles_empty_audio() {
         assert_eq!(rms_amplitude(&[]), 0.0);
@@ -2014,6 +3785,148 @@ mod tests {
         assert!(!is_silent_audio(&[1200, -1200, 1200, -1200]));
     }
 
+    #[test]
+    fn resolve_output_plan_prefers_app_rule() {
+        let settings = app_settings(serde_json::json!({
+            "outputMode": "SimulateKeyboard",
+            "outputRules": [
+                {"app": "WindowsTerminal", "outputMode": "Clipboard", "pasteShortcut": "ctrlShiftV", "trailingAction": "enter"},
+                {"app": "kitty", "enabled": false, "outputMode": "CopyOnly"},
+                {"outputMode": "Clipboard"},
+            ],
+        }));
+        let terminal = ForegroundApp {
+            process_name: "WindowsTerminal.exe".to_string(),
+            window_class: "CASCADIA_HOSTING_WINDOW_CLASS".to_string(),
+            ..Default::default()
+        };
+        let profile: HotkeyProfile = serde_json::from_value(serde_json::json!({
+            "id": "p",
+            "hotkey": "右Alt",
+            "outputMode": "PacedKeyboard",
+        }))
+        .unwrap();
+
+        // 全局设置
+        let plan = resolve_output_plan(&settings, None, None);
+        assert_eq!(plan.mode, OutputMode::SimulateKeyboard);
+        assert_eq!(plan.paste_shortcut, PasteShortcut::CtrlV);
+        assert_eq!(plan.trailing_action, TrailingAction::None);
+
+        // 快捷键方案覆盖全局设置
+        let plan = resolve_output_plan(&settings, Some(&profile), None);
+        assert_eq!(plan.mode, OutputMode::PacedKeyboard);
+
+        // 应用规则优先
+        let plan = resolve_output_plan(&settings, Some(&profile), Some(&terminal));
+        assert_eq!(
+            plan,
+            OutputPlan {
+                mode: OutputMode::Clipboard,
+                paste_shortcut: PasteShortcut::CtrlShiftV,
+                trailing_action: TrailingAction::Enter,
+            }
+        );
+
+        // 禁用的规则不生效
+        let kitty = ForegroundApp {
+            process_name: "kitty".to_string(),
+            window_class: "kitty".to_string(),
+            ..Default::default()
+        };
+        let plan = resolve_output_plan(&settings, None, Some(&kitty));
+        assert_eq!(plan.mode, OutputMode::SimulateKeyboard);
+
+        // 没有任何设置时默认剪贴板粘贴
+        let plan = resolve_output_plan(&AppSettings::default(), None, Some(&terminal));
+        assert_eq!(plan.mode, OutputMode::Clipboard);
+    }
+
+    #[test]
+    fn parse_hotkey_configs_maps_labels_to_actions() {
+        let settings = app_settings(serde_json::json!({
+            "toggleHotkey": "右Ctrl",
+            "transformHotkey": "右Alt",
+            "translationHotkeys": [
+                {"hotkey": "左Alt + E", "targetLanguage": "en"},
+                {"hotkey": "左Alt + J", "sourceLanguage": "zh", "targetLanguage": "ja"},
+                {"hotkey": "", "targetLanguage": "fr"},
+                {"hotkey": "左Alt + K", "targetLanguage": ""},
+            ],
+        }));
+        let configs = parse_hotkey_configs(&settings);
+        assert_eq!(configs.len(), 4);
+        assert_eq!(configs[0].action, HotkeyAction::ToggleRecording);
+        assert_eq!(configs[1].action, HotkeyAction::TransformSelection);
+        assert_eq!(
+            configs[1].binding.modifiers,
+            vec![config::Modifier::RightAlt]
+        );
+        assert_eq!(configs[2].action, HotkeyAction::Translate);
+        assert_eq!(
+            configs[2].translation.as_ref().unwrap().target_language,
+            "en"
+        );
+        assert_eq!(
+            configs[3].translation.as_ref().unwrap().source_language,
+            Some("zh".to_string())
+        );
+
+        let configs = parse_hotkey_configs(&app_settings(serde_json::json!({
+            "toggleHotkey": "右Ctrl",
+            "acceptOutputHotkey": "右Ctrl + Enter",
+        })));
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[1].action, HotkeyAction::AcceptOutput);
+        assert_eq!(configs[1].binding.key, 0x0D);
+
+        let configs = parse_hotkey_configs(&app_settings(serde_json::json!({
+            "toggleHotkey": "右Ctrl",
+            "undoOutputHotkey": "F9",
+        })));
+        assert_eq!(configs[1].action, HotkeyAction::UndoOutput);
+        assert_eq!(configs[1].binding.key, 0x78);
+
+        let configs =
+            parse_hotkey_configs(&app_settings(serde_json::json!({"toggleHotkey": "右Ctrl"})));
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].trigger, TriggerMode::Toggle);
+
+        let configs = parse_hotkey_configs(&app_settings(serde_json::json!({
+            "toggleHotkey": "右Ctrl",
+            "toggleHotkeyMode": "hold",
+        })));
+        assert_eq!(configs[0].trigger, TriggerMode::Hold);
+    }
+
+    #[test]
+    fn parse_hotkey_configs_includes_named_profiles() {
+        let settings = app_settings(serde_json::json!({
+            "toggleHotkey": "右Ctrl",
+            "hotkeyProfiles": [
+                {
+                    "id": "meeting",
+                    "name": "会议",
+                    "hotkey": "左Alt + M",
+                    "language": "en",
+                    "polish": {"type": "off"},
+                },
+                {"id": "", "hotkey": "左Alt + N"},
+                {"id": "broken", "hotkey": "not a hotkey"},
+                {"hotkey": "左Alt + P"},
+            ],
+        }));
+        let profiles = load_hotkey_profiles(&settings);
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].polish, ProfilePolish::Off);
+
+        let configs = parse_hotkey_configs(&settings);
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].profile_id, None);
+        assert_eq!(configs[1].profile_id.as_deref(), Some("meeting"));
+        assert_eq!(configs[1].action, HotkeyAction::ToggleRecording);
+    }
+
     #[test]
     fn has_autostart_arg_detects_exact_startup_flag() {
         assert!(has_autostart_arg(["sayble.exe", AUTOSTART_ARG]));
@@ -2049,4 +3962,44 @@ mod tests {
             Some("max duration")
         );
     }
+
+    #[test]
+    fn history_output_text_prefers_output_text() {
+        let record = serde_json::json!({
+            "asrText": "原文",
+            "polishedText": null,
+            "outputText": "输出",
+        });
+        assert_eq!(history_output_text(&record), Some("输出"));
+
+        let record = serde_json::json!({"asrText": "原文", "polishedText": "", "outputText": ""});
+        assert_eq!(history_output_text(&record), Some("原文"));
+        assert_eq!(history_output_text(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn smart_spacing_adjusts_first_text_segment() {
+        let mut segments = vec![
+            Segment::Text("world".to_string()),
+            Segment::Action(voice_command::CommandAction::NewLine),
+        ];
+        apply_smart_spacing(&mut segments, "Hello.", "en");
+        assert_eq!(segments[0], Segment::Text(" World".to_string()));
+
+        // 重复的句末标点被丢弃后整段为空，直接移除
+        let mut segments = vec![
+            Segment::Text("。".to_string()),
+            Segment::Action(voice_command::CommandAction::NewLine),
+        ];
+        apply_smart_spacing(&mut segments, "你好。", "zh");
+        assert_eq!(
+            segments,
+            vec![Segment::Action(voice_command::CommandAction::NewLine)]
+        );
+
+        // 以语音命令开头时不调整
+        let mut segments = vec![Segment::Action(voice_command::CommandAction::NewLine)];
+        apply_smart_spacing(&mut segments, "Hello.", "en");
+        assert_eq!(segments.len(), 1);
+    }
 }
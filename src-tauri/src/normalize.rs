@@ -0,0 +1,465 @@
+use crate::spacing::is_cjk;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// 本地文本规整设置（app_settings.normalize）
+/// 在润色之前按顺序执行：用户词典 → 去除语气词 → 数字格式化 → 全角/半角标点
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NormalizeSettings {
+    pub enabled: bool,
+    pub dictionary: Vec<DictionaryEntry>,
+    pub remove_fillers: bool,
+    pub filler_words: Vec<String>,
+    /// 中文数字转阿拉伯数字，并规整日期、百分比和金额
+    pub format_numbers: bool,
+    /// 中文语境使用全角标点，英文语境使用半角标点
+    pub convert_punctuation: bool,
+}
+
+impl Default for NormalizeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dictionary: Vec::new(),
+            remove_fillers: true,
+            filler_words: ["嗯", "呃", "uh", "um", "erm"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            format_numbers: true,
+            convert_punctuation: true,
+        }
+    }
+}
+
+/// 用户词典条目，regex 为 true 时 pattern 按正则表达式匹配，replacement 可引用分组（$1）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryEntry {
+    pub pattern: String,
+    pub replacement: String,
+    #[serde(default)]
+    pub regex: bool,
+}
+
+/// 按设置规整识别文本，未启用时原样返回
+pub fn normalize(text: &str, settings: &NormalizeSettings) -> String {
+    if !settings.enabled {
+        return text.to_string();
+    }
+    let mut text = apply_dictionary(text, &settings.dictionary);
+    if settings.remove_fillers {
+        text = remove_fillers(&text, &settings.filler_words);
+    }
+    if settings.format_numbers {
+        text = format_numbers(&text);
+    }
+    if settings.convert_punctuation {
+        text = convert_punctuation(&text);
+    }
+    text.trim().to_string()
+}
+
+/// 依次应用词典条目，无效的正则表达式跳过
+fn apply_dictionary(text: &str, dictionary: &[DictionaryEntry]) -> String {
+    let mut text = text.to_string();
+    for entry in dictionary.iter().filter(|e| !e.pattern.is_empty()) {
+        if !entry.regex {
+            text = text.replace(&entry.pattern, &entry.replacement);
+            continue;
+        }
+        match Regex::new(&entry.pattern) {
+            Ok(re) => {
+                text = re
+                    .replace_all(&text, entry.replacement.as_str())
+                    .into_owned()
+            }
+            Err(e) => log::warn!("[normalize] invalid pattern {:?}: {}", entry.pattern, e),
+        }
+    }
+    text
+}
+
+/// 去除语气词及其后的逗号；英文语气词按整词匹配且不区分大小写
+fn remove_fillers(text: &str, fillers: &[String]) -> String {
+    let alternatives: Vec<String> = fillers
+        .iter()
+        .filter(|f| !f.is_empty())
+        .map(|f| {
+            if f.chars().all(|c| c.is_ascii_alphanumeric()) {
+                format!(r"\b{}\b[,，]?\s*", regex::escape(f))
+            } else {
+                format!("(?:{})+[,，、]?", regex::escape(f))
+            }
+        })
+        .collect();
+    if alternatives.is_empty() {
+        return text.to_string();
+    }
+    match Regex::new(&format!("(?i){}", alternatives.join("|"))) {
+        Ok(re) => re.replace_all(text, "").into_owned(),
+        Err(e) => {
+            log::warn!("[normalize] invalid filler words: {}", e);
+            text.to_string()
+        }
+    }
+}
+
+fn chinese_digit(c: char) -> Option<u64> {
+    match c {
+        '零' | '〇' => Some(0),
+        '一' => Some(1),
+        '二' | '两' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    }
+}
+
+fn chinese_unit(c: char) -> Option<u64> {
+    match c {
+        '十' => Some(10),
+        '百' => Some(100),
+        '千' => Some(1_000),
+        '万' => Some(10_000),
+        '亿' => Some(100_000_000),
+        _ => None,
+    }
+}
+
+fn is_chinese_numeral(c: char) -> bool {
+    chinese_digit(c).is_some() || chinese_unit(c).is_some()
+}
+
+/// 解析带单位的中文数字，如 "三百零五"、"一万五"（口语省略末位单位）
+/// 超出 u64 范围时返回 None
+fn parse_chinese_number(s: &str) -> Option<u64> {
+    let mut total = 0u64;
+    let mut section = 0u64;
+    let mut number: Option<u64> = None;
+    let mut last_unit = 0u64;
+    let mut zero_since_unit = false;
+    let mut prev_digit = false;
+
+    for (i, c) in s.chars().enumerate() {
+        if let Some(d) = chinese_digit(c) {
+            // 连续数字（如 "一二"）不是带单位的数字
+            if prev_digit && !zero_since_unit {
+                return None;
+            }
+            if d == 0 {
+                zero_since_unit = true;
+                prev_digit = false;
+            } else {
+                number = Some(d);
+                prev_digit = true;
+            }
+            continue;
+        }
+        let unit = chinese_unit(c)?;
+        let n = match number.take() {
+            Some(n) => n,
+            // "十五" 省略了开头的 "一"
+            None if unit == 10 && i == 0 => 1,
+            None if unit >= 10_000 && section > 0 => 0,
+            None => return None,
+        };
+        match unit {
+            10_000 => {
+                total = total.checked_add(section.checked_add(n)?.checked_mul(unit)?)?;
+                section = 0;
+            }
+            100_000_000 => {
+                total = total
+                    .checked_add(section)?
+                    .checked_add(n)?
+                    .checked_mul(unit)?;
+                section = 0;
+            }
+            _ => section = section.checked_add(n.checked_mul(unit)?)?,
+        }
+        last_unit = unit;
+        zero_since_unit = false;
+        prev_digit = false;
+    }
+
+    if let Some(n) = number {
+        // "一百五" 即 150，"一万五" 即 15000
+        if !zero_since_unit && last_unit >= 100 {
+            section = section.checked_add(n * last_unit / 10)?;
+        } else {
+            section = section.checked_add(n)?;
+        }
+    }
+    total.checked_add(section)
+}
+
+/// 将一段中文数字按上下文转换为阿拉伯数字，不应转换时返回 None
+/// 单个字（如 "一下"、"十分"）和成语中的数字保持原样，仅在日期中转换
+fn convert_numeral(run: &str, prev: Option<char>, rest: &str) -> Option<String> {
+    let len = run.chars().count();
+    let digits_only = run.chars().all(|c| chinese_digit(c).is_some());
+
+    if digits_only {
+        // "二零二四年" 逐位转换
+        if len >= 2 && rest.starts_with('年') {
+            return run
+                .chars()
+                .map(|c| chinese_digit(c).map(|d| char::from(b'0' + d as u8)))
+                .collect();
+        }
+        let date_part = rest.starts_with('月')
+            || (prev == Some('月') && (rest.starts_with('日') || rest.starts_with('号')));
+        if len == 1 && run != "两" && date_part {
+            return parse_chinese_number(run).map(|n| n.to_string());
+        }
+        return None;
+    }
+
+    if len == 1 && !(rest.starts_with('月') || prev == Some('月')) {
+        return None;
+    }
+    parse_chinese_number(run).map(|n| n.to_string())
+}
+
+/// 数字、日期、百分比和金额格式化
+fn format_numbers(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if !is_chinese_numeral(c) {
+            result.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let end = rest
+            .char_indices()
+            .find(|(_, c)| !is_chinese_numeral(*c))
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let (run, after) = rest.split_at(end);
+        rest = after;
+
+        // "百分之五十" → "50%"
+        if let Some(stripped) = result.strip_suffix("百分之") {
+            if let Some(n) = parse_chinese_number(run) {
+                result.truncate(stripped.len());
+                result.push_str(&format!("{}%", n));
+                continue;
+            }
+        }
+        match convert_numeral(run, result.chars().last(), after) {
+            Some(converted) => result.push_str(&converted),
+            None => result.push_str(run),
+        }
+    }
+
+    format_units(&result)
+}
+
+/// 金额和百分比单位规则，只编译一次
+static UNIT_RULES: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    [
+        (r"(\d+(?:\.\d+)?)\s*块钱", "${1}元"),
+        (r"(\d+(?:\.\d+)?)\s*美元", "$$$1"),
+        (r"(?i)\b(\d+(?:\.\d+)?)\s+dollars?\b", "$$$1"),
+        (r"(?i)\b(\d+(?:\.\d+)?)\s+percent\b", "${1}%"),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).expect("built-in pattern"), replacement))
+    .collect()
+});
+
+/// 金额和百分比单位：¥/$ 前置，"块钱" 统一为 "元"
+fn format_units(text: &str) -> String {
+    let mut text = text.to_string();
+    for (re, replacement) in UNIT_RULES.iter() {
+        text = re.replace_all(&text, *replacement).into_owned();
+    }
+    text
+}
+
+/// 半角与全角标点对照
+const PUNCTUATION_PAIRS: &[(char, char)] = &[
+    (',', '，'),
+    ('.', '。'),
+    ('!', '！'),
+    ('?', '？'),
+    (':', '：'),
+    (';', '；'),
+];
+
+/// 中文之后的半角标点转为全角并去掉其后的空格；英文之间的全角标点转为半角并补空格
+fn convert_punctuation(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let prev = result.chars().last();
+        let next = chars.get(i + 1).copied();
+
+        if let Some(&(_, full)) = PUNCTUATION_PAIRS.iter().find(|(half, _)| *half == c) {
+            if prev.is_some_and(is_cjk) && !next.is_some_and(|n| n.is_ascii_digit()) {
+                result.push(full);
+                i += 1;
+                while chars.get(i).is_some_and(|c| *c == ' ') {
+                    i += 1;
+                }
+                continue;
+            }
+        }
+        if let Some(&(half, _)) = PUNCTUATION_PAIRS.iter().find(|(_, full)| *full == c) {
+            if prev.is_some_and(|p| p.is_ascii_alphanumeric()) && !next.is_some_and(is_cjk) {
+                result.push(half);
+                if next.is_some_and(|n| n.is_ascii_alphanumeric()) {
+                    result.push(' ');
+                }
+                i += 1;
+                continue;
+            }
+        }
+        result.push(c);
+        i += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> NormalizeSettings {
+        NormalizeSettings {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_disabled_keeps_text() {
+        assert_eq!(
+            normalize("嗯，二零二四年", &NormalizeSettings::default()),
+            "嗯，二零二四年"
+        );
+    }
+
+    #[test]
+    fn test_dictionary_literal_and_regex() {
+        let dictionary = vec![
+            DictionaryEntry {
+                pattern: "赛宝".to_string(),
+                replacement: "Sayble".to_string(),
+                regex: false,
+            },
+            DictionaryEntry {
+                pattern: r"(?i)git\s*hub".to_string(),
+                replacement: "GitHub".to_string(),
+                regex: true,
+            },
+            DictionaryEntry {
+                pattern: "([".to_string(),
+                replacement: "无效".to_string(),
+                regex: true,
+            },
+        ];
+        assert_eq!(
+            apply_dictionary("赛宝同步到git hub", &dictionary),
+            "Sayble同步到GitHub"
+        );
+    }
+
+    #[test]
+    fn test_remove_fillers() {
+        let fillers = NormalizeSettings::default().filler_words;
+        assert_eq!(remove_fillers("嗯，我觉得呃可以", &fillers), "我觉得可以");
+        assert_eq!(remove_fillers("嗯嗯，好的", &fillers), "好的");
+        assert_eq!(
+            remove_fillers("Um, I think uh we can", &fillers),
+            "I think we can"
+        );
+        // 整词匹配，不误删单词中的片段
+        assert_eq!(
+            remove_fillers("umbrella and hum", &fillers),
+            "umbrella and hum"
+        );
+    }
+
+    #[test]
+    fn test_parse_chinese_number() {
+        assert_eq!(parse_chinese_number("十五"), Some(15));
+        assert_eq!(parse_chinese_number("二十"), Some(20));
+        assert_eq!(parse_chinese_number("三百零五"), Some(305));
+        assert_eq!(parse_chinese_number("一百五"), Some(150));
+        assert_eq!(parse_chinese_number("一万五"), Some(15_000));
+        assert_eq!(parse_chinese_number("两千零二十四"), Some(2024));
+        assert_eq!(parse_chinese_number("三亿两千万"), Some(320_000_000));
+        assert_eq!(parse_chinese_number("一二"), None);
+        assert_eq!(parse_chinese_number("万一"), None);
+        // 超出 u64 范围
+        assert_eq!(parse_chinese_number("一亿一亿一亿"), None);
+        assert_eq!(parse_chinese_number(&"一亿".repeat(5)), None);
+    }
+
+    #[test]
+    fn test_format_numbers_and_dates() {
+        assert_eq!(format_numbers("二零二四年三月五号"), "2024年3月5号");
+        assert_eq!(format_numbers("十一月二十日"), "11月20日");
+        assert_eq!(format_numbers("一共三百五十个"), "一共350个");
+        assert_eq!(format_numbers("增长了百分之十五"), "增长了15%");
+        // 单个数字和成语保持原样
+        assert_eq!(
+            format_numbers("等一下，一定要十分小心"),
+            "等一下，一定要十分小心"
+        );
+        assert_eq!(format_numbers("万一不行"), "万一不行");
+    }
+
+    #[test]
+    fn test_format_currency() {
+        assert_eq!(format_numbers("花了五十块钱"), "花了50元");
+        assert_eq!(format_numbers("二十美元"), "$20");
+        assert_eq!(format_units("it costs 15 dollars"), "it costs $15");
+        assert_eq!(format_units("up 3.5 percent"), "up 3.5%");
+    }
+
+    #[test]
+    fn test_convert_punctuation() {
+        assert_eq!(convert_punctuation("你好, 世界."), "你好，世界。");
+        assert_eq!(convert_punctuation("hello，world。"), "hello, world.");
+        // 小数点和英文句子不受影响
+        assert_eq!(convert_punctuation("版本3.5, ok"), "版本3.5, ok");
+        assert_eq!(convert_punctuation("打开Chrome，然后"), "打开Chrome，然后");
+    }
+
+    #[test]
+    fn test_normalize_pipeline() {
+        assert_eq!(
+            normalize("嗯，今年是二零二四年, 预算涨了百分之二十", &enabled()),
+            "今年是2024年，预算涨了20%"
+        );
+    }
+
+    #[test]
+    fn test_settings_deserialize_with_defaults() {
+        let settings: NormalizeSettings = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "dictionary": [{"pattern": "a", "replacement": "b"}],
+            "removeFillers": false
+        }))
+        .unwrap();
+        assert!(settings.enabled);
+        assert!(!settings.dictionary[0].regex);
+        assert!(!settings.remove_fillers);
+        assert!(settings.format_numbers);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::config::HotkeyProfile;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 历史记录中的确认状态
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_ACCEPTED: &str = "accepted";
+pub const STATUS_DISCARDED: &str = "discarded";
+/// 已输出后被撤销
+pub const STATUS_UNDONE: &str = "undone";
+
+/// 关闭自动输出时等待用户确认的识别结果
+#[derive(Debug, Clone)]
+pub struct PendingOutput {
+    pub session_id: u64,
+    pub text: String,
+    /// 触发本次会话的快捷键方案，确认后按方案的输出设置输出
+    pub profile: Option<HotkeyProfile>,
+    /// 对应历史记录的 id，确认或丢弃后更新其状态
+    pub history_id: String,
+    pub created_at: Instant,
+}
+
+/// 前端展示用的待确认输出
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingOutputInfo {
+    pub session_id: u64,
+    pub text: String,
+}
+
+/// 按会话保存的待确认输出
+#[derive(Default)]
+pub struct PendingOutputs {
+    items: Mutex<Vec<PendingOutput>>,
+}
+
+impl PendingOutputs {
+    /// 保存待确认输出，同一会话重复保存时覆盖
+    pub fn hold(&self, output: PendingOutput) {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        items.retain(|item| item.session_id != output.session_id);
+        items.push(output);
+    }
+
+    /// 用户在悬浮窗中编辑后更新文本，会话不存在时返回 false
+    pub fn update_text(&self, session_id: u64, text: &str) -> bool {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        match items.iter_mut().find(|item| item.session_id == session_id) {
+            Some(item) => {
+                item.text = text.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, session_id: u64) -> bool {
+        let items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        items.iter().any(|item| item.session_id == session_id)
+    }
+
+    pub fn take(&self, session_id: u64) -> Option<PendingOutput> {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let index = items
+            .iter()
+            .position(|item| item.session_id == session_id)?;
+        Some(items.remove(index))
+    }
+
+    /// 取出最近一条待确认输出（确认快捷键使用）
+    pub fn take_latest(&self) -> Option<PendingOutput> {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        items.pop()
+    }
+
+    pub fn take_all(&self) -> Vec<PendingOutput> {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        std::mem::take(&mut *items)
+    }
+
+    /// 取出已超时的待确认输出
+    pub fn take_expired(&self, now: Instant, timeout: Duration) -> Vec<PendingOutput> {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let (expired, remaining) = std::mem::take(&mut *items)
+            .into_iter()
+            .partition(|item| now.duration_since(item.created_at) >= timeout);
+        *items = remaining;
+        expired
+    }
+
+    pub fn list(&self) -> Vec<PendingOutputInfo> {
+        let items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        items
+            .iter()
+            .map(|item| PendingOutputInfo {
+                session_id: item.session_id,
+                text: item.text.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(session_id: u64, text: &str, created_at: Instant) -> PendingOutput {
+        PendingOutput {
+            session_id,
+            text: text.to_string(),
+            profile: None,
+            history_id: format!("history-{}", session_id),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_hold_update_take() {
+        let outputs = PendingOutputs::default();
+        let now = Instant::now();
+        outputs.hold(pending(1, "你好", now));
+        outputs.hold(pending(2, "world", now));
+        outputs.hold(pending(1, "你好世界", now));
+        assert_eq!(outputs.list().len(), 2);
+
+        assert!(outputs.update_text(2, "World!"));
+        assert!(!outputs.update_text(3, "missing"));
+        assert!(outputs.contains(2));
+        assert!(!outputs.contains(3));
+
+        // 最近一条是重新保存的会话 1
+        assert_eq!(outputs.take_latest().unwrap().text, "你好世界");
+        assert_eq!(outputs.take(2).unwrap().text, "World!");
+        assert!(outputs.take(2).is_none());
+        assert!(outputs.take_latest().is_none());
+    }
+
+    #[test]
+    fn test_take_expired() {
+        let outputs = PendingOutputs::default();
+        let t0 = Instant::now();
+        outputs.hold(pending(1, "old", t0));
+        outputs.hold(pending(2, "new", t0 + Duration::from_secs(50)));
+
+        let expired = outputs.take_expired(t0 + Duration::from_secs(60), Duration::from_secs(60));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].session_id, 1);
+        assert_eq!(
+            outputs.list(),
+            vec![PendingOutputInfo {
+                session_id: 2,
+                text: "new".to_string()
+            }]
+        );
+        assert_eq!(outputs.take_all().len(), 1);
+        assert!(outputs.list().is_empty());
+    }
+}
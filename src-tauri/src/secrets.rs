@@ -0,0 +1,436 @@
+//! 密钥存储：ASR Access Key、润色 API Key 等敏感字段不写入 settings.json
+//!
+//! 优先使用系统凭据库（Windows 凭据管理器、macOS 钥匙串、Linux Secret Service），
+//! 不可用时退回到加密文件 ~/.sayble/secrets.json。加密文件的密钥由口令
+//! （环境变量 SAYBLE_SECRETS_PASSPHRASE）或本机标识经 Argon2id 派生，复制到其他设备后无法解密。
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::store;
+
+/// 系统凭据库中的服务名
+const KEYRING_SERVICE: &str = "sayble";
+
+/// 探测系统凭据库是否可用时读取的条目，不存在也视为可用
+const KEYRING_PROBE: &str = "probe";
+
+/// 设置后使用口令而不是本机标识派生加密文件的密钥
+pub const PASSPHRASE_ENV: &str = "SAYBLE_SECRETS_PASSPHRASE";
+
+const FILE_VERSION: u32 = 1;
+
+/// 密钥存储，读取时依次查找系统凭据库和加密文件
+pub struct SecretStore {
+    keyring: bool,
+    file: EncryptedFile,
+}
+
+impl SecretStore {
+    /// 打开密钥存储，系统凭据库不可用时只使用 base 目录下的加密文件
+    pub fn open(base: &Path) -> Self {
+        let keyring = keyring_available();
+        let passphrase = std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty());
+        log::info!(
+            "[secrets] backend: {}",
+            if keyring {
+                "system keyring"
+            } else {
+                "encrypted file"
+            }
+        );
+        Self {
+            keyring,
+            file: EncryptedFile::new(base.join("secrets.json"), passphrase),
+        }
+    }
+
+    /// 只使用加密文件，供测试和没有系统凭据库的环境使用
+    pub fn file_only(path: PathBuf, passphrase: Option<String>) -> Self {
+        Self {
+            keyring: false,
+            file: EncryptedFile::new(path, passphrase),
+        }
+    }
+
+    /// 读取密钥，不存在或无法解密时返回 None
+    pub fn get(&self, id: &str) -> Option<String> {
+        if self.keyring {
+            match keyring_entry(id).and_then(|e| e.get_password()) {
+                Ok(value) => return Some(value),
+                Err(keyring::Error::NoEntry) => {}
+                Err(e) => log::warn!("[secrets] keyring read {} failed: {}", id, e),
+            }
+        }
+        self.file.get(id).unwrap_or_else(|e| {
+            log::error!("[secrets] {}", e);
+            None
+        })
+    }
+
+    /// 保存密钥，值为空时删除
+    pub fn set(&self, id: &str, value: &str) -> Result<(), String> {
+        if value.is_empty() {
+            return self.delete(id);
+        }
+        if self.keyring {
+            match keyring_entry(id).and_then(|e| e.set_password(value)) {
+                // 清掉退回加密文件期间保存的旧值，避免读到过期的密钥
+                Ok(()) => return self.file.remove(id),
+                Err(e) => log::warn!(
+                    "[secrets] keyring write {} failed: {}, using encrypted file",
+                    id,
+                    e
+                ),
+            }
+        }
+        self.file.set(id, value)
+    }
+
+    /// 改为使用 base 目录下的加密文件，文件需已复制到新位置
+    pub fn relocate(&self, base: &Path) {
+        *self.file.path.lock().unwrap_or_else(|e| e.into_inner()) = base.join("secrets.json");
+    }
+
+    /// 删除密钥，不存在时忽略
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        if self.keyring {
+            match keyring_entry(id).and_then(|e| e.delete_credential()) {
+                Ok(()) | Err(keyring::Error::NoEntry) => {}
+                Err(e) => return Err(format!("Failed to delete secret {}: {}", id, e)),
+            }
+        }
+        self.file.remove(id)
+    }
+}
+
+/// 用口令加密的一组密钥，导出备份时使用，与本机无关
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SealedSecrets {
+    salt: String,
+    entries: BTreeMap<String, SealedSecret>,
+}
+
+impl SealedSecrets {
+    pub fn seal(password: &str, secrets: &[(String, String)]) -> Result<Self, String> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let key = derive_key(password.as_bytes(), &salt)?;
+        let mut entries = BTreeMap::new();
+        for (id, value) in secrets {
+            entries.insert(id.clone(), seal(&key, id, value)?);
+        }
+        Ok(Self {
+            salt: BASE64.encode(salt),
+            entries,
+        })
+    }
+
+    /// 解密全部密钥，口令错误时返回错误
+    pub fn open(&self, password: &str) -> Result<Vec<(String, String)>, String> {
+        let salt = BASE64
+            .decode(&self.salt)
+            .map_err(|e| format!("Invalid secrets salt: {}", e))?;
+        let key = derive_key(password.as_bytes(), &salt)?;
+        self.entries
+            .iter()
+            .map(|(id, sealed)| Ok((id.clone(), unseal(&key, id, sealed)?)))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn keyring_entry(id: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, id)
+}
+
+fn keyring_available() -> bool {
+    match keyring_entry(KEYRING_PROBE).and_then(|e| e.get_password()) {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(e) => {
+            log::warn!("[secrets] system keyring unavailable: {}", e);
+            false
+        }
+    }
+}
+
+/// 加密文件内容：每个密钥单独加密，密钥 id 作为附加数据防止条目被互换
+#[derive(Debug, Serialize, Deserialize)]
+struct SecretsFile {
+    version: u32,
+    /// Argon2id 的盐，Base64
+    salt: String,
+    /// 是否由口令派生密钥，否则由本机标识派生
+    passphrase: bool,
+    entries: BTreeMap<String, SealedSecret>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedSecret {
+    nonce: String,
+    data: String,
+}
+
+struct EncryptedFile {
+    /// 数据目录迁移时会改变
+    path: Mutex<PathBuf>,
+    passphrase: Option<String>,
+    /// 已加载的文件内容和派生出的密钥，首次使用时加载
+    state: Mutex<Option<(SecretsFile, Key)>>,
+}
+
+impl EncryptedFile {
+    fn new(path: PathBuf, passphrase: Option<String>) -> Self {
+        Self {
+            path: Mutex::new(path),
+            passphrase,
+            state: Mutex::new(None),
+        }
+    }
+
+    fn get(&self, id: &str) -> Result<Option<String>, String> {
+        self.with_state(|file, key| {
+            let Some(sealed) = file.entries.get(id) else {
+                return Ok(None);
+            };
+            unseal(key, id, sealed).map(Some)
+        })
+    }
+
+    fn set(&self, id: &str, value: &str) -> Result<(), String> {
+        self.with_state(|file, key| {
+            file.entries.insert(id.to_string(), seal(key, id, value)?);
+            self.save(file)
+        })
+    }
+
+    fn path(&self) -> PathBuf {
+        self.path.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn remove(&self, id: &str) -> Result<(), String> {
+        if !self.path().exists() {
+            return Ok(());
+        }
+        self.with_state(|file, _| {
+            if file.entries.remove(id).is_some() {
+                self.save(file)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn with_state<T>(
+        &self,
+        f: impl FnOnce(&mut SecretsFile, &Key) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.is_none() {
+            *state = Some(self.load()?);
+        }
+        let (file, key) = state.as_mut().expect("secrets file loaded");
+        f(file, key)
+    }
+
+    fn load(&self) -> Result<(SecretsFile, Key), String> {
+        let path = self.path();
+        let file = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str::<SecretsFile>(&content)
+                .map_err(|e| format!("Invalid secrets file {}: {}", path.display(), e))?
+        } else {
+            let mut salt = [0u8; 16];
+            OsRng.fill_bytes(&mut salt);
+            SecretsFile {
+                version: FILE_VERSION,
+                salt: BASE64.encode(salt),
+                passphrase: self.passphrase.is_some(),
+                entries: BTreeMap::new(),
+            }
+        };
+        if file.passphrase && self.passphrase.is_none() {
+            return Err(format!(
+                "secrets file is protected by a passphrase, set {} to unlock",
+                PASSPHRASE_ENV
+            ));
+        }
+        let material = match (&self.passphrase, file.passphrase) {
+            (Some(passphrase), true) => passphrase.clone(),
+            _ => machine_key_material(),
+        };
+        let salt = BASE64
+            .decode(&file.salt)
+            .map_err(|e| format!("Invalid secrets salt: {}", e))?;
+        let key = derive_key(material.as_bytes(), &salt)?;
+        Ok((file, key))
+    }
+
+    fn save(&self, file: &SecretsFile) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(file)
+            .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+        let path = self.path();
+        store::write_private_file_atomic(&path, json.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+fn derive_key(material: &[u8], salt: &[u8]) -> Result<Key, String> {
+    let mut key = Key::default();
+    argon2::Argon2::default()
+        .hash_password_into(material, salt, &mut key)
+        .map_err(|e| format!("Failed to derive secrets key: {}", e))?;
+    Ok(key)
+}
+
+fn seal(key: &Key, id: &str, value: &str) -> Result<SealedSecret, String> {
+    let cipher = ChaCha20Poly1305::new(key);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let payload = Payload {
+        msg: value.as_bytes(),
+        aad: id.as_bytes(),
+    };
+    let data = cipher
+        .encrypt(&nonce, payload)
+        .map_err(|e| format!("Failed to encrypt secret {}: {}", id, e))?;
+    Ok(SealedSecret {
+        nonce: BASE64.encode(nonce),
+        data: BASE64.encode(data),
+    })
+}
+
+fn unseal(key: &Key, id: &str, sealed: &SealedSecret) -> Result<String, String> {
+    let nonce = BASE64
+        .decode(&sealed.nonce)
+        .ok()
+        .filter(|n| n.len() == 12)
+        .ok_or_else(|| format!("Invalid nonce for secret {}", id))?;
+    let data = BASE64
+        .decode(&sealed.data)
+        .map_err(|e| format!("Invalid data for secret {}: {}", id, e))?;
+    let payload = Payload {
+        msg: &data,
+        aad: id.as_bytes(),
+    };
+    let plain = ChaCha20Poly1305::new(key)
+        .decrypt(Nonce::from_slice(&nonce), payload)
+        .map_err(|_| format!("Failed to decrypt secret {}, wrong key or device", id))?;
+    String::from_utf8(plain).map_err(|e| format!("Invalid secret {}: {}", id, e))
+}
+
+/// 本机标识派生密钥的原始材料，读不到机器标识时退回到用户主目录路径
+fn machine_key_material() -> String {
+    let id = machine_id().unwrap_or_else(|| {
+        log::warn!("[secrets] machine id unavailable, falling back to home directory");
+        dirs::home_dir()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default()
+    });
+    format!("sayble-secrets:{}", id)
+}
+
+#[cfg(target_os = "windows")]
+fn machine_id() -> Option<String> {
+    crate::get_machine_guid()
+}
+
+#[cfg(target_os = "linux")]
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("IOPlatformUUID"))
+        .and_then(|line| line.rsplit('"').nth(1))
+        .map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn machine_id() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.json");
+        let store = SecretStore::file_only(path.clone(), None);
+        store.set("asr.volcengine.accessKey", "ak-123").unwrap();
+        store.set("polish.deepseek.apiKey", "sk-456").unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("ak-123"));
+        assert!(!content.contains("sk-456"));
+
+        // 重新打开后仍能解密
+        let store = SecretStore::file_only(path.clone(), None);
+        assert_eq!(
+            store.get("asr.volcengine.accessKey").as_deref(),
+            Some("ak-123")
+        );
+        store.set("polish.deepseek.apiKey", "").unwrap();
+        assert_eq!(store.get("polish.deepseek.apiKey"), None);
+        assert_eq!(store.get("missing"), None);
+    }
+
+    #[test]
+    fn test_passphrase_and_swapped_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.json");
+        let store = SecretStore::file_only(path.clone(), Some("correct horse".to_string()));
+        store.set("a", "first").unwrap();
+        store.set("b", "second").unwrap();
+
+        assert_eq!(SecretStore::file_only(path.clone(), None).get("a"), None);
+        let wrong = SecretStore::file_only(path.clone(), Some("wrong".to_string()));
+        assert_eq!(wrong.get("a"), None);
+
+        // 条目被互换后无法通过校验
+        let mut file: SecretsFile =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let a = file.entries["a"].clone();
+        file.entries.insert("b".to_string(), a);
+        fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+        let store = SecretStore::file_only(path, Some("correct horse".to_string()));
+        assert_eq!(store.get("a").as_deref(), Some("first"));
+        assert_eq!(store.get("b"), None);
+    }
+
+    #[test]
+    fn test_sealed_secrets_password() {
+        let secrets = vec![("polish.deepseek.apiKey".to_string(), "sk-456".to_string())];
+        let sealed = SealedSecrets::seal("backup pw", &secrets).unwrap();
+        let json = serde_json::to_string(&sealed).unwrap();
+        assert!(!json.contains("sk-456"));
+
+        let sealed: SealedSecrets = serde_json::from_str(&json).unwrap();
+        assert_eq!(sealed.open("backup pw").unwrap(), secrets);
+        assert!(sealed.open("wrong").is_err());
+    }
+}
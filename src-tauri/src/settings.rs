@@ -0,0 +1,870 @@
+//! 设置的类型定义、版本迁移与校验
+//!
+//! settings.json 中 app_settings / asr_settings / polish_settings 三段设置以本模块的类型为准：
+//! - 启动时按 settings_version 逐级迁移旧格式
+//! - 读取时宽松解析，无效的字段或列表项被丢弃并记录日志，其余设置照常生效
+//! - 保存时严格校验，错误带字段路径返回给前端
+//!
+//! 前端合并默认值后整段回传，未识别的字段保存在各结构的 extra 中原样保留。
+
+use crate::config::{
+    HotkeyBinding, HotkeyProfile, OutputMode, OutputRule, TranslationTarget, TriggerMode,
+};
+use crate::control::{ControlSettings, DEFAULT_CONTROL_PORT};
+use crate::corrections::CorrectionSettings;
+use crate::history::HistoryRetention;
+use crate::input::{ClipboardSettings, TypingSettings};
+use crate::normalize::NormalizeSettings;
+use crate::stats::CostSettings;
+use crate::voice_command::VoiceCommandSettings;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// 当前设置格式版本，修改格式时加一并在 MIGRATIONS 末尾追加迁移函数
+pub const SETTINGS_VERSION: u64 = 2;
+
+/// settings.json 中记录版本号的 key，没有该 key 的旧文件视为版本 1
+pub const VERSION_KEY: &str = "settings_version";
+
+pub const APP_SETTINGS_KEY: &str = "app_settings";
+pub const ASR_SETTINGS_KEY: &str = "asr_settings";
+pub const POLISH_SETTINGS_KEY: &str = "polish_settings";
+
+/// 待确认输出的默认超时时间
+pub const DEFAULT_PREVIEW_TIMEOUT_SECS: u64 = 120;
+
+/// 宽松解析时最多修复的错误数，防止异常数据导致死循环
+const MAX_REPAIRS: usize = 64;
+
+/// 通用设置（app_settings）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// 主快捷键标签，如 "右Ctrl"
+    pub toggle_hotkey: String,
+    pub toggle_hotkey_mode: TriggerMode,
+    pub transform_hotkey: String,
+    pub accept_output_hotkey: String,
+    pub undo_output_hotkey: String,
+    pub translation_hotkeys: Vec<TranslationHotkey>,
+    pub hotkey_profiles: Vec<HotkeyProfile>,
+    pub output_mode: OutputMode,
+    pub output_rules: Vec<OutputRule>,
+    pub clipboard: ClipboardSettings,
+    pub typing: TypingSettings,
+    pub voice_commands: VoiceCommandSettings,
+    pub normalize: NormalizeSettings,
+    pub smart_spacing: bool,
+    /// 待确认结果的超时秒数，0 表示不超时
+    pub preview_timeout_secs: u64,
+    pub auto_output: bool,
+    pub undo_reopens_preview: bool,
+    pub history_retention: HistoryRetention,
+    /// 价格表与月度预算，用于估算用量费用
+    pub cost: CostSettings,
+    /// 从用户修改中学习纠正
+    pub corrections: CorrectionSettings,
+    /// 本地控制接口
+    pub control: ControlSettings,
+    /// 麦克风设备名（空字符串表示默认设备）
+    pub microphone_device: String,
+    pub auto_start: bool,
+    pub device_id: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            toggle_hotkey: String::new(),
+            toggle_hotkey_mode: TriggerMode::default(),
+            transform_hotkey: String::new(),
+            accept_output_hotkey: String::new(),
+            undo_output_hotkey: String::new(),
+            translation_hotkeys: Vec::new(),
+            hotkey_profiles: Vec::new(),
+            output_mode: OutputMode::default(),
+            output_rules: Vec::new(),
+            clipboard: ClipboardSettings::default(),
+            typing: TypingSettings::default(),
+            voice_commands: VoiceCommandSettings::default(),
+            normalize: NormalizeSettings::default(),
+            smart_spacing: false,
+            preview_timeout_secs: DEFAULT_PREVIEW_TIMEOUT_SECS,
+            auto_output: true,
+            undo_reopens_preview: false,
+            history_retention: HistoryRetention::default(),
+            cost: CostSettings::default(),
+            corrections: CorrectionSettings::default(),
+            control: ControlSettings::default(),
+            microphone_device: String::new(),
+            auto_start: false,
+            device_id: String::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+/// 翻译快捷键（app_settings.translationHotkeys 的一项）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationHotkey {
+    #[serde(default)]
+    pub hotkey: String,
+    #[serde(flatten)]
+    pub target: TranslationTarget,
+}
+
+/// 语音识别设置（asr_settings）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AsrSettings {
+    /// 当前 ASR 服务商 id，如 "volcengine"、"sapi"
+    pub selected_provider: String,
+    pub providers: BTreeMap<String, AsrProviderSettings>,
+    /// 热词，所有服务商共用，支持的服务商随识别请求发送
+    pub hotwords: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for AsrSettings {
+    fn default() -> Self {
+        Self {
+            selected_provider: "volcengine".to_string(),
+            providers: BTreeMap::new(),
+            hotwords: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl AsrSettings {
+    /// 本次识别使用的服务商，快捷键方案的设置优先
+    pub fn provider_id<'a>(&'a self, profile: Option<&'a HotkeyProfile>) -> &'a str {
+        profile
+            .and_then(|p| p.asr_provider.as_deref())
+            .filter(|p| !p.is_empty())
+            .unwrap_or(&self.selected_provider)
+    }
+}
+
+/// 单个 ASR 服务商的配置，零配置引擎（如 sapi）各字段为空
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AsrProviderSettings {
+    pub app_id: String,
+    /// 保存在密钥存储中（见 secrets.rs），settings.json 中为空
+    pub access_key: String,
+    /// 识别语言: "zh", "en", "auto"
+    pub language: String,
+    pub auto_punctuation: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for AsrProviderSettings {
+    fn default() -> Self {
+        Self {
+            app_id: String::new(),
+            access_key: String::new(),
+            language: "zh".to_string(),
+            auto_punctuation: true,
+            extra: Map::new(),
+        }
+    }
+}
+
+/// 润色设置（polish_settings）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PolishSettings {
+    pub enabled: bool,
+    pub selected_provider_id: String,
+    pub selected_prompt_id: String,
+    pub providers: Vec<PolishProviderSettings>,
+    pub prompts: Vec<PolishPrompt>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl PolishSettings {
+    /// 按 id 查找润色服务商
+    pub fn provider(&self, id: &str) -> Option<&PolishProviderSettings> {
+        self.providers.iter().find(|p| p.id == id)
+    }
+
+    /// 按 id 查找提示词内容
+    pub fn prompt_content(&self, id: &str) -> Option<&str> {
+        self.prompts
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.content.as_str())
+    }
+}
+
+/// OpenAI 兼容的润色服务商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolishProviderSettings {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub base_url: String,
+    /// 保存在密钥存储中（见 secrets.rs），settings.json 中为空
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default = "default_temperature")]
+    pub temperature: f64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+fn default_temperature() -> f64 {
+    0.7
+}
+
+/// 润色提示词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolishPrompt {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 三段设置的完整视图
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub app: AppSettings,
+    pub asr: AsrSettings,
+    pub polish: PolishSettings,
+}
+
+impl Settings {
+    /// 从 settings.json 的内容宽松解析
+    pub fn from_map(data: &Map<String, Value>) -> Self {
+        let section = |key: &str| data.get(key).cloned().unwrap_or(Value::Null);
+        Self {
+            app: load_lenient(APP_SETTINGS_KEY, section(APP_SETTINGS_KEY)),
+            asr: load_lenient(ASR_SETTINGS_KEY, section(ASR_SETTINGS_KEY)),
+            polish: load_lenient(POLISH_SETTINGS_KEY, section(POLISH_SETTINGS_KEY)),
+        }
+    }
+}
+
+/// 宽松解析一段设置：逐个丢弃出错的字段或列表项后重试，缺失或无法修复时使用默认值
+pub fn load_lenient<T: DeserializeOwned + Default>(section: &str, mut value: Value) -> T {
+    if !value.is_object() {
+        return T::default();
+    }
+    for _ in 0..MAX_REPAIRS {
+        match serde_path_to_error::deserialize::<_, T>(&value) {
+            Ok(parsed) => return parsed,
+            Err(e) => {
+                log::warn!(
+                    "[settings] invalid {}.{}: {}, ignored",
+                    section,
+                    e.path(),
+                    e.inner()
+                );
+                let path: Vec<_> = e.path().iter().cloned().collect();
+                if !remove_at(&mut value, &path) {
+                    break;
+                }
+            }
+        }
+    }
+    T::default()
+}
+
+/// 删除路径指向的字段或列表项，路径为空或无法定位时返回 false
+fn remove_at(value: &mut Value, path: &[serde_path_to_error::Segment]) -> bool {
+    use serde_path_to_error::Segment;
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    let mut current = value;
+    for segment in parents {
+        let next = match segment {
+            Segment::Map { key } => current.get_mut(key.as_str()),
+            Segment::Seq { index } => current.get_mut(*index),
+            _ => None,
+        };
+        match next {
+            Some(next) => current = next,
+            None => return false,
+        }
+    }
+    match (last, current) {
+        (Segment::Map { key }, Value::Object(map)) => map.remove(key).is_some(),
+        (Segment::Seq { index }, Value::Array(items)) if *index < items.len() => {
+            items.remove(*index);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// ASR 服务商 Access Key 在密钥存储中的 id
+pub fn asr_secret_id(provider: &str) -> String {
+    format!("asr.{}.accessKey", provider)
+}
+
+/// 润色服务商 API Key 在密钥存储中的 id
+pub fn polish_secret_id(provider_id: &str) -> String {
+    format!("polish.{}.apiKey", provider_id)
+}
+
+/// 设置中的密钥字段：ASR 服务商的 accessKey 和润色服务商的 apiKey
+fn secret_fields(data: &mut Map<String, Value>) -> Vec<(String, &mut Map<String, Value>, &str)> {
+    let mut fields = Vec::new();
+    for (section, value) in data.iter_mut() {
+        match section.as_str() {
+            ASR_SETTINGS_KEY => {
+                let providers = value.get_mut("providers").and_then(|p| p.as_object_mut());
+                for (provider, config) in providers.into_iter().flatten() {
+                    if let Some(config) = config.as_object_mut() {
+                        fields.push((asr_secret_id(provider), config, "accessKey"));
+                    }
+                }
+            }
+            POLISH_SETTINGS_KEY => {
+                let providers = value.get_mut("providers").and_then(|p| p.as_array_mut());
+                for config in providers.into_iter().flatten() {
+                    let Some(config) = config.as_object_mut() else {
+                        continue;
+                    };
+                    let id = config.get("id").and_then(|v| v.as_str()).unwrap_or("");
+                    if !id.is_empty() {
+                        fields.push((polish_secret_id(id), config, "apiKey"));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// 从设置中取出密钥字段，返回 (密钥 id, 值)，值为空表示删除该密钥
+pub fn take_secrets(data: &mut Map<String, Value>) -> Vec<(String, String)> {
+    secret_fields(data)
+        .into_iter()
+        .filter_map(|(id, config, field)| match config.remove(field)? {
+            Value::String(value) => Some((id, value)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 把密钥填回设置，供前端显示和编辑
+pub fn fill_secrets(data: &mut Map<String, Value>, get: impl Fn(&str) -> Option<String>) {
+    for (id, config, field) in secret_fields(data) {
+        if let Some(value) = get(&id) {
+            config.insert(field.to_string(), Value::String(value));
+        }
+    }
+}
+
+/// 旧版本到新版本的迁移函数，第 i 项把版本 i + 1 升级到 i + 2
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v1_to_v2];
+
+/// v1 → v2：ASR 服务商配置中以 "true"/"false" 字符串保存的开关改为布尔值
+fn migrate_v1_to_v2(data: &mut Map<String, Value>) {
+    let Some(providers) = data
+        .get_mut(ASR_SETTINGS_KEY)
+        .and_then(|asr| asr.get_mut("providers"))
+        .and_then(|p| p.as_object_mut())
+    else {
+        return;
+    };
+    for config in providers.values_mut().filter_map(|c| c.as_object_mut()) {
+        for value in config.values_mut() {
+            let flag = match value.as_str() {
+                Some("true") => true,
+                Some("false") => false,
+                _ => continue,
+            };
+            *value = Value::Bool(flag);
+        }
+    }
+}
+
+/// 读取 settings.json 内容的版本号
+pub fn version_of(data: &Map<String, Value>) -> u64 {
+    data.get(VERSION_KEY).and_then(|v| v.as_u64()).unwrap_or(1)
+}
+
+/// 把设置逐级迁移到当前版本，返回是否有改动
+/// 来自更新版本的设置不做修改，按当前格式宽松读取
+pub fn migrate(data: &mut Map<String, Value>) -> Result<bool, String> {
+    let version = version_of(data);
+    if version == 0 {
+        return Err("settings version 0 is invalid".to_string());
+    }
+    if version > SETTINGS_VERSION {
+        return Err(format!(
+            "settings version {} is newer than supported version {}",
+            version, SETTINGS_VERSION
+        ));
+    }
+    if version == SETTINGS_VERSION {
+        return Ok(false);
+    }
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+        log::info!("[settings] migrating v{} -> v{}", step + 1, step + 2);
+        migration(data);
+    }
+    data.insert(VERSION_KEY.to_string(), Value::from(SETTINGS_VERSION));
+    Ok(true)
+}
+
+/// 严格解析一段设置，出错时返回带字段路径的错误
+fn parse_strict<T: DeserializeOwned>(section: &str, value: &Value) -> Result<T, String> {
+    serde_path_to_error::deserialize(value)
+        .map_err(|e| format!("{}.{}: {}", section, e.path(), e.inner()))
+}
+
+/// 保存前校验前端提交的设置段，返回所有错误
+/// 未提交的段不校验；提交的段必须能完整解析并通过取值检查
+pub fn validate(data: &Map<String, Value>) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    if let Some(value) = data.get(APP_SETTINGS_KEY) {
+        match parse_strict::<AppSettings>(APP_SETTINGS_KEY, value) {
+            Ok(app) => check_app_settings(&app, &mut errors),
+            Err(e) => errors.push(e),
+        }
+    }
+    if let Some(value) = data.get(ASR_SETTINGS_KEY) {
+        if let Err(e) = parse_strict::<AsrSettings>(ASR_SETTINGS_KEY, value) {
+            errors.push(e);
+        }
+    }
+    if let Some(value) = data.get(POLISH_SETTINGS_KEY) {
+        match parse_strict::<PolishSettings>(POLISH_SETTINGS_KEY, value) {
+            Ok(polish) => check_polish_settings(&polish, &mut errors),
+            Err(e) => errors.push(e),
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// 修正各段中无法通过 validate 的内容，用于把设置交给前端编辑
+///
+/// 能通过校验的段原样保留；否则宽松解析后清除或丢弃无效的取值，保证前端原样保存时能通过校验。
+pub fn repair(data: &mut Map<String, Value>) {
+    repair_section(data, APP_SETTINGS_KEY, repair_app_settings);
+    repair_section(data, ASR_SETTINGS_KEY, |_: &mut AsrSettings| {});
+    repair_section(data, POLISH_SETTINGS_KEY, repair_polish_settings);
+}
+
+fn repair_section<T: Serialize + DeserializeOwned + Default>(
+    data: &mut Map<String, Value>,
+    key: &str,
+    fix: impl FnOnce(&mut T),
+) {
+    let Some(value) = data.get(key) else {
+        return;
+    };
+    let section = Map::from_iter([(key.to_string(), value.clone())]);
+    let Err(errors) = validate(&section) else {
+        return;
+    };
+    log::warn!("[settings] repairing {}: {:?}", key, errors);
+    let mut parsed: T = load_lenient(key, value.clone());
+    fix(&mut parsed);
+    match serde_json::to_value(parsed) {
+        Ok(value) => {
+            data.insert(key.to_string(), value);
+        }
+        Err(e) => log::error!("[settings] failed to serialize repaired {}: {}", key, e),
+    }
+}
+
+/// 快捷键为空（未设置）或能被识别
+fn is_valid_hotkey(label: &str) -> bool {
+    label.trim().is_empty() || HotkeyBinding::parse_from_label(label).is_some()
+}
+
+/// 润色服务商地址为空（使用默认地址）或是 http/https 地址
+fn is_valid_base_url(base_url: &str) -> bool {
+    let base_url = base_url.trim();
+    base_url.is_empty()
+        || url::Url::parse(base_url)
+            .map(|u| matches!(u.scheme(), "http" | "https"))
+            .unwrap_or(false)
+}
+
+fn is_valid_temperature(temperature: f64) -> bool {
+    (0.0..=2.0).contains(&temperature)
+}
+
+fn check_hotkey(field: &str, label: &str, errors: &mut Vec<String>) {
+    if !is_valid_hotkey(label) {
+        errors.push(format!("{}: 无法识别的快捷键「{}」", field, label));
+    }
+}
+
+fn check_app_settings(app: &AppSettings, errors: &mut Vec<String>) {
+    check_hotkey("app_settings.toggleHotkey", &app.toggle_hotkey, errors);
+    check_hotkey(
+        "app_settings.transformHotkey",
+        &app.transform_hotkey,
+        errors,
+    );
+    check_hotkey(
+        "app_settings.acceptOutputHotkey",
+        &app.accept_output_hotkey,
+        errors,
+    );
+    check_hotkey(
+        "app_settings.undoOutputHotkey",
+        &app.undo_output_hotkey,
+        errors,
+    );
+    for (i, item) in app.translation_hotkeys.iter().enumerate() {
+        check_hotkey(
+            &format!("app_settings.translationHotkeys[{}].hotkey", i),
+            &item.hotkey,
+            errors,
+        );
+    }
+
+    let mut ids = std::collections::HashSet::new();
+    for (i, profile) in app.hotkey_profiles.iter().enumerate() {
+        let field = format!("app_settings.hotkeyProfiles[{}]", i);
+        if profile.id.is_empty() {
+            errors.push(format!("{}.id: 不能为空", field));
+        } else if !ids.insert(profile.id.as_str()) {
+            errors.push(format!("{}.id: 重复的方案 id「{}」", field, profile.id));
+        }
+        check_hotkey(&format!("{}.hotkey", field), &profile.hotkey, errors);
+    }
+
+    for (i, rule) in app.output_rules.iter().enumerate() {
+        if rule.app.trim().is_empty() {
+            errors.push(format!("app_settings.outputRules[{}].app: 不能为空", i));
+        }
+    }
+
+    if app.control.port == 0 {
+        errors.push("app_settings.control.port: 端口必须在 1-65535 之间".to_string());
+    }
+
+    for (i, entry) in app.normalize.dictionary.iter().enumerate() {
+        if entry.regex {
+            if let Err(e) = regex::Regex::new(&entry.pattern) {
+                errors.push(format!(
+                    "app_settings.normalize.dictionary[{}].pattern: 无效的正则表达式: {}",
+                    i, e
+                ));
+            }
+        }
+    }
+
+    errors.extend(
+        app.cost
+            .check()
+            .into_iter()
+            .map(|e| format!("app_settings.{}", e)),
+    );
+}
+
+/// 清除无法识别的快捷键，使其变为未设置
+fn clear_invalid_hotkey(label: &mut String) {
+    if !is_valid_hotkey(label) {
+        label.clear();
+    }
+}
+
+/// 修正 check_app_settings 报告的问题
+fn repair_app_settings(app: &mut AppSettings) {
+    clear_invalid_hotkey(&mut app.toggle_hotkey);
+    clear_invalid_hotkey(&mut app.transform_hotkey);
+    clear_invalid_hotkey(&mut app.accept_output_hotkey);
+    clear_invalid_hotkey(&mut app.undo_output_hotkey);
+    for item in &mut app.translation_hotkeys {
+        clear_invalid_hotkey(&mut item.hotkey);
+    }
+
+    let mut ids = std::collections::HashSet::new();
+    app.hotkey_profiles
+        .retain(|profile| !profile.id.is_empty() && ids.insert(profile.id.clone()));
+    for profile in &mut app.hotkey_profiles {
+        clear_invalid_hotkey(&mut profile.hotkey);
+    }
+
+    app.output_rules.retain(|rule| !rule.app.trim().is_empty());
+    if app.control.port == 0 {
+        app.control.port = DEFAULT_CONTROL_PORT;
+    }
+    app.normalize
+        .dictionary
+        .retain(|entry| !entry.regex || regex::Regex::new(&entry.pattern).is_ok());
+    app.cost.repair();
+}
+
+/// 修正 check_polish_settings 报告的问题：温度恢复默认，无效地址清空
+fn repair_polish_settings(polish: &mut PolishSettings) {
+    for provider in &mut polish.providers {
+        if !is_valid_temperature(provider.temperature) {
+            provider.temperature = default_temperature();
+        }
+        if !is_valid_base_url(&provider.base_url) {
+            provider.base_url.clear();
+        }
+    }
+}
+
+fn check_polish_settings(polish: &PolishSettings, errors: &mut Vec<String>) {
+    for (i, provider) in polish.providers.iter().enumerate() {
+        let field = format!("polish_settings.providers[{}]", i);
+        if !is_valid_temperature(provider.temperature) {
+            errors.push(format!("{}.temperature: 取值范围为 0 ~ 2", field));
+        }
+        if !is_valid_base_url(&provider.base_url) {
+            errors.push(format!(
+                "{}.baseUrl: 无效的地址「{}」",
+                field,
+                provider.base_url.trim()
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_lenient_load_drops_only_invalid_items() {
+        let app: AppSettings = load_lenient(
+            APP_SETTINGS_KEY,
+            json!({
+                "toggleHotkey": "右Ctrl",
+                "outputMode": "Bogus",
+                "previewTimeoutSecs": "soon",
+                "hotkeyProfiles": [
+                    {"id": "meeting", "hotkey": "左Alt + M", "language": 5},
+                    {"hotkey": "左Alt + P"},
+                    {"id": "mail", "hotkey": "左Alt + E"},
+                ],
+                "clipboard": {"restoreDelayMs": 300, "maxWaitMs": -1},
+                "liveWindows": [{"id": "w"}],
+            }),
+        );
+        assert_eq!(app.toggle_hotkey, "右Ctrl");
+        assert_eq!(app.output_mode, OutputMode::Clipboard);
+        assert_eq!(app.preview_timeout_secs, DEFAULT_PREVIEW_TIMEOUT_SECS);
+        let ids: Vec<_> = app.hotkey_profiles.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, ["meeting", "mail"]);
+        assert_eq!(app.hotkey_profiles[0].language, None);
+        assert_eq!(app.clipboard.restore_delay_ms, 300);
+        assert_eq!(app.clipboard.max_wait_ms, 1000);
+        assert_eq!(app.extra["liveWindows"], json!([{"id": "w"}]));
+
+        let app: AppSettings = load_lenient(APP_SETTINGS_KEY, Value::Null);
+        assert!(app.auto_output);
+    }
+
+    #[test]
+    fn test_unknown_fields_round_trip() {
+        let value = json!({
+            "selectedProvider": "sapi",
+            "providers": {"volcengine": {"appId": "a", "region": "cn"}},
+            "future": 1,
+        });
+        let asr: AsrSettings = load_lenient(ASR_SETTINGS_KEY, value);
+        let saved = serde_json::to_value(&asr).unwrap();
+        assert_eq!(saved["future"], json!(1));
+        assert_eq!(saved["providers"]["volcengine"]["region"], json!("cn"));
+        assert_eq!(
+            saved["providers"]["volcengine"]["autoPunctuation"],
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn test_migrate_runs_each_step_once() {
+        let mut data = json!({
+            "asr_settings": {
+                "providers": {"volcengine": {"language": "en", "autoPunctuation": "false"}},
+            },
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+        assert_eq!(migrate(&mut data), Ok(true));
+        assert_eq!(version_of(&data), SETTINGS_VERSION);
+        assert_eq!(
+            data["asr_settings"]["providers"]["volcengine"]["autoPunctuation"],
+            json!(false)
+        );
+        assert_eq!(migrate(&mut data), Ok(false));
+
+        data.insert(VERSION_KEY.to_string(), json!(SETTINGS_VERSION + 1));
+        assert!(migrate(&mut data).is_err());
+    }
+
+    #[test]
+    fn test_migrate_rejects_version_zero() {
+        let mut data = json!({"settings_version": 0, "app_settings": {}})
+            .as_object()
+            .cloned()
+            .unwrap();
+        assert!(migrate(&mut data).is_err());
+        assert_eq!(data["settings_version"], json!(0));
+    }
+
+    #[test]
+    fn test_take_and_fill_secrets() {
+        let mut data = json!({
+            "asr_settings": {
+                "providers": {"volcengine": {"appId": "1", "accessKey": "ak"}, "sapi": {}},
+            },
+            "polish_settings": {
+                "providers": [{"id": "p", "apiKey": "sk"}, {"id": "q", "apiKey": ""}],
+            },
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+        let taken = take_secrets(&mut data);
+        assert_eq!(
+            taken,
+            [
+                ("asr.volcengine.accessKey".to_string(), "ak".to_string()),
+                ("polish.p.apiKey".to_string(), "sk".to_string()),
+                ("polish.q.apiKey".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(
+            data["asr_settings"]["providers"]["volcengine"],
+            json!({"appId": "1"})
+        );
+        assert!(take_secrets(&mut data).is_empty());
+
+        fill_secrets(&mut data, |id| {
+            (id == "polish.p.apiKey").then(|| "sk".to_string())
+        });
+        assert_eq!(
+            data["polish_settings"]["providers"][0]["apiKey"],
+            json!("sk")
+        );
+        assert!(data["polish_settings"]["providers"][1]
+            .get("apiKey")
+            .is_none());
+    }
+
+    #[test]
+    fn test_validate_reports_field_paths() {
+        let data = json!({
+            "app_settings": {
+                "toggleHotkey": "not a hotkey",
+                "hotkeyProfiles": [
+                    {"id": "a", "hotkey": "左Alt + A"},
+                    {"id": "a", "hotkey": "左Alt + B"},
+                ],
+                "normalize": {"dictionary": [{"pattern": "(", "replacement": "", "regex": true}]},
+                "cost": {"monthlyBudget": -5},
+            },
+            "asr_settings": {"providers": {"volcengine": {"autoPunctuation": "true"}}},
+            "polish_settings": {"providers": [{"id": "p", "baseUrl": "ftp://x", "temperature": 3}]},
+        });
+        let errors = validate(data.as_object().unwrap()).unwrap_err();
+        let fields: Vec<_> = errors
+            .iter()
+            .map(|e| e.split(':').next().unwrap())
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "app_settings.toggleHotkey",
+                "app_settings.hotkeyProfiles[1].id",
+                "app_settings.normalize.dictionary[0].pattern",
+                "app_settings.cost.monthlyBudget",
+                "asr_settings.providers.volcengine.autoPunctuation",
+                "polish_settings.providers[0].temperature",
+                "polish_settings.providers[0].baseUrl",
+            ]
+        );
+
+        let data = json!({"app_settings": {"toggleHotkey": "右Ctrl", "undoOutputHotkey": ""}});
+        assert_eq!(validate(data.as_object().unwrap()), Ok(()));
+    }
+
+    #[test]
+    fn test_repair_makes_sections_valid() {
+        let mut data = json!({
+            "app_settings": {
+                "toggleHotkey": "not a hotkey",
+                "hotkeyProfiles": [
+                    {"id": "a", "hotkey": "左Alt + A"},
+                    {"id": "a", "hotkey": "左Alt + B"},
+                    {"id": "b", "hotkey": "???"},
+                ],
+                "outputRules": [{"app": " ", "mode": "Clipboard"}],
+                "control": {"enabled": true, "port": 0},
+                "normalize": {"dictionary": [
+                    {"pattern": "(", "replacement": "", "regex": true},
+                    {"pattern": "(", "replacement": ")"},
+                ]},
+                "cost": {"monthlyBudget": -5},
+                "liveWindows": [1],
+            },
+            "asr_settings": {"providers": {"volcengine": {"autoPunctuation": "true"}}},
+            "polish_settings": {
+                "enabled": true,
+                "providers": [{"id": "p", "baseUrl": "ftp://x", "temperature": 3}],
+            },
+        });
+        let data = data.as_object_mut().unwrap();
+        repair(data);
+        assert_eq!(validate(data), Ok(()));
+
+        let settings = Settings::from_map(data);
+        assert_eq!(settings.app.toggle_hotkey, "");
+        let profiles: Vec<_> = settings
+            .app
+            .hotkey_profiles
+            .iter()
+            .map(|p| (p.id.as_str(), p.hotkey.as_str()))
+            .collect();
+        assert_eq!(profiles, [("a", "左Alt + A"), ("b", "")]);
+        assert!(settings.app.output_rules.is_empty());
+        assert!(settings.app.control.enabled);
+        assert_eq!(settings.app.control.port, DEFAULT_CONTROL_PORT);
+        assert_eq!(settings.app.normalize.dictionary.len(), 1);
+        assert_eq!(settings.app.cost.monthly_budget, 0.0);
+        assert_eq!(settings.app.extra["liveWindows"], json!([1]));
+        assert!(settings.polish.enabled);
+        assert_eq!(
+            settings.polish.providers[0].temperature,
+            default_temperature()
+        );
+        assert_eq!(settings.polish.providers[0].base_url, "");
+
+        // 能通过校验的段原样保留
+        let mut data = json!({"app_settings": {"toggleHotkey": "右Ctrl"}});
+        let data = data.as_object_mut().unwrap();
+        let before = data.clone();
+        repair(data);
+        assert_eq!(*data, before);
+    }
+}
@@ -176,10 +176,6 @@ struct ShareServerHandle {
 
 pub struct ShareManager {
     app: tauri::AppHandle,
-    data_path: PathBuf,
-    settings_path: PathBuf,
-    upload_path: PathBuf,
-    content_path: PathBuf,
     configured_port: Mutex<u16>,
     selected_host: Mutex<String>,
     upload_save_dir: Mutex<Option<PathBuf>>,
@@ -194,24 +190,16 @@ impl ShareManager {
     pub fn init(app: tauri::AppHandle) -> Result<Arc<Self>, String> {
         let base = store::base_dir();
         fs::create_dir_all(&base).map_err(|e| format!("创建共享数据目录失败: {}", e))?;
-        let data_path = base.join(SHARE_FILE);
-        let settings_path = base.join(SHARE_SETTINGS_FILE);
-        let upload_path = base.join(SHARE_UPLOAD_FILE);
-        let content_path = base.join(SHARE_CONTENT_FILE);
-        let files = read_files(&data_path)?;
-        let uploads = read_uploads(&upload_path)?;
-        let contents = read_contents(&content_path)?;
-        let settings = read_settings(&settings_path)?;
+        let files = read_files(&base.join(SHARE_FILE))?;
+        let uploads = read_uploads(&base.join(SHARE_UPLOAD_FILE))?;
+        let contents = read_contents(&base.join(SHARE_CONTENT_FILE))?;
+        let settings = read_settings(&base.join(SHARE_SETTINGS_FILE))?;
         let port = normalize_port(settings.port)?;
         let candidates = host_candidates(port, "");
         let selected_host = choose_host(settings.selected_host.as_deref(), &candidates);
 
         let manager = Arc::new(Self {
             app,
-            data_path,
-            settings_path,
-            upload_path,
-            content_path,
             configured_port: Mutex::new(port),
             selected_host: Mutex::new(selected_host),
             upload_save_dir: Mutex::new(settings.upload_save_dir),
@@ -226,6 +214,34 @@ impl ShareManager {
         Ok(manager)
     }
 
+    /// 数据目录迁移后从新位置重新读取共享列表，运行中的服务不受影响
+    pub fn reload(&self) -> Result<(), String> {
+        let base = store::base_dir();
+        let files = read_files(&base.join(SHARE_FILE))?;
+        let uploads = read_uploads(&base.join(SHARE_UPLOAD_FILE))?;
+        let contents = read_contents(&base.join(SHARE_CONTENT_FILE))?;
+        *self.files.lock().unwrap_or_else(|e| e.into_inner()) = files;
+        *self.uploads.lock().unwrap_or_else(|e| e.into_inner()) = uploads;
+        *self.contents.lock().unwrap_or_else(|e| e.into_inner()) = contents;
+        Ok(())
+    }
+
+    fn data_path(&self) -> PathBuf {
+        store::base_dir().join(SHARE_FILE)
+    }
+
+    fn settings_path(&self) -> PathBuf {
+        store::base_dir().join(SHARE_SETTINGS_FILE)
+    }
+
+    fn upload_path(&self) -> PathBuf {
+        store::base_dir().join(SHARE_UPLOAD_FILE)
+    }
+
+    fn content_path(&self) -> PathBuf {
+        store::base_dir().join(SHARE_CONTENT_FILE)
+    }
+
     pub fn state(&self) -> ShareServerState {
         let running_port = self
             .server
@@ -384,7 +400,7 @@ impl ShareManager {
         if files.len() > MAX_FILES {
             files.truncate(MAX_FILES);
         }
-        write_json(&self.data_path, &*files)?;
+        write_json(&self.data_path(), &*files)?;
         drop(files);
 
         Ok(self.state())
@@ -393,7 +409,7 @@ impl ShareManager {
     pub fn remove_file(&self, id: String) -> Result<ShareServerState, String> {
         let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
         files.retain(|item| item.id != id);
-        write_json(&self.data_path, &*files)?;
+        write_json(&self.data_path(), &*files)?;
         drop(files);
         Ok(self.state())
     }
@@ -401,7 +417,7 @@ impl ShareManager {
     pub fn clear_files(&self) -> Result<ShareServerState, String> {
         let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
         files.clear();
-        write_json(&self.data_path, &*files)?;
+        write_json(&self.data_path(), &*files)?;
         drop(files);
         Ok(self.state())
     }
@@ -457,7 +473,7 @@ impl ShareManager {
                     | ShareUploadStatus::Uploading
             )
         });
-        write_json(&self.upload_path, &*uploads)?;
+        write_json(&self.upload_path(), &*uploads)?;
         drop(uploads);
         Ok(self.state())
     }
@@ -478,7 +494,7 @@ impl ShareManager {
             }
         }
         uploads.remove(index);
-        write_json(&self.upload_path, &*uploads)?;
+        write_json(&self.upload_path(), &*uploads)?;
         drop(uploads);
         Ok(self.state())
     }
@@ -491,7 +507,7 @@ impl ShareManager {
     pub fn remove_content(&self, id: String) -> Result<ShareServerState, String> {
         let mut contents = self.contents.lock().unwrap_or_else(|e| e.into_inner());
         contents.retain(|item| item.id != id);
-        write_json(&self.content_path, &*contents)?;
+        write_json(&self.content_path(), &*contents)?;
         drop(contents);
         Ok(self.state())
     }
@@ -499,7 +515,7 @@ impl ShareManager {
     pub fn clear_contents(&self) -> Result<ShareServerState, String> {
         let mut contents = self.contents.lock().unwrap_or_else(|e| e.into_inner());
         contents.clear();
-        write_json(&self.content_path, &*contents)?;
+        write_json(&self.content_path(), &*contents)?;
         drop(contents);
         Ok(self.state())
     }
@@ -537,7 +553,7 @@ impl ShareManager {
         if contents.len() > MAX_CONTENT_RECORDS {
             contents.truncate(MAX_CONTENT_RECORDS);
         }
-        write_json(&self.content_path, &*contents)?;
+        write_json(&self.content_path(), &*contents)?;
         drop(contents);
 
         let _ = self.app.emit("share-content-changed", &item);
@@ -547,8 +563,39 @@ impl ShareManager {
         Ok(item)
     }
 
-    fn save_settings(&self) -> Result<(), String> {
-        let settings = ShareSettings {
+    /// 导出共享设置，用于备份
+    pub fn export_settings(&self) -> serde_json::Value {
+        serde_json::to_value(self.current_settings()).unwrap_or_default()
+    }
+
+    /// 校验待导入的共享设置，不修改现有设置
+    pub fn check_import(value: &serde_json::Value) -> Result<(), String> {
+        parse_imported_settings(value.clone()).map(|_| ())
+    }
+
+    /// 从备份导入共享设置；本机不存在的上传目录被忽略，合并时保留当前目录
+    pub fn import_settings(&self, value: serde_json::Value, replace: bool) -> Result<(), String> {
+        let imported = parse_imported_settings(value)?;
+        let port = imported.port;
+        *self
+            .configured_port
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = port;
+        if let Some(host) = imported.selected_host {
+            *self.selected_host.lock().unwrap_or_else(|e| e.into_inner()) = host;
+        }
+        let upload_save_dir = imported.upload_save_dir.filter(|path| path.is_dir());
+        if replace || upload_save_dir.is_some() {
+            *self
+                .upload_save_dir
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = upload_save_dir;
+        }
+        self.save_settings()
+    }
+
+    fn current_settings(&self) -> ShareSettings {
+        ShareSettings {
             selected_host: Some(
                 self.selected_host
                     .lock()
@@ -564,8 +611,11 @@ impl ShareManager {
                 .lock()
                 .unwrap_or_else(|e| e.into_inner())
                 .clone(),
-        };
-        write_json(&self.settings_path, &settings)
+        }
+    }
+
+    fn save_settings(&self) -> Result<(), String> {
+        write_json(&self.settings_path(), &self.current_settings())
     }
 
     fn validate_upload_dir(&self) -> Result<PathBuf, String> {
@@ -605,7 +655,7 @@ impl ShareManager {
         record.updated_at = now_string();
         record.error = error;
         let record = record.clone();
-        write_json(&self.upload_path, &*uploads)?;
+        write_json(&self.upload_path(), &*uploads)?;
         let _ = self.app.emit("share-upload-changed", &record);
         Ok(())
     }
@@ -636,7 +686,7 @@ impl ShareManager {
             .filter(|item| item.batch_id == batch_id)
             .cloned()
             .collect();
-        write_json(&self.upload_path, &*uploads)?;
+        write_json(&self.upload_path(), &*uploads)?;
         for record in changed_records {
             let _ = self.app.emit("share-upload-changed", &record);
         }
@@ -995,7 +1045,7 @@ impl ShareManager {
             if uploads.len() > MAX_UPLOAD_RECORDS {
                 uploads.truncate(MAX_UPLOAD_RECORDS);
             }
-            if let Err(e) = write_json(&self.upload_path, &*uploads) {
+            if let Err(e) = write_json(&self.upload_path(), &*uploads) {
                 return write_text_response(
                     reader.get_mut(),
                     500,
@@ -1253,7 +1303,7 @@ impl ShareManager {
             record.received = 0;
             record.updated_at = now_string();
             let record = record.clone();
-            let _ = write_json(&self.upload_path, &*uploads);
+            let _ = write_json(&self.upload_path(), &*uploads);
             record
         };
         let _ = self.app.emit("share-upload-progress", &record);
@@ -1285,7 +1335,7 @@ impl ShareManager {
             record.completed_at = Some(record.updated_at.clone());
             record.error = None;
             let record = record.clone();
-            let _ = write_json(&self.upload_path, &*uploads);
+            let _ = write_json(&self.upload_path(), &*uploads);
             record
         };
         let _ = self.app.emit("share-upload-finished", &record);
@@ -1302,7 +1352,7 @@ impl ShareManager {
             record.completed_at = Some(record.updated_at.clone());
             record.error = Some(error);
             let record = record.clone();
-            let _ = write_json(&self.upload_path, &*uploads);
+            let _ = write_json(&self.upload_path(), &*uploads);
             record
         };
         let _ = self.app.emit("share-upload-finished", &record);
@@ -2224,6 +2274,13 @@ pub fn cmd_clear_share_contents(
     manager.clear_contents()
 }
 
+fn parse_imported_settings(value: serde_json::Value) -> Result<ShareSettings, String> {
+    let mut imported: ShareSettings =
+        serde_json::from_value(value).map_err(|e| format!("解析共享设置失败: {}", e))?;
+    imported.port = normalize_port(imported.port)?;
+    Ok(imported)
+}
+
 fn normalize_port(port: u16) -> Result<u16, String> {
     if port == 0 {
         Err("端口必须在 1-65535 之间".to_string())
@@ -2405,13 +2462,11 @@ fn normalize_path(path: PathBuf) -> PathBuf {
     path
 }
 
-fn write_json<T: serde::Serialize>(path: &PathBuf, value: &T) -> Result<(), String> {
+fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> Result<(), String> {
     let json =
         serde_json::to_string_pretty(value).map_err(|e| format!("序列化共享数据失败: {}", e))?;
-    let tmp_path = path.with_extension("json.tmp");
-    fs::write(&tmp_path, json).map_err(|e| format!("写入 {} 失败: {}", tmp_path.display(), e))?;
-    fs::rename(&tmp_path, path).map_err(|e| format!("保存 {} 失败: {}", path.display(), e))?;
-    Ok(())
+    store::write_file_atomic(path, json.as_bytes())
+        .map_err(|e| format!("保存 {} 失败: {}", path.display(), e))
 }
 
 fn header_value<'a>(headers: &'a [(String, String)], key: &str) -> Option<&'a str> {
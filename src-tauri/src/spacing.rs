@@ -0,0 +1,167 @@
+/// 句末标点
+const SENTENCE_END: &[char] = &['.', '?', '!', '。', '？', '！', '…'];
+/// 中文句末标点，其后的英文单词不自动大写（如 "。iPhone"）
+const CJK_SENTENCE_END: &[char] = &['。', '？', '！', '…'];
+/// 句末标点之后可能跟随的右引号、右括号
+const CLOSING: &[char] = &['"', '\'', ')', ']', '}', '”', '’', '）', '」', '』', '》'];
+/// 其后不需要补空格的左括号、左引号和连接符
+/// 直引号无法区分左右，按右引号处理
+const OPENING: &[char] = &['(', '[', '{', '“', '‘', '（', '「', '『', '《', '/', '-'];
+/// 前文以句末标点结尾时，本次开头可丢弃的标点
+const LEADING_DUPLICATE: &[char] = &[
+    '.', '?', '!', '。', '？', '！', ',', '，', ';', '；', ':', '：', '、',
+];
+
+/// 智能空格和大小写使用的语言规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpacingLanguage {
+    Zh,
+    En,
+}
+
+impl SpacingLanguage {
+    /// 按识别语言（AsrConfig.language）选择规则，"auto" 时按文本是否含中日韩文字判断
+    pub fn from_asr_language(language: &str, text: &str) -> Self {
+        match language {
+            "en" => SpacingLanguage::En,
+            "zh" => SpacingLanguage::Zh,
+            _ if text.chars().any(is_cjk) => SpacingLanguage::Zh,
+            _ => SpacingLanguage::En,
+        }
+    }
+}
+
+/// 中日韩文字及全角标点，两侧不加空格
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303F}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
+/// 去掉末尾空白和右引号、右括号后的文本
+fn trim_closing(text: &str) -> &str {
+    text.trim_end().trim_end_matches(CLOSING)
+}
+
+fn ends_sentence(text: &str) -> bool {
+    trim_closing(text).ends_with(SENTENCE_END)
+}
+
+/// 前文末尾与本次开头之间是否需要补空格
+fn needs_space(prev: char, next: char) -> bool {
+    if prev.is_whitespace() || next.is_whitespace() {
+        return false;
+    }
+    if OPENING.contains(&prev) || is_cjk(prev) || is_cjk(next) {
+        return false;
+    }
+    // 本次以标点开头（如 ", and"）时紧贴前文
+    !next.is_ascii_punctuation() || OPENING.contains(&next)
+}
+
+/// 首个非空白字符转为大写
+fn capitalize_first(text: &str) -> String {
+    let rest = text.trim_start();
+    let mut chars = rest.chars();
+    match chars.next() {
+        Some(first) => {
+            let leading = &text[..text.len() - rest.len()];
+            leading
+                .chars()
+                .chain(first.to_uppercase())
+                .chain(chars)
+                .collect()
+        }
+        None => text.to_string(),
+    }
+}
+
+/// 按光标前的文本调整本次输出：补前导空格、句首字母大写、丢弃重复的句末标点
+/// previous 为光标前的文本（通常是上一次输出），为空时原样返回
+pub fn apply(previous: &str, text: &str, language: SpacingLanguage) -> String {
+    let Some(prev_char) = previous.chars().last() else {
+        return text.to_string();
+    };
+
+    let sentence_ended = ends_sentence(previous);
+    let text = match text.trim_start_matches(LEADING_DUPLICATE) {
+        stripped if sentence_ended && stripped.len() != text.len() => stripped.trim_start(),
+        _ => text,
+    };
+    let Some(first) = text.chars().next() else {
+        return String::new();
+    };
+
+    let capitalize = sentence_ended
+        && text.trim_start().starts_with(char::is_lowercase)
+        && !(language == SpacingLanguage::Zh && trim_closing(previous).ends_with(CJK_SENTENCE_END));
+    let mut result = if capitalize {
+        capitalize_first(text)
+    } else {
+        text.to_string()
+    };
+
+    if needs_space(prev_char, first) {
+        result.insert(0, ' ');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EN: SpacingLanguage = SpacingLanguage::En;
+    const ZH: SpacingLanguage = SpacingLanguage::Zh;
+
+    #[test]
+    fn test_language_from_asr_setting() {
+        assert_eq!(SpacingLanguage::from_asr_language("en", "你好"), EN);
+        assert_eq!(SpacingLanguage::from_asr_language("zh", "hello"), ZH);
+        assert_eq!(SpacingLanguage::from_asr_language("auto", "hello"), EN);
+        assert_eq!(SpacingLanguage::from_asr_language("auto", "你好 world"), ZH);
+    }
+
+    #[test]
+    fn test_english_spacing_and_casing() {
+        assert_eq!(apply("", "hello.", EN), "hello.");
+        assert_eq!(apply("Hello.", "world.", EN), " World.");
+        assert_eq!(apply("Hello,", "world", EN), " world");
+        assert_eq!(apply("Hello.", " world", EN), " World");
+        assert_eq!(apply("Hello", "world", EN), " world");
+        assert_eq!(apply("He said \"stop.\"", "then", EN), " Then");
+        // 前文已有空格或左括号
+        assert_eq!(apply("Hello ", "world", EN), "world");
+        assert_eq!(apply("call (", "maybe", EN), "maybe");
+        // 本次以标点开头
+        assert_eq!(apply("Hello", ", world", EN), ", world");
+    }
+
+    #[test]
+    fn test_drop_duplicate_terminal_punctuation() {
+        assert_eq!(apply("Hello.", ". world", EN), " World");
+        assert_eq!(apply("你好。", "。再见", ZH), "再见");
+        assert_eq!(apply("Done!", ".", EN), "");
+        // 前文未结束句子时保留标点
+        assert_eq!(apply("Hello", ".", EN), ".");
+    }
+
+    #[test]
+    fn test_chinese_conventions() {
+        assert_eq!(apply("你好", "世界", ZH), "世界");
+        assert_eq!(apply("你好。", "世界。", ZH), "世界。");
+        // 中文句号后的英文单词不自动大写，也不加空格
+        assert_eq!(
+            apply("我买了新手机。", "iPhone 很好用", ZH),
+            "iPhone 很好用"
+        );
+        // 英文单词之间仍需要空格
+        assert_eq!(apply("打开 Visual", "studio", ZH), " studio");
+        assert_eq!(apply("It works.", "ok", ZH), " Ok");
+    }
+}
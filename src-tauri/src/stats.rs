@@ -0,0 +1,321 @@
+//! 用量统计：按天、按服务商记录识别时长与润色 token，并按用户填写的价格表估算费用
+//!
+//! 明细保存在 stats.json 的 daily 字段，按日期（YYYY-MM-DD）分组，
+//! 原有的 total_* 累计值保留，供首页展示。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// stats.json 中按天明细的键
+pub const DAILY_KEY: &str = "daily";
+
+/// stats.json 中记录已提醒超出预算的月份（YYYY-MM）的键
+pub const BUDGET_WARNED_KEY: &str = "budget_warned_month";
+
+/// 某个 ASR 服务商的识别用量
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AsrUsage {
+    pub count: u64,
+    /// 识别的音频时长（秒）
+    pub seconds: f64,
+    pub chars: u64,
+}
+
+/// 某个润色服务商的 token 用量，服务商未返回 usage 时只累计请求数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PolishUsage {
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// 一天内的用量，按服务商 id 分组
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DayUsage {
+    pub asr: BTreeMap<String, AsrUsage>,
+    pub polish: BTreeMap<String, PolishUsage>,
+}
+
+/// 按日期（YYYY-MM-DD）分组的用量明细
+pub type DailyUsage = BTreeMap<String, DayUsage>;
+
+/// 润色服务商每百万 token 的价格
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TokenPrice {
+    pub input: f64,
+    pub output: f64,
+}
+
+/// 价格表与月度预算，保存在通用设置的 cost 字段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CostSettings {
+    /// 货币符号，仅用于显示
+    pub currency: String,
+    /// 每月预算，0 表示不提醒
+    pub monthly_budget: f64,
+    /// ASR 服务商每小时音频的价格，按服务商 id
+    pub asr_prices: BTreeMap<String, f64>,
+    /// 润色服务商每百万 token 的价格，按服务商 id
+    pub polish_prices: BTreeMap<String, TokenPrice>,
+}
+
+impl Default for CostSettings {
+    fn default() -> Self {
+        Self {
+            currency: "¥".to_string(),
+            monthly_budget: 0.0,
+            asr_prices: BTreeMap::new(),
+            polish_prices: BTreeMap::new(),
+        }
+    }
+}
+
+impl CostSettings {
+    pub fn asr_cost(&self, provider: &str, usage: &AsrUsage) -> f64 {
+        let per_hour = self.asr_prices.get(provider).copied().unwrap_or(0.0);
+        usage.seconds / 3600.0 * per_hour
+    }
+
+    pub fn polish_cost(&self, provider: &str, usage: &PolishUsage) -> f64 {
+        let Some(price) = self.polish_prices.get(provider) else {
+            return 0.0;
+        };
+        (usage.prompt_tokens as f64 * price.input + usage.completion_tokens as f64 * price.output)
+            / 1_000_000.0
+    }
+
+    pub fn day_cost(&self, day: &DayUsage) -> f64 {
+        let asr: f64 = day.asr.iter().map(|(p, u)| self.asr_cost(p, u)).sum();
+        let polish: f64 = day.polish.iter().map(|(p, u)| self.polish_cost(p, u)).sum();
+        asr + polish
+    }
+
+    /// 价格表中所有价格都应为非负有限数
+    pub fn check(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut check = |field: String, value: f64| {
+            if !value.is_finite() || value < 0.0 {
+                errors.push(format!("{}: 必须是非负数", field));
+            }
+        };
+        check("cost.monthlyBudget".to_string(), self.monthly_budget);
+        for (provider, price) in &self.asr_prices {
+            check(format!("cost.asrPrices.{}", provider), *price);
+        }
+        for (provider, price) in &self.polish_prices {
+            check(format!("cost.polishPrices.{}.input", provider), price.input);
+            check(
+                format!("cost.polishPrices.{}.output", provider),
+                price.output,
+            );
+        }
+        errors
+    }
+
+    /// 把 check 报告的无效预算和价格归零
+    pub fn repair(&mut self) {
+        let fix = |value: &mut f64| {
+            if !value.is_finite() || *value < 0.0 {
+                *value = 0.0;
+            }
+        };
+        fix(&mut self.monthly_budget);
+        self.asr_prices.values_mut().for_each(fix);
+        for price in self.polish_prices.values_mut() {
+            fix(&mut price.input);
+            fix(&mut price.output);
+        }
+    }
+}
+
+/// 累加一次识别的用量
+pub fn add_asr(daily: &mut DailyUsage, date: &str, provider: &str, seconds: f64, chars: u64) {
+    let usage = daily
+        .entry(date.to_string())
+        .or_default()
+        .asr
+        .entry(provider.to_string())
+        .or_default();
+    usage.count += 1;
+    usage.seconds += seconds.max(0.0);
+    usage.chars += chars;
+}
+
+/// 累加一次润色请求的 token 用量
+pub fn add_polish(
+    daily: &mut DailyUsage,
+    date: &str,
+    provider: &str,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+) {
+    let usage = daily
+        .entry(date.to_string())
+        .or_default()
+        .polish
+        .entry(provider.to_string())
+        .or_default();
+    usage.requests += 1;
+    usage.prompt_tokens += prompt_tokens;
+    usage.completion_tokens += completion_tokens;
+}
+
+/// 某个月（YYYY-MM）的估算费用
+pub fn month_cost(daily: &DailyUsage, month: &str, cost: &CostSettings) -> f64 {
+    daily
+        .iter()
+        .filter(|(date, _)| date.starts_with(month))
+        .map(|(_, day)| cost.day_cost(day))
+        .sum()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayReport {
+    pub date: String,
+    #[serde(flatten)]
+    pub usage: DayUsage,
+    pub cost: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderReport<T> {
+    pub provider: String,
+    #[serde(flatten)]
+    pub usage: T,
+    pub cost: f64,
+}
+
+/// 日期范围内的用量报表，按天和按服务商汇总
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub days: Vec<DayReport>,
+    pub asr_providers: Vec<ProviderReport<AsrUsage>>,
+    pub polish_providers: Vec<ProviderReport<PolishUsage>>,
+    pub total_cost: f64,
+    pub currency: String,
+}
+
+/// 汇总 [from, to] 范围内的用量，任一端为空表示不限制
+pub fn report(
+    daily: &DailyUsage,
+    from: Option<&str>,
+    to: Option<&str>,
+    cost: &CostSettings,
+) -> UsageReport {
+    let mut asr: BTreeMap<String, AsrUsage> = BTreeMap::new();
+    let mut polish: BTreeMap<String, PolishUsage> = BTreeMap::new();
+    let mut days = Vec::new();
+    let in_range =
+        |date: &str| from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to);
+    for (date, day) in daily.iter().filter(|(date, _)| in_range(date)) {
+        for (provider, usage) in &day.asr {
+            let total = asr.entry(provider.clone()).or_default();
+            total.count += usage.count;
+            total.seconds += usage.seconds;
+            total.chars += usage.chars;
+        }
+        for (provider, usage) in &day.polish {
+            let total = polish.entry(provider.clone()).or_default();
+            total.requests += usage.requests;
+            total.prompt_tokens += usage.prompt_tokens;
+            total.completion_tokens += usage.completion_tokens;
+        }
+        days.push(DayReport {
+            date: date.clone(),
+            usage: day.clone(),
+            cost: cost.day_cost(day),
+        });
+    }
+
+    let asr_providers: Vec<_> = asr
+        .into_iter()
+        .map(|(provider, usage)| ProviderReport {
+            cost: cost.asr_cost(&provider, &usage),
+            provider,
+            usage,
+        })
+        .collect();
+    let polish_providers: Vec<_> = polish
+        .into_iter()
+        .map(|(provider, usage)| ProviderReport {
+            cost: cost.polish_cost(&provider, &usage),
+            provider,
+            usage,
+        })
+        .collect();
+    let total_cost = asr_providers.iter().map(|p| p.cost).sum::<f64>()
+        + polish_providers.iter().map(|p| p.cost).sum::<f64>();
+
+    UsageReport {
+        days,
+        asr_providers,
+        polish_providers,
+        total_cost,
+        currency: cost.currency.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices() -> CostSettings {
+        CostSettings {
+            asr_prices: BTreeMap::from([("volcengine".to_string(), 4.5)]),
+            polish_prices: BTreeMap::from([(
+                "deepseek".to_string(),
+                TokenPrice {
+                    input: 2.0,
+                    output: 8.0,
+                },
+            )]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_accumulate_and_report_by_range() {
+        let mut daily = DailyUsage::new();
+        add_asr(&mut daily, "2026-09-30", "volcengine", 1800.0, 100);
+        add_asr(&mut daily, "2026-10-01", "volcengine", 600.0, 40);
+        add_asr(&mut daily, "2026-10-01", "volcengine", 1200.0, 60);
+        add_asr(&mut daily, "2026-10-02", "sapi", 60.0, 10);
+        add_polish(&mut daily, "2026-10-02", "deepseek", 500_000, 100_000);
+
+        let october = report(&daily, Some("2026-10-01"), None, &prices());
+        assert_eq!(october.days.len(), 2);
+        assert_eq!(october.days[0].usage.asr["volcengine"].count, 2);
+
+        let volcengine = &october.asr_providers[1];
+        assert_eq!(volcengine.provider, "volcengine");
+        assert_eq!(volcengine.usage.seconds, 1800.0);
+        assert!((volcengine.cost - 2.25).abs() < 1e-9);
+        // sapi 没有价格，费用为 0
+        assert_eq!(october.asr_providers[0].cost, 0.0);
+        assert!((october.polish_providers[0].cost - 1.8).abs() < 1e-9);
+        assert!((october.total_cost - 4.05).abs() < 1e-9);
+
+        let september = report(&daily, None, Some("2026-09-30"), &prices());
+        assert_eq!(september.days.len(), 1);
+        assert!((month_cost(&daily, "2026-09", &prices()) - 2.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_check_rejects_negative_prices() {
+        let mut cost = prices();
+        assert!(cost.check().is_empty());
+        cost.monthly_budget = -1.0;
+        cost.polish_prices.get_mut("deepseek").unwrap().output = f64::NAN;
+        let errors = cost.check();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[1].starts_with("cost.polishPrices.deepseek.output"));
+    }
+}
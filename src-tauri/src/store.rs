@@ -1,33 +1,67 @@
-use serde_json::Value;
+use crate::corrections::{self, Correction, CorrectionDb};
+use crate::history::{HistoryDb, HistoryQuery};
+use crate::polish::TokenUsage;
+use crate::secrets::SecretStore;
+use crate::settings::{self, AppSettings, AsrSettings, PolishSettings};
+use crate::stats::{self, DailyUsage, UsageReport};
+use serde::Serialize;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+/// 每个 JSON 文件保留的轮转备份数（file.json.bak.1 最新）
+const BACKUP_COUNT: usize = 3;
+
+/// 加载时发现文件损坏并进行了恢复
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreRecovery {
+    /// 损坏的文件名，如 "settings.json"
+    pub file: String,
+    /// 损坏文件被移到的位置
+    pub quarantined: Option<PathBuf>,
+    /// 恢复所用的备份，没有可用备份时为空（从空数据开始）
+    pub restored_from: Option<PathBuf>,
+}
+
 pub struct JsonStore {
-    path: PathBuf,
+    path: Mutex<PathBuf>,
     data: Mutex<HashMap<String, Value>>,
+    recovery: Option<StoreRecovery>,
 }
 
 impl JsonStore {
+    /// 读取 JSON 文件；文件损坏时隔离该文件并从最新的有效备份恢复
     pub fn new(path: PathBuf) -> Self {
-        let data = if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        let (data, recovery) = match fs::read(&path) {
+            Ok(content) => match serde_json::from_slice(&content) {
+                Ok(data) => (data, None),
                 Err(e) => {
-                    log::warn!("[store] failed to read {}: {}", path.display(), e);
-                    HashMap::new()
+                    log::error!("[store] {} is corrupt: {}", path.display(), e);
+                    let (data, recovery) = recover(&path);
+                    (data, Some(recovery))
                 }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (HashMap::new(), None),
+            Err(e) => {
+                log::warn!("[store] failed to read {}: {}", path.display(), e);
+                (HashMap::new(), None)
             }
-        } else {
-            HashMap::new()
         };
         Self {
-            path,
+            path: Mutex::new(path),
             data: Mutex::new(data),
+            recovery,
         }
     }
 
+    /// 加载时进行的损坏恢复
+    pub fn recovery(&self) -> Option<&StoreRecovery> {
+        self.recovery.as_ref()
+    }
+
     pub fn get(&self, key: &str) -> Option<Value> {
         let data = self.data.lock().unwrap_or_else(|e| e.into_inner());
         data.get(key).cloned()
@@ -38,55 +72,525 @@ impl JsonStore {
         data.insert(key.to_string(), value);
     }
 
+    pub fn remove(&self, key: &str) {
+        let mut data = self.data.lock().unwrap_or_else(|e| e.into_inner());
+        data.remove(key);
+    }
+
     pub fn entries(&self) -> Vec<(String, Value)> {
         let data = self.data.lock().unwrap_or_else(|e| e.into_inner());
         data.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
     }
 
+    /// 保存前把当前文件轮转为备份，再原子替换
     pub fn save(&self) -> Result<(), String> {
         let data = self.data.lock().unwrap_or_else(|e| e.into_inner());
         let json = serde_json::to_string_pretty(&*data)
             .map_err(|e| format!("Failed to serialize: {}", e))?;
 
-        let tmp_path = self.path.with_extension("json.tmp");
-        fs::write(&tmp_path, &json)
-            .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
-        fs::rename(&tmp_path, &self.path).map_err(|e| {
-            format!(
-                "Failed to rename {} -> {}: {}",
-                tmp_path.display(),
-                self.path.display(),
-                e
-            )
-        })?;
-        Ok(())
+        let path = self.path.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = rotate_backups(&path) {
+            log::warn!("[store] failed to back up {}: {}", path.display(), e);
+        }
+        write_file_atomic(&path, json.as_bytes())
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+    }
+
+    /// 改为保存到 path，并立即写入当前数据
+    pub fn relocate(&self, path: PathBuf) -> Result<(), String> {
+        *self.path.lock().unwrap_or_else(|e| e.into_inner()) = path;
+        self.save()
     }
 }
 
-/// 返回数据根目录 `~/.sayble/`，所有持久化文件统一存放于此
-pub fn base_dir() -> PathBuf {
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".bak.{}", index));
+    path.with_file_name(name)
+}
+
+/// file.json.bak.N-1 → .bak.N，当前文件复制为 .bak.1；当前文件无法解析时不覆盖已有备份
+fn rotate_backups(path: &Path) -> std::io::Result<()> {
+    let Ok(content) = fs::read(path) else {
+        return Ok(());
+    };
+    if serde_json::from_slice::<HashMap<String, Value>>(&content).is_err() {
+        return Ok(());
+    }
+    for index in (1..BACKUP_COUNT).rev() {
+        let from = backup_path(path, index);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, index + 1))?;
+        }
+    }
+    write_file_atomic(&backup_path(path, 1), &content)
+}
+
+/// 隔离损坏的文件，按从新到旧的顺序尝试备份，返回恢复后的数据
+fn recover(path: &Path) -> (HashMap<String, Value>, StoreRecovery) {
+    let file = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".corrupt-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let quarantine = path.with_file_name(name);
+    let quarantined = match fs::rename(path, &quarantine) {
+        Ok(()) => {
+            log::warn!("[store] moved corrupt file to {}", quarantine.display());
+            Some(quarantine)
+        }
+        Err(e) => {
+            log::error!("[store] failed to quarantine {}: {}", path.display(), e);
+            None
+        }
+    };
+
+    for index in 1..=BACKUP_COUNT {
+        let backup = backup_path(path, index);
+        let Ok(content) = fs::read(&backup) else {
+            continue;
+        };
+        let Ok(data) = serde_json::from_slice::<HashMap<String, Value>>(&content) else {
+            log::warn!("[store] backup {} is also corrupt", backup.display());
+            continue;
+        };
+        if let Err(e) = write_file_atomic(path, &content) {
+            log::error!("[store] failed to restore {}: {}", path.display(), e);
+        }
+        log::info!("[store] restored {} from {}", file, backup.display());
+        return (
+            data,
+            StoreRecovery {
+                file,
+                quarantined,
+                restored_from: Some(backup),
+            },
+        );
+    }
+    log::error!("[store] no valid backup for {}, starting empty", file);
+    (
+        HashMap::new(),
+        StoreRecovery {
+            file,
+            quarantined,
+            restored_from: None,
+        },
+    )
+}
+
+/// 写入临时文件并 fsync 后重命名为目标文件，再 fsync 所在目录，保证崩溃或断电后文件完整
+pub fn write_file_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    write_atomic(path, contents, false)
+}
+
+/// 同 write_file_atomic，但文件仅当前用户可读写（Unix 0600），用于密钥等敏感数据
+pub fn write_private_file_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    write_atomic(path, contents, true)
+}
+
+fn write_atomic(path: &Path, contents: &[u8], private: bool) -> std::io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let tmp_path = path.with_file_name(name);
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        if private {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                file.set_permissions(fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        std::io::Write::write_all(&mut file, contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)?;
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        fs::File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// 指定数据目录的环境变量
+pub const DATA_DIR_ENV: &str = "SAYBLE_DATA_DIR";
+
+/// 指定数据目录的命令行参数：--data-dir <目录> 或 --data-dir=<目录>
+pub const DATA_DIR_ARG: &str = "--data-dir";
+
+/// 启用便携模式的命令行参数
+pub const PORTABLE_ARG: &str = "--portable";
+
+/// 程序所在目录存在该文件时启用便携模式，数据保存在程序目录的 data 子目录
+pub const PORTABLE_MARKER: &str = "portable";
+
+/// 默认数据目录中记录迁移后位置的文件
+const LOCATION_FILE: &str = "location";
+
+/// 日志目录，迁移时复制但不删除（日志插件在重启前仍写入旧目录）
+const LOGS_DIR: &str = "logs";
+
+/// 应用在数据目录中创建的文件，移动数据目录时只复制和删除这些文件及其备份、临时文件
+const DATA_FILES: &[&str] = &[
+    "settings.json",
+    "stats.json",
+    "secrets.json",
+    "history.db",
+    "corrections.db",
+    "history.json",
+    "history.json.migrated",
+    "control_token",
+    "tunnels.json",
+    "tunnel_logs.json",
+    "share_files.json",
+    "share_settings.json",
+    "share_uploads.json",
+    "share_contents.json",
+];
+
+/// 数据目录的来源，按优先级从高到低：命令行参数、环境变量、便携模式、迁移记录、默认目录
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataDirSource {
+    Arg,
+    Env,
+    Portable,
+    Moved,
+    Default,
+}
+
+impl DataDirSource {
+    /// 由启动方式决定的数据目录不能在应用内迁移
+    pub fn is_fixed(self) -> bool {
+        matches!(self, Self::Arg | Self::Env | Self::Portable)
+    }
+}
+
+static DATA_DIR: Mutex<Option<(PathBuf, DataDirSource)>> = Mutex::new(None);
+
+/// 默认数据目录 `~/.sayble/`
+fn default_dir() -> PathBuf {
     dirs::home_dir()
         .expect("Failed to get home directory")
         .join(".sayble")
 }
 
+/// 返回数据根目录，所有持久化文件统一存放于此；首次调用时按启动参数和环境确定
+pub fn base_dir() -> PathBuf {
+    data_dir().0
+}
+
+/// 数据根目录及其来源
+pub fn data_dir() -> (PathBuf, DataDirSource) {
+    let mut dir = DATA_DIR.lock().unwrap_or_else(|e| e.into_inner());
+    dir.get_or_insert_with(|| {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let env = std::env::var(DATA_DIR_ENV).ok();
+        let exe_dir = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf));
+        resolve_data_dir(&args, env.as_deref(), exe_dir.as_deref(), default_dir())
+    })
+    .clone()
+}
+
+fn resolve_data_dir(
+    args: &[String],
+    env: Option<&str>,
+    exe_dir: Option<&Path>,
+    default: PathBuf,
+) -> (PathBuf, DataDirSource) {
+    let arg =
+        args.iter()
+            .enumerate()
+            .find_map(|(i, arg)| match arg.strip_prefix(DATA_DIR_ARG)? {
+                "" => args.get(i + 1).cloned(),
+                value => value.strip_prefix('=').map(str::to_string),
+            });
+    if let Some(dir) = arg.filter(|d| !d.trim().is_empty()) {
+        return (absolute(dir.trim()), DataDirSource::Arg);
+    }
+    if let Some(dir) = env.map(str::trim).filter(|d| !d.is_empty()) {
+        return (absolute(dir), DataDirSource::Env);
+    }
+    if let Some(exe_dir) = exe_dir {
+        if args.iter().any(|a| a == PORTABLE_ARG) || exe_dir.join(PORTABLE_MARKER).exists() {
+            return (exe_dir.join("data"), DataDirSource::Portable);
+        }
+    }
+    let moved = fs::read_to_string(default.join(LOCATION_FILE))
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|dir| !dir.is_empty());
+    match moved {
+        Some(dir) => (PathBuf::from(dir), DataDirSource::Moved),
+        None => (default, DataDirSource::Default),
+    }
+}
+
+fn absolute(dir: &str) -> PathBuf {
+    std::path::absolute(dir).unwrap_or_else(|_| PathBuf::from(dir))
+}
+
+/// 迁移后留在旧目录中的文件：位置记录和仍在写入的日志
+fn is_left_behind(name: &std::ffi::OsStr) -> bool {
+    name == LOCATION_FILE || name == LOGS_DIR
+}
+
+/// 是否为应用创建的数据文件，包括轮转备份、损坏隔离文件、临时文件和 SQLite 日志
+fn is_data_file(name: &str) -> bool {
+    DATA_FILES.iter().any(|file| {
+        name.strip_prefix(file).is_some_and(|suffix| {
+            suffix.is_empty()
+                || suffix.starts_with(".bak.")
+                || suffix.starts_with(".corrupt-")
+                || matches!(suffix, ".tmp" | "-wal" | "-shm" | "-journal")
+        })
+    })
+}
+
+/// 需要复制到新目录的数据文件：数据库由 AppStore::relocate 导出一致的副本，临时文件不复制
+fn is_copied_file(name: &str) -> bool {
+    is_data_file(name) && !name.contains(".db") && !name.ends_with(".tmp")
+}
+
+/// 检查迁移目标：必须是绝对路径，不在当前数据目录内，且不存在或为空目录
+///
+/// 只剩位置记录和日志的目录（如迁出后的默认目录）也视为空目录。
+pub fn check_move_target(current: &Path, target: &Path) -> Result<(), String> {
+    if !target.is_absolute() {
+        return Err("请选择完整的目录路径".to_string());
+    }
+    if target == current {
+        return Err("新位置与当前数据目录相同".to_string());
+    }
+    if target.starts_with(current) {
+        return Err("不能移动到当前数据目录内".to_string());
+    }
+    if let Ok(entries) = fs::read_dir(target) {
+        if entries
+            .flatten()
+            .any(|entry| !is_left_behind(&entry.file_name()))
+        {
+            return Err("目标目录不为空，请选择空目录".to_string());
+        }
+    } else if target.exists() {
+        return Err("目标位置不是目录".to_string());
+    }
+    Ok(())
+}
+
+/// 复制应用的数据文件和日志，数据目录中的其他文件不复制
+pub fn copy_data_files(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("创建目录 {} 失败: {}", to.display(), e))?;
+    copy_dir(&from.join(LOGS_DIR), &to.join(LOGS_DIR))?;
+    copy_files(from, to, |_, _| true)
+}
+
+/// 切换目录后再次复制比新目录中更新的数据文件，补上首次复制到切换之间写入旧目录的内容
+pub fn copy_changed_files(from: &Path, to: &Path) -> Result<(), String> {
+    copy_files(from, to, |source, dest| {
+        let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+        match (modified(source), modified(dest)) {
+            (Some(source), Some(dest)) => source > dest,
+            (_, None) => true,
+            (None, Some(_)) => false,
+        }
+    })
+}
+
+fn copy_files(
+    from: &Path,
+    to: &Path,
+    should_copy: impl Fn(&Path, &Path) -> bool,
+) -> Result<(), String> {
+    let entries = fs::read_dir(from).map_err(|e| format!("读取 {} 失败: {}", from.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || !is_copied_file(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let dest = to.join(entry.file_name());
+        if should_copy(&path, &dest) {
+            fs::copy(&path, &dest).map_err(|e| format!("复制 {} 失败: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(from) else {
+        return Ok(());
+    };
+    fs::create_dir_all(to).map_err(|e| format!("创建目录 {} 失败: {}", to.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let dest = to.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &dest)?;
+        } else {
+            fs::copy(&path, &dest).map_err(|e| format!("复制 {} 失败: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 切换到新的数据目录，并在默认目录中记录位置，下次启动时使用
+pub fn set_data_dir(dir: &Path) -> Result<(), String> {
+    let default = default_dir();
+    let location = default.join(LOCATION_FILE);
+    let source = if dir == default {
+        if location.exists() {
+            fs::remove_file(&location).map_err(|e| format!("更新数据目录记录失败: {}", e))?;
+        }
+        DataDirSource::Default
+    } else {
+        fs::create_dir_all(&default).map_err(|e| format!("更新数据目录记录失败: {}", e))?;
+        write_file_atomic(&location, dir.to_string_lossy().as_bytes())
+            .map_err(|e| format!("更新数据目录记录失败: {}", e))?;
+        DataDirSource::Moved
+    };
+    *DATA_DIR.lock().unwrap_or_else(|e| e.into_inner()) = Some((dir.to_path_buf(), source));
+    Ok(())
+}
+
+/// 迁移完成后删除旧目录中应用创建的数据文件；日志仍在写入，保留 logs 目录，
+/// 默认目录保留位置记录，用户放在目录中的其他文件不动
+pub fn remove_old_data(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || !is_data_file(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        if let Err(e) = fs::remove_file(&path) {
+            log::warn!("[store] failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
 pub struct AppStore {
     settings: JsonStore,
     stats: JsonStore,
-    history: JsonStore,
+    history: HistoryDb,
+    secrets: SecretStore,
+    corrections: CorrectionDb,
+}
+
+/// 将旧版本的设置逐级迁移到当前格式并保存
+fn migrate_settings(store: &JsonStore) {
+    let mut data: Map<String, Value> = store.entries().into_iter().collect();
+    match settings::migrate(&mut data) {
+        Ok(false) => {}
+        Ok(true) => {
+            for (key, value) in data {
+                store.set(&key, value);
+            }
+            match store.save() {
+                Ok(()) => log::info!(
+                    "[store] settings migrated to v{}",
+                    settings::SETTINGS_VERSION
+                ),
+                Err(e) => log::error!("[store] settings migration save failed: {}", e),
+            }
+        }
+        Err(e) => log::warn!("[store] {}, loading as current version", e),
+    }
+}
+
+/// 把 settings.json 中的明文密钥移入密钥存储，全部保存成功后才从设置中删除
+fn migrate_plaintext_secrets(store: &JsonStore, secrets: &SecretStore) {
+    let mut data: Map<String, Value> = store.entries().into_iter().collect();
+    let plaintext: Vec<_> = settings::take_secrets(&mut data)
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+    if plaintext.is_empty() {
+        return;
+    }
+    for (id, value) in &plaintext {
+        if let Err(e) = secrets.set(id, value) {
+            log::error!("[store] failed to move secret {}: {}", id, e);
+            return;
+        }
+    }
+    for (key, value) in data {
+        store.set(&key, value);
+    }
+    match store.save() {
+        Ok(()) => log::info!(
+            "[store] moved {} plaintext secrets out of settings",
+            plaintext.len()
+        ),
+        Err(e) => log::error!("[store] settings save failed: {}", e),
+    }
 }
 
-const MAX_HISTORY_RECORDS: usize = 200;
+/// 将旧版 history.json 导入 SQLite，成功后重命名为 history.json.migrated
+fn migrate_legacy_history(history: &HistoryDb, legacy_path: &Path) {
+    if !legacy_path.exists() {
+        return;
+    }
+    let records = JsonStore::new(legacy_path.to_path_buf())
+        .get("records")
+        .and_then(|v| v.as_array().cloned())
+        .unwrap_or_default();
+    match history.import_json(&records) {
+        Ok(count) => {
+            log::info!("[store] migrated {} history records to SQLite", count);
+            if let Err(e) = fs::rename(legacy_path, legacy_path.with_extension("json.migrated")) {
+                log::error!("[store] failed to rename legacy history: {}", e);
+            }
+        }
+        Err(e) => log::error!("[store] history migration failed: {}", e),
+    }
+}
+
+/// 打开 history.db 并导入旧版记录；打开失败时使用内存数据库，且不动 history.json，
+/// 以免旧记录导入内存后随重命名丢失
+fn open_history(base: &Path) -> HistoryDb {
+    match HistoryDb::open(&base.join("history.db")) {
+        Ok(history) => {
+            migrate_legacy_history(&history, &base.join("history.json"));
+            history
+        }
+        Err(e) => {
+            log::error!("[store] {}, history will not be persisted", e);
+            HistoryDb::open_in_memory().expect("Failed to create in-memory history")
+        }
+    }
+}
 
 impl AppStore {
     pub fn init() -> Self {
         let base = base_dir();
-        fs::create_dir_all(&base).expect("Failed to create ~/.sayble directory");
+        fs::create_dir_all(&base).expect("Failed to create data directory");
+
+        let history = open_history(&base);
+
+        let settings = JsonStore::new(base.join("settings.json"));
+        migrate_settings(&settings);
+        let secrets = SecretStore::open(&base);
+        migrate_plaintext_secrets(&settings, &secrets);
+
+        let corrections = CorrectionDb::open(&base.join("corrections.db")).unwrap_or_else(|e| {
+            log::error!("[store] {}, corrections will not be persisted", e);
+            CorrectionDb::open_in_memory().expect("Failed to create in-memory corrections")
+        });
 
         Self {
-            settings: JsonStore::new(base.join("settings.json")),
+            settings,
             stats: JsonStore::new(base.join("stats.json")),
-            history: JsonStore::new(base.join("history.json")),
+            history,
+            secrets,
+            corrections,
         }
     }
 
@@ -94,12 +598,54 @@ impl AppStore {
         &self.settings
     }
 
+    /// 把数据切换到新目录：JSON 文件按内存中的数据重新写入，数据库导出一致的副本后重新打开
+    pub fn relocate(&self, base: &Path) -> Result<(), String> {
+        self.history.relocate(&base.join("history.db"))?;
+        self.corrections.relocate(&base.join("corrections.db"))?;
+        self.settings.relocate(base.join("settings.json"))?;
+        self.stats.relocate(base.join("stats.json"))?;
+        self.secrets.relocate(base);
+        Ok(())
+    }
+
+    /// 启动时从损坏中恢复的文件
+    pub fn recoveries(&self) -> Vec<StoreRecovery> {
+        [&self.settings, &self.stats]
+            .into_iter()
+            .filter_map(|store| store.recovery().cloned())
+            .collect()
+    }
+
+    /// 读取通用设置，缺失或无效的字段使用默认值
+    pub fn app_settings(&self) -> AppSettings {
+        self.load_section(settings::APP_SETTINGS_KEY)
+    }
+
+    /// 读取语音识别设置
+    pub fn asr_settings(&self) -> AsrSettings {
+        self.load_section(settings::ASR_SETTINGS_KEY)
+    }
+
+    /// 读取润色设置
+    pub fn polish_settings(&self) -> PolishSettings {
+        self.load_section(settings::POLISH_SETTINGS_KEY)
+    }
+
+    /// 密钥存储，保存 ASR 和润色服务商的密钥
+    pub fn secrets(&self) -> &SecretStore {
+        &self.secrets
+    }
+
+    fn load_section<T: serde::de::DeserializeOwned + Default>(&self, key: &str) -> T {
+        settings::load_lenient(key, self.settings.get(key).unwrap_or(Value::Null))
+    }
+
     pub fn stats(&self) -> &JsonStore {
         &self.stats
     }
 
-    /// 累加一次识别的统计数据并持久化
-    pub fn accumulate_stats(&self, chars: usize, duration_ms: Option<i64>) {
+    /// 累加一次识别的统计数据并持久化，同时记入当天该服务商的明细
+    pub fn accumulate_stats(&self, provider: &str, chars: usize, duration_ms: Option<i64>) {
         let s = self.stats();
         let prev_dur: i64 = s
             .get("total_duration_ms")
@@ -113,58 +659,365 @@ impl AppStore {
         );
         s.set("total_chars", serde_json::json!(prev_chars + chars as i64));
         s.set("total_count", serde_json::json!(count + 1));
+
+        let seconds = duration_ms.unwrap_or(0) as f64 / 1000.0;
+        self.update_daily(|daily, today| {
+            stats::add_asr(daily, today, provider, seconds, chars as u64)
+        });
         if let Err(e) = s.save() {
             log::error!("[store] stats save failed: {}", e);
         }
     }
 
-    /// 追加一条历史记录，超过 MAX_HISTORY_RECORDS 时删除最旧的
-    pub fn append_history(&self, record: Value) {
-        let h = &self.history;
-        let mut records = h
-            .get("records")
-            .and_then(|v| v.as_array().cloned())
-            .unwrap_or_default();
-        records.push(record);
-        while records.len() > MAX_HISTORY_RECORDS {
-            records.remove(0);
+    /// 记录一次润色请求的 token 用量，服务商未返回 usage 时只计请求数
+    pub fn record_polish_usage(&self, provider: &str, usage: Option<TokenUsage>) {
+        let usage = usage.unwrap_or_default();
+        self.update_daily(|daily, today| {
+            stats::add_polish(
+                daily,
+                today,
+                provider,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+            )
+        });
+        if let Err(e) = self.stats.save() {
+            log::error!("[store] stats save failed: {}", e);
+        }
+    }
+
+    fn update_daily(&self, update: impl FnOnce(&mut DailyUsage, &str)) {
+        let mut daily = self.daily_usage();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        update(&mut daily, &today);
+        self.stats.set(
+            stats::DAILY_KEY,
+            serde_json::to_value(&daily).unwrap_or_default(),
+        );
+    }
+
+    fn daily_usage(&self) -> DailyUsage {
+        settings::load_lenient(
+            stats::DAILY_KEY,
+            self.stats.get(stats::DAILY_KEY).unwrap_or(Value::Null),
+        )
+    }
+
+    /// 按日期范围汇总用量与估算费用
+    pub fn usage_report(&self, from: Option<&str>, to: Option<&str>) -> UsageReport {
+        stats::report(&self.daily_usage(), from, to, &self.app_settings().cost)
+    }
+
+    /// 本月费用首次超出预算时返回 (本月费用, 预算)，每月只提醒一次
+    pub fn check_budget(&self) -> Option<(f64, f64)> {
+        let cost = self.app_settings().cost;
+        if cost.monthly_budget <= 0.0 {
+            return None;
+        }
+        let month = chrono::Local::now().format("%Y-%m").to_string();
+        let warned = self.stats.get(stats::BUDGET_WARNED_KEY);
+        if warned.as_ref().and_then(|v| v.as_str()) == Some(month.as_str()) {
+            return None;
+        }
+        let spent = stats::month_cost(&self.daily_usage(), &month, &cost);
+        if spent < cost.monthly_budget {
+            return None;
         }
-        h.set("records", Value::Array(records));
-        if let Err(e) = h.save() {
-            log::error!("[store] history save failed: {}", e);
+        self.stats
+            .set(stats::BUDGET_WARNED_KEY, Value::String(month));
+        if let Err(e) = self.stats.save() {
+            log::error!("[store] stats save failed: {}", e);
+        }
+        Some((spent, cost.monthly_budget))
+    }
+
+    /// 追加一条历史记录，返回记录 id，并按保留策略清理旧记录
+    pub fn append_history(&self, record: Value) -> String {
+        let id = match self.history.insert(&record) {
+            Ok(id) => id,
+            Err(e) => {
+                log::error!("[store] history save failed: {}", e);
+                return String::new();
+            }
+        };
+        let retention = self.app_settings().history_retention;
+        let now = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+        match self.history.prune(&retention, &now) {
+            Ok(0) => {}
+            Ok(removed) => log::info!("[store] pruned {} history records", removed),
+            Err(e) => log::error!("[store] history prune failed: {}", e),
         }
+        id
     }
 
     /// 读取历史记录，倒序返回（最新在前）
     pub fn load_history(&self) -> Vec<Value> {
-        let mut records = self
-            .history
-            .get("records")
-            .and_then(|v| v.as_array().cloned())
-            .unwrap_or_default();
-        records.reverse();
-        records
-    }
-
-    /// 删除一条历史记录（按 timestamp 匹配）
-    pub fn remove_history(&self, timestamp: &str) {
-        let h = &self.history;
-        let mut records = h
-            .get("records")
-            .and_then(|v| v.as_array().cloned())
-            .unwrap_or_default();
-        records.retain(|r| r.get("timestamp").and_then(|t| t.as_str()) != Some(timestamp));
-        h.set("records", Value::Array(records));
-        if let Err(e) = h.save() {
+        self.history.list()
+    }
+
+    /// 按 id 或 timestamp 查找历史记录，timestamp 重复时返回最近的一条
+    pub fn find_history(&self, key: &str) -> Option<Value> {
+        self.history.find(key)
+    }
+
+    /// 按条件搜索历史记录
+    pub fn search_history(&self, query: &HistoryQuery) -> Result<Vec<Value>, String> {
+        self.history.search(query)
+    }
+
+    /// 修改一条历史记录（按 id 或 timestamp 匹配最近的一条），返回是否找到
+    pub fn update_history(&self, key: &str, update: impl FnOnce(&mut Value)) -> bool {
+        self.history.update(key, update).unwrap_or_else(|e| {
+            log::error!("[store] history update save failed: {}", e);
+            false
+        })
+    }
+
+    /// 删除一条历史记录（按 id 匹配，兼容按 timestamp 匹配）
+    pub fn remove_history(&self, key: &str) {
+        if let Err(e) = self.history.remove(key) {
             log::error!("[store] history remove save failed: {}", e);
         }
     }
 
+    /// 从备份导入历史记录，替换时先清空；id 已存在的记录保持不变
+    pub fn import_history(&self, records: &[Value], replace: bool) -> Result<usize, String> {
+        if replace {
+            self.history.clear()?;
+        }
+        self.history.import_json(records)
+    }
+
     /// 清空历史记录
     pub fn clear_history(&self) {
-        self.history.set("records", Value::Array(vec![]));
-        if let Err(e) = self.history.save() {
+        if let Err(e) = self.history.clear() {
             log::error!("[store] history clear save failed: {}", e);
         }
     }
+
+    /// 从一次修改中学习纠正，返回本次新加入热词和词典的纠正
+    pub fn learn_corrections(&self, before: &str, after: &str) -> Vec<Correction> {
+        let config = self.app_settings().corrections;
+        if !config.enabled {
+            return Vec::new();
+        }
+        let items = corrections::substitutions(before, after);
+        if items.is_empty() {
+            return Vec::new();
+        }
+        let now = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+        let recorded = match self.corrections.record(&items, &now) {
+            Ok(recorded) => recorded,
+            Err(e) => {
+                log::error!("[store] {}", e);
+                return Vec::new();
+            }
+        };
+        if !config.auto_promote {
+            return Vec::new();
+        }
+
+        let mut data: Map<String, Value> = self.settings.entries().into_iter().collect();
+        let mut promoted = Vec::new();
+        for mut c in recorded {
+            if c.promoted
+                || c.count < config.promote_threshold.max(1)
+                || !corrections::is_proper_noun(&c.corrected)
+            {
+                continue;
+            }
+            corrections::promote(&mut data, &c);
+            c.promoted = true;
+            promoted.push(c);
+        }
+        if promoted.is_empty() {
+            return Vec::new();
+        }
+        for key in [settings::APP_SETTINGS_KEY, settings::ASR_SETTINGS_KEY] {
+            if let Some(value) = data.remove(key) {
+                self.settings.set(key, value);
+            }
+        }
+        if let Err(e) = self.settings.save() {
+            log::error!("[store] settings save failed: {}", e);
+            return Vec::new();
+        }
+        for c in &promoted {
+            if let Err(e) = self.corrections.mark_promoted(&c.original, &c.corrected) {
+                log::error!("[store] {}", e);
+            }
+        }
+        promoted
+    }
+
+    /// 按出现次数列出纠正记录
+    pub fn list_corrections(&self) -> Vec<Correction> {
+        self.corrections.list()
+    }
+
+    /// 删除一条纠正记录，已加入的热词和词典条目不受影响
+    pub fn remove_correction(&self, original: &str, corrected: &str) -> Result<(), String> {
+        self.corrections.remove(original, corrected).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resolve_data_dir_priority() {
+        let root = tempfile::tempdir().unwrap();
+        let exe_dir = root.path().join("app");
+        let default = root.path().join("home");
+        fs::create_dir_all(&exe_dir).unwrap();
+        fs::create_dir_all(&default).unwrap();
+        let resolve = |a: &[&str], env: Option<&str>| {
+            resolve_data_dir(&args(a), env, Some(&exe_dir), default.clone())
+        };
+
+        assert_eq!(
+            resolve(&[], None),
+            (default.clone(), DataDirSource::Default)
+        );
+        let moved = root.path().join("moved");
+        fs::write(
+            default.join(LOCATION_FILE),
+            moved.to_string_lossy().as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(resolve(&[], None), (moved, DataDirSource::Moved));
+
+        fs::write(exe_dir.join(PORTABLE_MARKER), "").unwrap();
+        assert_eq!(
+            resolve(&[], None),
+            (exe_dir.join("data"), DataDirSource::Portable)
+        );
+
+        let env_dir = root.path().join("env");
+        let env = env_dir.to_string_lossy().into_owned();
+        assert_eq!(resolve(&[], Some(&env)), (env_dir, DataDirSource::Env));
+
+        let arg_dir = root.path().join("arg");
+        let arg = arg_dir.to_string_lossy().into_owned();
+        let expected = (arg_dir, DataDirSource::Arg);
+        assert_eq!(resolve(&["--data-dir", &arg], Some(&env)), expected);
+        assert_eq!(resolve(&[&format!("--data-dir={}", arg)], None), expected);
+    }
+
+    #[test]
+    fn test_open_history_keeps_legacy_file_when_db_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = dir.path().join("history.json");
+        fs::write(
+            &legacy,
+            r#"{"records": [{"id": "1", "text": "旧记录", "timestamp": 1}]}"#,
+        )
+        .unwrap();
+
+        // history.db 是目录时无法打开
+        fs::create_dir(dir.path().join("history.db")).unwrap();
+        open_history(dir.path());
+        assert!(legacy.exists());
+        assert!(!dir.path().join("history.json.migrated").exists());
+
+        fs::remove_dir(dir.path().join("history.db")).unwrap();
+        open_history(dir.path());
+        assert!(!legacy.exists());
+        assert!(dir.path().join("history.json.migrated").exists());
+    }
+
+    #[test]
+    fn test_move_only_touches_data_files() {
+        let root = tempfile::tempdir().unwrap();
+        let old = root.path().join("old");
+        let new = root.path().join("new");
+        fs::create_dir_all(old.join(LOGS_DIR)).unwrap();
+        fs::create_dir_all(old.join("photos")).unwrap();
+        for name in [
+            "settings.json",
+            "settings.json.bak.1",
+            "settings.json.tmp",
+            "history.db",
+            "history.db-wal",
+            "notes.txt",
+            "settings.json.old",
+            LOCATION_FILE,
+        ] {
+            fs::write(old.join(name), name).unwrap();
+        }
+        fs::write(old.join(LOGS_DIR).join("sayble.log"), "log").unwrap();
+
+        copy_data_files(&old, &new).unwrap();
+        let mut copied: Vec<String> = fs::read_dir(&new)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        copied.sort();
+        assert_eq!(copied, [LOGS_DIR, "settings.json", "settings.json.bak.1"]);
+        assert!(new.join(LOGS_DIR).join("sayble.log").exists());
+
+        // 复制之后、切换之前写入旧目录的内容再次复制；新目录中更新的文件不被覆盖
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        fs::write(old.join("settings.json"), "changed").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(old.join("settings.json"))
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        fs::write(old.join("stats.json"), "stats").unwrap();
+        fs::write(new.join("settings.json.bak.1"), "newer").unwrap();
+        copy_changed_files(&old, &new).unwrap();
+        assert_eq!(
+            fs::read_to_string(new.join("settings.json")).unwrap(),
+            "changed"
+        );
+        assert_eq!(fs::read_to_string(new.join("stats.json")).unwrap(), "stats");
+        assert_eq!(
+            fs::read_to_string(new.join("settings.json.bak.1")).unwrap(),
+            "newer"
+        );
+
+        remove_old_data(&old);
+        let mut remaining: Vec<String> = fs::read_dir(&old)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [
+                LOCATION_FILE,
+                LOGS_DIR,
+                "notes.txt",
+                "photos",
+                "settings.json.old"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_move_target() {
+        let root = tempfile::tempdir().unwrap();
+        let current = root.path().join("current");
+        fs::create_dir_all(current.join("logs")).unwrap();
+
+        assert!(check_move_target(&current, Path::new("relative")).is_err());
+        assert!(check_move_target(&current, &current).is_err());
+        assert!(check_move_target(&current, &current.join("sub")).is_err());
+        assert!(check_move_target(&current, &root.path().join("new")).is_ok());
+
+        // 迁出后只剩位置记录和日志的目录可以迁回
+        let old = root.path().join("old");
+        fs::create_dir_all(old.join("logs")).unwrap();
+        fs::write(old.join(LOCATION_FILE), "").unwrap();
+        assert!(check_move_target(&current, &old).is_ok());
+        fs::write(old.join("settings.json"), "{}").unwrap();
+        assert!(check_move_target(&current, &old).is_err());
+    }
 }
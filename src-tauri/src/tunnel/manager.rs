@@ -2,7 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     fs,
     io::{BufRead, BufReader},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::Child,
     sync::{Arc, Mutex},
     time::Duration,
@@ -27,8 +27,6 @@ type ChildHandle = Arc<Mutex<Child>>;
 
 pub struct TunnelManager {
     app: AppHandle,
-    config_path: PathBuf,
-    log_path: PathBuf,
     configs: Mutex<Vec<TunnelConfig>>,
     statuses: Mutex<HashMap<String, TunnelStatus>>,
     children: Mutex<HashMap<String, ChildHandle>>,
@@ -40,14 +38,10 @@ impl TunnelManager {
     pub fn init(app: AppHandle) -> Result<Arc<Self>, String> {
         let base = store::base_dir();
         fs::create_dir_all(&base).map_err(|e| format!("创建隧道数据目录失败: {}", e))?;
-        let config_path = base.join(CONFIG_FILE);
-        let log_path = base.join(LOG_FILE);
-        let configs = read_configs(&config_path)?;
+        let configs = read_configs(&base.join(CONFIG_FILE))?;
 
         Ok(Arc::new(Self {
             app,
-            config_path,
-            log_path,
             configs: Mutex::new(configs),
             statuses: Mutex::new(HashMap::new()),
             children: Mutex::new(HashMap::new()),
@@ -56,6 +50,21 @@ impl TunnelManager {
         }))
     }
 
+    /// 数据目录迁移后从新位置重新读取配置，运行中的隧道不受影响
+    pub fn reload(&self) -> Result<(), String> {
+        let configs = read_configs(&self.config_path())?;
+        *self.configs.lock().unwrap_or_else(|e| e.into_inner()) = configs;
+        Ok(())
+    }
+
+    fn config_path(&self) -> PathBuf {
+        store::base_dir().join(CONFIG_FILE)
+    }
+
+    fn log_path(&self) -> PathBuf {
+        store::base_dir().join(LOG_FILE)
+    }
+
     pub fn start_autostart_tunnels(self: &Arc<Self>) {
         for config in self.list_tunnels().into_iter().filter(|c| c.auto_start) {
             if let Err(e) = self.start_tunnel(&config.id) {
@@ -86,7 +95,7 @@ impl TunnelManager {
         } else {
             configs.insert(0, config.clone());
         }
-        write_json(&self.config_path, &*configs)?;
+        write_json(&self.config_path(), &*configs)?;
         self.append_log(&config.id, &config.name, TunnelLogLevel::Info, "配置已保存");
         Ok(config)
     }
@@ -97,7 +106,7 @@ impl TunnelManager {
 
         let mut configs = self.configs.lock().unwrap_or_else(|e| e.into_inner());
         configs.retain(|item| item.id != id);
-        write_json(&self.config_path, &*configs)?;
+        write_json(&self.config_path(), &*configs)?;
 
         self.statuses
             .lock()
@@ -117,6 +126,54 @@ impl TunnelManager {
         Ok(())
     }
 
+    /// 规范化并校验待导入的隧道配置，不修改现有配置
+    pub fn check_import(imported: &mut [TunnelConfig]) -> Result<(), String> {
+        for config in imported {
+            normalize_config(config);
+            validate_config(config).map_err(|e| format!("隧道「{}」: {}", config.name, e))?;
+        }
+        Ok(())
+    }
+
+    /// 从备份导入隧道配置：合并时按 id 覆盖或追加，替换时先停止并移除现有隧道
+    pub fn import_tunnels(
+        &self,
+        mut imported: Vec<TunnelConfig>,
+        replace: bool,
+    ) -> Result<usize, String> {
+        Self::check_import(&mut imported)?;
+        if replace {
+            self.stop_all();
+            self.statuses
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clear();
+        }
+
+        let mut configs = self.configs.lock().unwrap_or_else(|e| e.into_inner());
+        if replace {
+            configs.clear();
+        }
+        for config in &imported {
+            if let Some(existing) = configs.iter_mut().find(|item| item.id == config.id) {
+                *existing = config.clone();
+            } else {
+                configs.push(config.clone());
+            }
+        }
+        write_json(&self.config_path(), &*configs)?;
+        drop(configs);
+
+        let _ = self.app.emit(
+            "tunnel-event",
+            serde_json::json!({
+                "kind": "imported",
+                "count": imported.len(),
+            }),
+        );
+        Ok(imported.len())
+    }
+
     pub fn start_tunnel(self: &Arc<Self>, id: &str) -> Result<(), String> {
         self.start_tunnel_inner(id, false)
     }
@@ -197,7 +254,7 @@ impl TunnelManager {
         limit: usize,
     ) -> Result<Vec<TunnelLogEntry>, String> {
         let _guard = self.log_lock.lock().unwrap_or_else(|e| e.into_inner());
-        let mut logs = read_logs(&self.log_path)?;
+        let mut logs = read_logs(&self.log_path())?;
         if let Some(tunnel_id) = tunnel_id {
             logs.retain(|entry| entry.tunnel_id == tunnel_id);
         }
@@ -208,13 +265,13 @@ impl TunnelManager {
 
     pub fn clear_logs(&self, tunnel_id: Option<&str>) -> Result<(), String> {
         let _guard = self.log_lock.lock().unwrap_or_else(|e| e.into_inner());
-        let mut logs = read_logs(&self.log_path)?;
+        let mut logs = read_logs(&self.log_path())?;
         if let Some(tunnel_id) = tunnel_id {
             logs.retain(|entry| entry.tunnel_id != tunnel_id);
         } else {
             logs.clear();
         }
-        write_json(&self.log_path, &logs)?;
+        write_json(&self.log_path(), &logs)?;
         let _ = self.app.emit(
             "tunnel-event",
             serde_json::json!({
@@ -570,13 +627,13 @@ impl TunnelManager {
 
         let save_result = (|| -> Result<(), String> {
             let _guard = self.log_lock.lock().unwrap_or_else(|e| e.into_inner());
-            let mut logs = read_logs(&self.log_path)?;
+            let mut logs = read_logs(&self.log_path())?;
             logs.push(entry.clone());
             if logs.len() > MAX_LOGS {
                 let drop_count = logs.len() - MAX_LOGS;
                 logs.drain(0..drop_count);
             }
-            write_json(&self.log_path, &logs)
+            write_json(&self.log_path(), &logs)
         })();
 
         if let Err(e) = save_result {
@@ -673,11 +730,9 @@ fn read_logs(path: &PathBuf) -> Result<Vec<TunnelLogEntry>, String> {
     serde_json::from_str(&content).map_err(|e| format!("解析隧道日志失败: {}", e))
 }
 
-fn write_json<T: serde::Serialize>(path: &PathBuf, value: &T) -> Result<(), String> {
+fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> Result<(), String> {
     let json =
         serde_json::to_string_pretty(value).map_err(|e| format!("序列化隧道数据失败: {}", e))?;
-    let tmp_path = path.with_extension("json.tmp");
-    fs::write(&tmp_path, json).map_err(|e| format!("写入 {} 失败: {}", tmp_path.display(), e))?;
-    fs::rename(&tmp_path, path).map_err(|e| format!("保存 {} 失败: {}", path.display(), e))?;
-    Ok(())
+    store::write_file_atomic(path, json.as_bytes())
+        .map_err(|e| format!("保存 {} 失败: {}", path.display(), e))
 }
@@ -0,0 +1,519 @@
+use serde::{Deserialize, Serialize};
+
+use crate::input::KeyAction;
+
+/// ASR 可能在命令词两侧自动添加的标点（中英文）
+const ASR_PUNCTUATION: &[char] = &[
+    ',', '.', '?', '!', ';', ':', '，', '。', '？', '！', '；', '：', '、',
+];
+/// 停顿类标点：命令词前的这类标点通常是 ASR 断句产生的，应丢弃
+const PAUSE_PUNCTUATION: &[char] = &[',', ';', ':', '，', '；', '：', '、'];
+/// 句末标点：用于"删除上一句"时划分句子
+const SENTENCE_TERMINATORS: &[char] = &['.', '?', '!', '。', '？', '！', '\n'];
+
+/// 语音命令动作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum CommandAction {
+    /// 换行（Shift+Enter，聊天软件中不会触发发送）
+    NewLine,
+    /// 新段落（两次换行）
+    NewParagraph,
+    Tab,
+    /// 按下 Enter 发送
+    Send,
+    /// Ctrl+Z
+    Undo,
+    /// 删除上一次输出的最后一句
+    DeleteLastSentence,
+    /// 插入标点，会替换 ASR 在命令词两侧自动添加的标点
+    Punctuation(String),
+    /// 原样插入文本
+    Insert(String),
+}
+
+impl CommandAction {
+    /// 转换为输出层的按键序列，`previous_output` 为上一次输出到目标应用的文本
+    pub fn key_actions(&self, previous_output: &str) -> Vec<KeyAction> {
+        match self {
+            CommandAction::NewLine => vec![KeyAction::ShiftEnter],
+            CommandAction::NewParagraph => vec![KeyAction::ShiftEnter, KeyAction::ShiftEnter],
+            CommandAction::Tab => vec![KeyAction::Tab],
+            CommandAction::Send => vec![KeyAction::Enter],
+            CommandAction::Undo => vec![KeyAction::Undo],
+            CommandAction::DeleteLastSentence => {
+                vec![KeyAction::Backspace; last_sentence_len(previous_output)]
+            }
+            CommandAction::Punctuation(_) | CommandAction::Insert(_) => vec![],
+        }
+    }
+}
+
+/// 用户自定义命令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCommand {
+    pub phrase: String,
+    pub action: CommandAction,
+    /// 仅当整句话就是该命令时才生效
+    #[serde(default)]
+    pub standalone: bool,
+}
+
+/// 语音命令设置（app_settings.voiceCommands）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VoiceCommandSettings {
+    pub enabled: bool,
+    /// 是否把"逗号"、"comma"等标点词转换为符号
+    pub punctuation_words: bool,
+    pub custom_commands: Vec<CustomCommand>,
+}
+
+impl Default for VoiceCommandSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            punctuation_words: true,
+            custom_commands: vec![],
+        }
+    }
+}
+
+/// 命令解析后的输出片段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Text(String),
+    Action(CommandAction),
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    phrase: Vec<char>,
+    action: CommandAction,
+    standalone: bool,
+}
+
+/// 内建命令表：(短语, 动作, 是否仅整句生效)
+fn builtin_commands() -> Vec<(&'static str, CommandAction, bool)> {
+    use CommandAction::*;
+    vec![
+        ("new line", NewLine, false),
+        ("newline", NewLine, false),
+        ("new paragraph", NewParagraph, false),
+        ("press tab", Tab, false),
+        ("press enter", Send, false),
+        ("press send", Send, false),
+        ("send", Send, true),
+        ("send it", Send, true),
+        ("undo", Undo, true),
+        ("undo that", Undo, true),
+        ("delete last sentence", DeleteLastSentence, true),
+        ("delete that", DeleteLastSentence, true),
+        ("scratch that", DeleteLastSentence, true),
+        ("换行", NewLine, false),
+        ("新段落", NewParagraph, false),
+        ("另起一段", NewParagraph, false),
+        ("按回车", Send, false),
+        ("按发送", Send, false),
+        ("发送", Send, true),
+        ("撤销", Undo, true),
+        ("删除上一句", DeleteLastSentence, true),
+        ("删掉上一句", DeleteLastSentence, true),
+    ]
+}
+
+/// 内建标点词表：(短语, 符号)
+fn builtin_punctuation() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("comma", ","),
+        ("period", "."),
+        ("full stop", "."),
+        ("question mark", "?"),
+        ("exclamation mark", "!"),
+        ("exclamation point", "!"),
+        ("colon", ":"),
+        ("semicolon", ";"),
+        ("逗号", "，"),
+        ("句号", "。"),
+        ("问号", "？"),
+        ("感叹号", "！"),
+        ("冒号", "："),
+        ("分号", "；"),
+        ("顿号", "、"),
+    ]
+}
+
+fn normalize_phrase(phrase: &str) -> Vec<char> {
+    phrase
+        .trim()
+        .chars()
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// 语音命令语法：将最终文本拆分为文本片段和按键动作
+pub struct CommandGrammar {
+    rules: Vec<Rule>,
+}
+
+impl CommandGrammar {
+    pub fn new(settings: &VoiceCommandSettings) -> Self {
+        let mut rules: Vec<Rule> = settings
+            .custom_commands
+            .iter()
+            .filter(|c| !c.phrase.trim().is_empty())
+            .map(|c| Rule {
+                phrase: normalize_phrase(&c.phrase),
+                action: c.action.clone(),
+                standalone: c.standalone,
+            })
+            .collect();
+
+        rules.extend(
+            builtin_commands()
+                .into_iter()
+                .map(|(phrase, action, standalone)| Rule {
+                    phrase: normalize_phrase(phrase),
+                    action,
+                    standalone,
+                }),
+        );
+
+        if settings.punctuation_words {
+            rules.extend(
+                builtin_punctuation()
+                    .into_iter()
+                    .map(|(phrase, symbol)| Rule {
+                        phrase: normalize_phrase(phrase),
+                        action: CommandAction::Punctuation(symbol.to_string()),
+                        standalone: false,
+                    }),
+            );
+        }
+
+        // 长短语优先匹配（"new paragraph" 先于 "new line"，"question mark" 先于其他）；
+        // 排序稳定，同长度时自定义命令优先于内建命令
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.phrase.len()));
+        Self { rules }
+    }
+
+    pub fn parse(&self, text: &str) -> Vec<Segment> {
+        if let Some(segment) = self.match_standalone(text) {
+            return vec![segment];
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        let lower: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+        let mut segments = Vec::new();
+        let mut pending = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let Some(rule) = self.match_inline(&lower, i) else {
+                pending.push(chars[i]);
+                i += 1;
+                continue;
+            };
+            i += rule.phrase.len();
+
+            match &rule.action {
+                CommandAction::Insert(value) => pending.push_str(value),
+                CommandAction::Punctuation(symbol) => {
+                    trim_end_in_place(&mut pending);
+                    while pending.ends_with(ASR_PUNCTUATION) {
+                        pending.pop();
+                    }
+                    pending.push_str(symbol);
+                    while i < chars.len() && ASR_PUNCTUATION.contains(&chars[i]) {
+                        i += 1;
+                    }
+                }
+                action => {
+                    trim_end_in_place(&mut pending);
+                    while pending.ends_with(PAUSE_PUNCTUATION) {
+                        pending.pop();
+                    }
+                    if !pending.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut pending)));
+                    }
+                    segments.push(Segment::Action(action.clone()));
+                    while i < chars.len()
+                        && (chars[i].is_whitespace() || ASR_PUNCTUATION.contains(&chars[i]))
+                    {
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            segments.push(Segment::Text(pending));
+        }
+        segments
+    }
+
+    /// 整句命令：去掉首尾空白和 ASR 标点后与短语完全一致；
+    /// 仅整句生效的插入/标点命令在此输出对应文本
+    fn match_standalone(&self, text: &str) -> Option<Segment> {
+        let normalized = normalize_phrase(
+            text.trim_matches(|c: char| c.is_whitespace() || ASR_PUNCTUATION.contains(&c)),
+        );
+        if normalized.is_empty() {
+            return None;
+        }
+        self.rules
+            .iter()
+            .filter(|rule| rule.phrase == normalized)
+            .find_map(|rule| match &rule.action {
+                CommandAction::Punctuation(value) | CommandAction::Insert(value) => {
+                    (rule.standalone && !value.is_empty()).then(|| Segment::Text(value.clone()))
+                }
+                action => Some(Segment::Action(action.clone())),
+            })
+    }
+
+    fn match_inline(&self, lower: &[char], start: usize) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .filter(|rule| !rule.standalone)
+            .find(|rule| {
+                let end = start + rule.phrase.len();
+                if end > lower.len() || lower[start..end] != rule.phrase[..] {
+                    return false;
+                }
+                // 英文短语要求完整单词边界，避免 "commands" 中的 "comma" 被误识别
+                let first = rule.phrase[0];
+                let last = rule.phrase[rule.phrase.len() - 1];
+                let starts_ok = !first.is_ascii_alphanumeric()
+                    || start == 0
+                    || !lower[start - 1].is_ascii_alphanumeric();
+                let ends_ok = !last.is_ascii_alphanumeric()
+                    || end == lower.len()
+                    || !lower[end].is_ascii_alphanumeric();
+                starts_ok && ends_ok
+            })
+    }
+}
+
+fn trim_end_in_place(value: &mut String) {
+    let trimmed_len = value.trim_end().len();
+    value.truncate(trimmed_len);
+}
+
+/// 计算文本最后一句的字符数（含句末标点及其前导空白）
+pub fn last_sentence_len(text: &str) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut end = chars.len();
+    while end > 0 && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+    while end > 0 && SENTENCE_TERMINATORS.contains(&chars[end - 1]) {
+        end -= 1;
+    }
+    let start = chars[..end]
+        .iter()
+        .rposition(|c| SENTENCE_TERMINATORS.contains(c))
+        .map(|pos| pos + 1)
+        .unwrap_or(0);
+    chars.len() - start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Segment {
+        Segment::Text(value.to_string())
+    }
+
+    fn action(value: CommandAction) -> Segment {
+        Segment::Action(value)
+    }
+
+    fn enabled() -> VoiceCommandSettings {
+        VoiceCommandSettings {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_builtin_commands() {
+        use CommandAction::*;
+        let cases: Vec<(&str, Vec<Segment>)> = vec![
+            ("hello world", vec![text("hello world")]),
+            ("你好世界", vec![text("你好世界")]),
+            (
+                "Hello world. New line. How are you?",
+                vec![text("Hello world."), action(NewLine), text("How are you?")],
+            ),
+            (
+                "first line new line second line",
+                vec![text("first line"), action(NewLine), text("second line")],
+            ),
+            (
+                "你好，换行，今天天气不错",
+                vec![text("你好"), action(NewLine), text("今天天气不错")],
+            ),
+            (
+                "第一段新段落第二段",
+                vec![text("第一段"), action(NewParagraph), text("第二段")],
+            ),
+            (
+                "see you tomorrow press send",
+                vec![text("see you tomorrow"), action(Send)],
+            ),
+            ("Send.", vec![action(Send)]),
+            ("发送。", vec![action(Send)]),
+            ("undo", vec![action(Undo)]),
+            ("撤销", vec![action(Undo)]),
+            ("Delete last sentence.", vec![action(DeleteLastSentence)]),
+            ("删除上一句", vec![action(DeleteLastSentence)]),
+            ("I will send it later", vec![text("I will send it later")]),
+            ("请撤销这次修改", vec![text("请撤销这次修改")]),
+        ];
+
+        let grammar = CommandGrammar::new(&enabled());
+        for (input, expected) in cases {
+            assert_eq!(grammar.parse(input), expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_punctuation_words() {
+        let cases = vec![
+            ("hello comma world", "hello, world"),
+            ("Hello, comma, world", "Hello, world"),
+            ("are you there question mark", "are you there?"),
+            ("wait full stop", "wait."),
+            ("Note colon buy milk", "Note: buy milk"),
+            ("你好逗号今天", "你好，今天"),
+            ("你好，逗号，今天怎么样问号", "你好，今天怎么样？"),
+            ("太好了感叹号", "太好了！"),
+            ("苹果顿号香蕉", "苹果、香蕉"),
+            ("voice commands work", "voice commands work"),
+        ];
+
+        let grammar = CommandGrammar::new(&enabled());
+        for (input, expected) in cases {
+            assert_eq!(
+                grammar.parse(input),
+                vec![text(expected)],
+                "input: {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_punctuation_words_can_be_disabled() {
+        let settings = VoiceCommandSettings {
+            enabled: true,
+            punctuation_words: false,
+            custom_commands: vec![],
+        };
+        let grammar = CommandGrammar::new(&settings);
+        assert_eq!(
+            grammar.parse("hello comma world"),
+            vec![text("hello comma world")]
+        );
+    }
+
+    #[test]
+    fn test_custom_commands() {
+        let settings = VoiceCommandSettings {
+            enabled: true,
+            punctuation_words: true,
+            custom_commands: vec![
+                CustomCommand {
+                    phrase: "我的邮箱".to_string(),
+                    action: CommandAction::Insert("me@example.com".to_string()),
+                    standalone: false,
+                },
+                CustomCommand {
+                    phrase: "Ship it".to_string(),
+                    action: CommandAction::Send,
+                    standalone: true,
+                },
+                CustomCommand {
+                    phrase: "签名".to_string(),
+                    action: CommandAction::Insert("此致敬礼".to_string()),
+                    standalone: true,
+                },
+            ],
+        };
+        let grammar = CommandGrammar::new(&settings);
+        assert_eq!(
+            grammar.parse("请发到我的邮箱"),
+            vec![text("请发到me@example.com")]
+        );
+        assert_eq!(grammar.parse("ship it!"), vec![action(CommandAction::Send)]);
+        assert_eq!(
+            grammar.parse("we ship it today"),
+            vec![text("we ship it today")]
+        );
+        assert_eq!(grammar.parse("签名。"), vec![text("此致敬礼")]);
+        assert_eq!(grammar.parse("我的签名"), vec![text("我的签名")]);
+    }
+
+    #[test]
+    fn test_key_actions() {
+        use CommandAction::*;
+        let cases: Vec<(CommandAction, &str, Vec<KeyAction>)> = vec![
+            (NewLine, "", vec![KeyAction::ShiftEnter]),
+            (
+                NewParagraph,
+                "",
+                vec![KeyAction::ShiftEnter, KeyAction::ShiftEnter],
+            ),
+            (Tab, "", vec![KeyAction::Tab]),
+            (Send, "", vec![KeyAction::Enter]),
+            (Undo, "", vec![KeyAction::Undo]),
+            (DeleteLastSentence, "", vec![]),
+            (DeleteLastSentence, "Hi.", vec![KeyAction::Backspace; 3]),
+            (
+                DeleteLastSentence,
+                "Hello. World.",
+                vec![KeyAction::Backspace; 7],
+            ),
+            (Punctuation("，".to_string()), "", vec![]),
+        ];
+        for (command, previous, expected) in cases {
+            assert_eq!(
+                command.key_actions(previous),
+                expected,
+                "command: {:?}",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn test_last_sentence_len() {
+        let cases = vec![
+            ("", 0),
+            ("hello", 5),
+            ("Hello. World.", 7),
+            ("Hello. World", 6),
+            ("Hello. World.  ", 9),
+            ("今天天气不错。我们去公园吧！", 7),
+            ("第一行\n第二行", 3),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(last_sentence_len(input), expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_settings_deserialize_with_defaults() {
+        let settings: VoiceCommandSettings = serde_json::from_str(
+            r#"{"enabled": true, "customCommands": [{"phrase": "sign off", "action": {"type": "insert", "value": "Best regards"}}]}"#,
+        )
+        .unwrap();
+        assert!(settings.enabled);
+        assert!(settings.punctuation_words);
+        assert_eq!(settings.custom_commands.len(), 1);
+        assert!(!settings.custom_commands[0].standalone);
+    }
+}
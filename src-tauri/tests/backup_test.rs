@@ -0,0 +1,58 @@
+use sayble_lib::backup::*;
+use sayble_lib::history::HistoryDb;
+use sayble_lib::settings::{self, Settings};
+use serde_json::{json, Map, Value};
+
+fn load_fixture(name: &str) -> String {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("backup")
+        .join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {:?}: {}", path, e))
+}
+
+/// 旧版设置的备份：解析时迁移并通过校验，合并后保留本机设置
+#[test]
+fn imports_v1_backup() {
+    let backup = Backup::parse(&load_fixture("v1_backup.json")).unwrap();
+    assert_eq!(
+        settings::version_of(&backup.settings),
+        settings::SETTINGS_VERSION
+    );
+    assert_eq!(backup.open_secrets(None), Ok(Some(Vec::new())));
+    assert_eq!(backup.tunnels.len(), 1);
+    assert_eq!(backup.tunnels[0].local_port, 15432);
+
+    let mut current: Map<String, Value> = json!({
+        "settings_version": 2,
+        "app_settings": { "toggleHotkey": "右Ctrl", "deviceId": "local-device" },
+        "polish_settings": {
+            "prompts": [{ "id": "default", "name": "默认", "content": "润色" }]
+        }
+    })
+    .as_object()
+    .cloned()
+    .unwrap();
+    settings::migrate(&mut current).unwrap();
+    merge_settings(&mut current, backup.settings.clone(), ImportMode::Merge);
+    assert_eq!(settings::validate(&current), Ok(()));
+
+    let merged = Settings::from_map(&current);
+    assert_eq!(merged.app.toggle_hotkey, "右Alt");
+    assert_eq!(merged.app.device_id, "local-device");
+    assert_eq!(merged.app.hotkey_profiles[0].id, "meeting");
+    assert!(!merged.asr.providers["volcengine"].auto_punctuation);
+    assert_eq!(merged.polish.prompts.len(), 2);
+    assert_eq!(
+        merged.polish.prompt_content("meeting-notes"),
+        Some("整理成会议纪要，保留发言要点。")
+    );
+
+    let history = HistoryDb::open_in_memory().unwrap();
+    let records = backup.history.unwrap();
+    assert_eq!(history.import_json(&records).unwrap(), 1);
+    // 重复导入不产生重复记录
+    history.import_json(&records).unwrap();
+    assert_eq!(history.list().len(), 1);
+}
@@ -0,0 +1,122 @@
+use sayble_lib::control::{ControlApi, ControlRequest, ControlSettings, TOKEN_FILE};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+
+fn request(port: u16, token: Option<&str>, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let auth = token
+        .map(|t| format!("Authorization: Bearer {}\r\n", t))
+        .unwrap_or_default();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        auth,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+/// 订阅 /events，读到 connected 注释行后返回
+fn open_events(port: u16, token: &str) -> BufReader<TcpStream> {
+    let mut events = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    write!(
+        events,
+        "GET /events HTTP/1.1\r\nAuthorization: Bearer {}\r\n\r\n",
+        token
+    )
+    .unwrap();
+    let mut reader = BufReader::new(events);
+    let mut line = String::new();
+    while line != ": connected\n" {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+    }
+    reader
+}
+
+/// 控制接口只接受带 token 的请求，转发给处理函数并通过 SSE 推送事件
+#[test]
+fn serves_requests_and_events() {
+    let dir = tempfile::tempdir().unwrap();
+    std::env::set_var("SAYBLE_DATA_DIR", dir.path());
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let handler_received = Arc::clone(&received);
+    let api = ControlApi::new(Arc::new(move |request: ControlRequest| {
+        handler_received.lock().unwrap().push(request.clone());
+        match request {
+            ControlRequest::Stop => Err("当前没有在录音".to_string()),
+            _ => Ok(json!({ "sessionId": 1 })),
+        }
+    }));
+    let settings = ControlSettings {
+        enabled: true,
+        port: 47699,
+    };
+    api.apply(&settings).unwrap();
+    assert!(api.status().running);
+    let token = std::fs::read_to_string(dir.path().join(TOKEN_FILE)).unwrap();
+
+    assert_eq!(request(47699, None, "GET", "/state", "").0, 401);
+    assert_eq!(request(47699, Some("wrong"), "GET", "/state", "").0, 401);
+    let (status, body) = request(
+        47699,
+        Some(&token),
+        "POST",
+        "/recording/start",
+        r#"{"profile":"meeting"}"#,
+    );
+    assert_eq!((status, body), (200, json!({ "sessionId": 1 })));
+    let (status, body) = request(47699, Some(&token), "POST", "/recording/stop", "");
+    assert_eq!((status, body), (400, json!({ "error": "当前没有在录音" })));
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![
+            ControlRequest::Start {
+                profile: Some("meeting".to_string())
+            },
+            ControlRequest::Stop
+        ]
+    );
+
+    let mut reader = open_events(47699, &token);
+    let mut line = String::new();
+    api.broadcast("asr-event", r#"{"sessionId":1,"event":"Finished"}"#);
+    let mut message = Vec::new();
+    for _ in 0..3 {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        message.push(line.clone());
+    }
+    assert_eq!(
+        message,
+        [
+            "\n",
+            "event: asr-event\n",
+            "data: {\"sessionId\":1,\"event\":\"Finished\"}\n"
+        ]
+    );
+
+    // SSE 连接单独计数：订阅数达到上限后拒绝新的订阅，但普通请求不受影响
+    let mut streams = vec![reader];
+    while streams.len() < 8 {
+        streams.push(open_events(47699, &token));
+    }
+    assert_eq!(request(47699, Some(&token), "GET", "/events", "").0, 503);
+    assert_eq!(request(47699, Some(&token), "GET", "/state", "").0, 200);
+    drop(streams);
+
+    api.apply(&ControlSettings::default()).unwrap();
+    assert!(!api.status().running);
+    assert!(TcpStream::connect(("127.0.0.1", 47699)).is_err());
+}
@@ -20,6 +20,7 @@ fn test_config_to_asr_validation_flow() {
         access_key: "test_key".to_string(),
         language: "zh".to_string(),
         auto_punctuation: true,
+        hotwords: Vec::new(),
     };
     assert!(VolcEngineAsr::validate_config(&valid_config).is_ok());
 }
@@ -80,12 +81,18 @@ fn test_full_config_persistence_flow() {
                 modifiers: vec![Modifier::RightCtrl],
                 key: 0,
             },
+            action: HotkeyAction::ToggleRecording,
+            translation: None,
+            profile_id: None,
+            trigger: TriggerMode::Toggle,
         },
+        hotkey_profiles: Vec::new(),
         asr: AsrConfig {
             app_id: "my_app".to_string(),
             access_key: "my_key".to_string(),
             language: "zh".to_string(),
             auto_punctuation: true,
+            hotwords: Vec::new(),
         },
         output_mode: OutputMode::Clipboard,
         microphone_device: "Default Mic".to_string(),
@@ -319,6 +326,9 @@ async fn test_asr_with_mp3_file() {
                         final_text = text;
                         break;
                     }
+                    AsrEvent::Utterances(items) => {
+                        println!("  Utterances: {}", items.len());
+                    }
                     AsrEvent::Error(e) => {
                         panic!("ASR error: {}", e);
                     }
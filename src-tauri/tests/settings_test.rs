@@ -0,0 +1,81 @@
+use sayble_lib::config::{OutputMode, ProfilePolish};
+use sayble_lib::settings::*;
+use serde_json::{Map, Value};
+
+fn load_fixture(name: &str) -> Map<String, Value> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("settings")
+        .join(name);
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {:?}: {}", path, e));
+    serde_json::from_str(&content).unwrap()
+}
+
+/// 旧版完整设置文件：迁移后通过严格校验，各字段按类型读取
+#[test]
+fn migrates_full_v1_settings() {
+    let mut data = load_fixture("v1_full.json");
+    assert_eq!(version_of(&data), 1);
+    // 迁移前的字符串开关无法通过校验
+    assert!(validate(&data).is_err());
+
+    assert_eq!(migrate(&mut data), Ok(true));
+    assert_eq!(version_of(&data), SETTINGS_VERSION);
+    assert_eq!(validate(&data), Ok(()));
+
+    let settings = Settings::from_map(&data);
+    assert_eq!(settings.app.output_mode, OutputMode::SimulateKeyboard);
+    assert_eq!(settings.app.microphone_device, "USB Microphone");
+    assert!(settings.app.auto_start);
+    assert!(!settings.app.auto_output);
+    assert_eq!(settings.app.hotkey_profiles[0].polish, ProfilePolish::Off);
+    assert!(settings.app.extra.contains_key("liveWindows"));
+
+    let volcengine = &settings.asr.providers["volcengine"];
+    assert_eq!(volcengine.language, "auto");
+    assert!(!volcengine.auto_punctuation);
+    assert_eq!(settings.asr.provider_id(None), "volcengine");
+    assert!(settings.asr.providers.contains_key("sapi"));
+
+    assert!(settings.polish.enabled);
+    assert_eq!(
+        settings.polish.provider("deepseek").unwrap().temperature,
+        0.3
+    );
+    assert!(settings
+        .polish
+        .prompt_content("fix-only")
+        .unwrap()
+        .starts_with("请只修正"));
+
+    // 再次迁移不做修改，保存后重新读取结果一致
+    assert_eq!(migrate(&mut data), Ok(false));
+    let saved = serde_json::to_value(&settings.app).unwrap();
+    assert_eq!(saved["liveWindows"], data["app_settings"]["liveWindows"]);
+}
+
+/// 含无效条目的旧版文件：宽松读取丢弃无效条目，保存时报告字段路径
+#[test]
+fn migrates_partial_v1_settings() {
+    let mut data = load_fixture("v1_partial.json");
+    assert_eq!(migrate(&mut data), Ok(true));
+
+    let settings = Settings::from_map(&data);
+    assert_eq!(settings.app.toggle_hotkey, "右Ctrl");
+    assert!(settings.app.hotkey_profiles.is_empty());
+    assert!(settings.app.auto_output);
+    assert!(settings.asr.providers["volcengine"].auto_punctuation);
+    assert!(!settings.polish.enabled);
+    assert!(settings.polish.providers.is_empty());
+
+    let errors = validate(&data).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("app_settings.hotkeyProfiles[0].hotkey:"));
+
+    // 加载时修正后，前端原样保存能通过校验
+    repair(&mut data);
+    assert_eq!(validate(&data), Ok(()));
+    assert_eq!(Settings::from_map(&data).app.toggle_hotkey, "右Ctrl");
+}
@@ -0,0 +1,99 @@
+use sayble_lib::history::HistoryDb;
+use sayble_lib::store::JsonStore;
+use serde_json::json;
+use std::fs;
+
+/// 每次保存都轮转备份，文件损坏时隔离并从最新的有效备份恢复
+#[test]
+fn recovers_corrupt_file_from_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("settings.json");
+
+    let store = JsonStore::new(path.clone());
+    assert!(store.recovery().is_none());
+    for version in 1..=5 {
+        store.set("version", json!(version));
+        store.save().unwrap();
+    }
+    assert!(dir.path().join("settings.json.bak.1").exists());
+    assert!(dir.path().join("settings.json.bak.3").exists());
+    assert!(!dir.path().join("settings.json.bak.4").exists());
+    assert!(!dir.path().join("settings.json.tmp").exists());
+
+    // 最新备份也损坏时使用更早的备份
+    fs::write(&path, "{\"version\": 6,").unwrap();
+    fs::write(dir.path().join("settings.json.bak.1"), "").unwrap();
+    let store = JsonStore::new(path.clone());
+    let recovery = store.recovery().unwrap();
+    assert_eq!(recovery.file, "settings.json");
+    assert_eq!(
+        recovery.restored_from.as_deref(),
+        Some(dir.path().join("settings.json.bak.2").as_path())
+    );
+    let quarantined = recovery.quarantined.as_ref().unwrap();
+    assert_eq!(fs::read_to_string(quarantined).unwrap(), "{\"version\": 6,");
+    assert_eq!(store.get("version"), Some(json!(3)));
+
+    // 恢复后的文件可正常读取
+    let reopened = JsonStore::new(path);
+    assert!(reopened.recovery().is_none());
+    assert_eq!(reopened.get("version"), Some(json!(3)));
+}
+
+/// 没有可用备份时从空数据开始，损坏的文件保留供排查
+#[test]
+fn starts_empty_without_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+    fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+
+    let store = JsonStore::new(path.clone());
+    let recovery = store.recovery().unwrap();
+    assert!(recovery.restored_from.is_none());
+    assert!(recovery.quarantined.as_ref().unwrap().exists());
+    assert!(store.entries().is_empty());
+    assert!(!path.exists());
+}
+
+/// 移动数据目录：JSON 文件写到新位置，数据库导出副本后继续使用新文件
+#[test]
+fn relocates_json_store_and_history() {
+    let old = tempfile::tempdir().unwrap();
+    let new = tempfile::tempdir().unwrap();
+    let target = new.path().join("data");
+
+    let store = JsonStore::new(old.path().join("settings.json"));
+    store.set("theme", json!("dark"));
+    store.save().unwrap();
+    let history = HistoryDb::open(&old.path().join("history.db")).unwrap();
+    history
+        .insert(&json!({"id": "a", "timestamp": "2026-10-01T09:00:00", "asrText": "你好"}))
+        .unwrap();
+
+    sayble_lib::store::copy_data_files(old.path(), &target).unwrap();
+    assert!(!target.join("history.db").exists());
+    store.relocate(target.join("settings.json")).unwrap();
+    history.relocate(&target.join("history.db")).unwrap();
+
+    history
+        .insert(&json!({"id": "b", "timestamp": "2026-10-01T10:00:00", "asrText": "再见"}))
+        .unwrap();
+    assert_eq!(
+        HistoryDb::open(&target.join("history.db"))
+            .unwrap()
+            .list()
+            .len(),
+        2
+    );
+    assert_eq!(
+        HistoryDb::open(&old.path().join("history.db"))
+            .unwrap()
+            .list()
+            .len(),
+        1
+    );
+    assert_eq!(
+        JsonStore::new(target.join("settings.json")).get("theme"),
+        Some(json!("dark"))
+    );
+}
@@ -6,6 +6,7 @@ import { Dashboard } from "@/components/Dashboard";
 import { Settings } from "@/components/Settings";
 import { TitleBar } from "@/components/TitleBar";
 import { Toaster } from "@/components/ui/sonner";
+import { toast } from "sonner";
 import { useSettingsStore } from "@/stores/useSettingsStore";
 import { setupKeyEventBridge } from "@/lib/keyEventBridge";
 
@@ -24,6 +25,20 @@ function App() {
     useSettingsStore.getState().checkUpdate();
   }, []);
 
+  // 启动时有数据文件损坏并已恢复，提示用户
+  useEffect(() => {
+    invoke<{ file: string; restoredFrom: string | null }[]>("cmd_get_store_recoveries")
+      .then((recoveries) => {
+        for (const r of recoveries) {
+          toast.warning(
+            r.restoredFrom ? `${r.file} 已损坏，已从备份恢复` : `${r.file} 已损坏且没有可用备份，已重置`,
+            { description: r.restoredFrom ?? undefined, duration: 8000 },
+          );
+        }
+      })
+      .catch(() => {});
+  }, []);
+
   // WebView2 焦点时键盘钩子失效补偿：通过 JS 层监听按键并注入后端
   useEffect(() => {
     return setupKeyEventBridge();
@@ -52,6 +67,31 @@ function App() {
     };
   }, []);
 
+  // 从用户修改中学到新的热词和词典条目时，重新加载设置并提示
+  useEffect(() => {
+    let cancelled = false;
+    let unlisten: UnlistenFn | null = null;
+
+    listen<{ original: string; corrected: string }[]>("corrections-learned", (event) => {
+      if (cancelled) return;
+      useSettingsStore.setState({ loaded: false });
+      useSettingsStore.getState().loadSettings();
+      const words = event.payload.map((c) => c.corrected).join("、");
+      toast.success("已加入热词和词典", { description: words });
+    }).then((fn) => {
+      if (cancelled) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    });
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
   // 检测自启动状态
   const checkAutostart = useCallback(() => {
     invoke<string | null>("cmd_check_autostart").then((result) => {
@@ -7,6 +7,11 @@ import { FloatingWindow, type FloatingStatus } from "@/components/FloatingWindow
 
 const appWindow = getCurrentWindow();
 
+interface PendingOutputInfo {
+  sessionId: number;
+  text: string;
+}
+
 export function FloatingApp() {
   const [floatingStatus, setFloatingStatus] = useState<FloatingStatus>("idle");
   const [partialText, setPartialText] = useState("");
@@ -16,6 +21,13 @@ export function FloatingApp() {
   const timerRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const maxSessionRef = useRef(0);
   const cancelledSessionRef = useRef(0);
+  // 预览中的待确认输出所属的 session
+  const pendingSessionRef = useRef<number | null>(null);
+  const statusRef = useRef<FloatingStatus>("idle");
+
+  useEffect(() => {
+    statusRef.current = floatingStatus;
+  }, [floatingStatus]);
 
   const clearTimer = useCallback(() => {
     if (timerRef.current) {
@@ -32,9 +44,9 @@ export function FloatingApp() {
     }, 1000);
   }, [clearTimer]);
 
-  const showWindow = useCallback(async () => {
+  const showWindow = useCallback(async (preview = false) => {
     try {
-      await invoke("cmd_show_floating_window");
+      await invoke("cmd_show_floating_window", { preview });
     } catch (e) {
       logError("[floating] backend show failed: " + String(e));
       try {
@@ -57,6 +69,31 @@ export function FloatingApp() {
     setDuration(0);
   }, []);
 
+  const showPending = useCallback((output: PendingOutputInfo) => {
+    pendingSessionRef.current = output.sessionId;
+    setFinalText(output.text);
+    setFloatingStatus("pending");
+    showWindow(true);
+  }, [showWindow]);
+
+  // 还有待确认的输出时预览最近一条，否则隐藏悬浮窗
+  const showNextPending = useCallback(async () => {
+    let outputs: PendingOutputInfo[] = [];
+    try {
+      outputs = await invoke<PendingOutputInfo[]>("cmd_get_pending_outputs");
+    } catch (e) {
+      logError("[floating] get pending outputs failed: " + String(e));
+    }
+    const latest = outputs[outputs.length - 1];
+    if (latest) {
+      showPending(latest);
+    } else {
+      pendingSessionRef.current = null;
+      setFloatingStatus("idle");
+      hideWindow();
+    }
+  }, [showPending, hideWindow]);
+
   // 监听 ASR 事件（后端驱动，每个事件携带 sessionId）
   useEffect(() => {
     let cancelled = false;
@@ -64,11 +101,39 @@ export function FloatingApp() {
 
     listen<{
       sessionId: number;
-      event: string | { PartialResult?: string; FinalResult?: string; PolishResult?: string; Polishing?: string; Error?: string; Connected?: null };
+      event: string | {
+        PartialResult?: string;
+        FinalResult?: string;
+        PolishResult?: string;
+        Polishing?: string;
+        Error?: string;
+        Connected?: null;
+        PendingOutput?: { text: string; timeoutSecs: number };
+        PendingOutputResolved?: string;
+      };
     }>("asr-event", (ev) => {
       if (cancelled) return;
       const { sessionId, event } = ev.payload;
 
+      // 待确认输出可能来自较早的 session（撤销后放回预览），不参与 session 过滤
+      if (event && typeof event === "object" && "PendingOutput" in event && event.PendingOutput) {
+        info("[asr-event] session " + sessionId + " PendingOutput");
+        const busy = ["recording", "recognizing", "polishing"].includes(statusRef.current);
+        if (busy && sessionId !== maxSessionRef.current) {
+          // 正在进行新的录音，结束后再预览
+          return;
+        }
+        showPending({ sessionId, text: event.PendingOutput.text });
+        return;
+      }
+      if (event && typeof event === "object" && "PendingOutputResolved" in event) {
+        info("[asr-event] session " + sessionId + " PendingOutputResolved: " + event.PendingOutputResolved);
+        if (pendingSessionRef.current === sessionId && statusRef.current === "pending") {
+          showNextPending();
+        }
+        return;
+      }
+
       // 旧 session 或已取消 session 的事件直接丢弃
       if (sessionId < maxSessionRef.current) {
         debug("[asr-event] discarded old session: " + sessionId + " current: " + maxSessionRef.current);
@@ -135,8 +200,7 @@ export function FloatingApp() {
         setFloatingStatus("polish_error");
       } else if (type === "Finished") {
         info("[asr-event] session " + sessionId + " Finished");
-        setFloatingStatus("idle");
-        hideWindow();
+        showNextPending();
       } else if (type === "Error") {
         logError("[asr-event] session " + sessionId + " Error: " + JSON.stringify(event));
         const errStr = typeof event === "object" && "Error" in event ? String(event.Error) : "未知错误";
@@ -163,7 +227,7 @@ export function FloatingApp() {
       unlisten?.();
       clearTimer();
     };
-  }, [clearTimer, showWindow, hideWindow, resetState]);
+  }, [clearTimer, showWindow, resetState, showPending, showNextPending]);
 
   // 监听主窗口发来的控制事件
   useEffect(() => {
@@ -192,6 +256,11 @@ export function FloatingApp() {
         setFloatingStatus("recognizing");
         clearTimer();
       } else if (action === "cancel") {
+        // 预览待确认输出时 Esc 由预览窗口自己处理，其他应用中的 Esc 不关闭预览
+        if (statusRef.current === "pending") {
+          debug("[floating-control] cancel ignored while previewing pending output");
+          return;
+        }
         // 标记当前 session 为已取消，其后续事件会被丢弃
         cancelledSessionRef.current = typeof sessionId === "number" ? sessionId : maxSessionRef.current;
         setFloatingStatus("idle");
@@ -219,6 +288,30 @@ export function FloatingApp() {
       finalText={finalText}
       duration={duration}
       errorMessage={errorMessage}
+      onPendingChange={(text) => {
+        const sessionId = pendingSessionRef.current;
+        setFinalText(text);
+        if (sessionId === null) return;
+        invoke("cmd_update_pending_output", { sessionId, text }).catch((e) => {
+          logError("[floating] update pending output failed: " + String(e));
+        });
+      }}
+      onAccept={() => {
+        const sessionId = pendingSessionRef.current;
+        if (sessionId === null) return;
+        invoke("cmd_accept_pending_output", { sessionId, text: finalText }).catch((e) => {
+          logError("[floating] accept pending output failed: " + String(e));
+          showNextPending();
+        });
+      }}
+      onDiscard={() => {
+        const sessionId = pendingSessionRef.current;
+        if (sessionId === null) return;
+        invoke("cmd_discard_pending_output", { sessionId }).catch((e) => {
+          logError("[floating] discard pending output failed: " + String(e));
+          showNextPending();
+        });
+      }}
       onCancel={async () => {
         try {
           const sessionId = await invoke<number | null>("cmd_cancel_recording");
@@ -1,7 +1,7 @@
 import { useEffect, useState } from "react";
 import { cn } from "@/lib/utils";
 
-export type FloatingStatus = "idle" | "recording" | "recognizing" | "done" | "error" | "polishing" | "polish_error";
+export type FloatingStatus = "idle" | "recording" | "recognizing" | "done" | "error" | "polishing" | "polish_error" | "pending";
 
 interface FloatingWindowProps {
   status: FloatingStatus;
@@ -10,6 +10,10 @@ interface FloatingWindowProps {
   duration: number;
   errorMessage?: string;
   onCancel?: () => void;
+  /** 待确认输出的文本编辑 */
+  onPendingChange?: (text: string) => void;
+  onAccept?: () => void;
+  onDiscard?: () => void;
 }
 
 export function FloatingWindow({
@@ -19,6 +23,9 @@ export function FloatingWindow({
   duration,
   errorMessage,
   onCancel,
+  onPendingChange,
+  onAccept,
+  onDiscard,
 }: FloatingWindowProps) {
   const [visible, setVisible] = useState(false);
 
@@ -32,6 +39,17 @@ export function FloatingWindow({
 
   if (!visible) return null;
 
+  if (status === "pending") {
+    return (
+      <PendingPreview
+        text={finalText}
+        onChange={onPendingChange}
+        onAccept={onAccept}
+        onDiscard={onDiscard}
+      />
+    );
+  }
+
   const displayText =
     status === "recording"
       ? partialText || "请开始说话..."
@@ -100,6 +118,56 @@ export function FloatingWindow({
   );
 }
 
+/** 关闭自动输出时的预览：可编辑文本，确认后输出到目标应用 */
+function PendingPreview({
+  text,
+  onChange,
+  onAccept,
+  onDiscard,
+}: {
+  text: string;
+  onChange?: (text: string) => void;
+  onAccept?: () => void;
+  onDiscard?: () => void;
+}) {
+  return (
+    <div className="flex h-screen w-screen flex-col gap-1.5 rounded-lg border border-green-500/40 bg-neutral-900 px-3 py-2">
+      <div className="flex items-center justify-between">
+        <span className="text-xs font-medium text-neutral-300">待确认</span>
+        <span className="text-[10px] text-neutral-500">Ctrl+Enter 输出 · Esc 丢弃</span>
+      </div>
+      <textarea
+        value={text}
+        onChange={(e) => onChange?.(e.target.value)}
+        onKeyDown={(e) => {
+          if (e.key === "Escape") {
+            e.preventDefault();
+            onDiscard?.();
+          } else if (e.key === "Enter" && (e.ctrlKey || e.metaKey)) {
+            e.preventDefault();
+            onAccept?.();
+          }
+        }}
+        className="flex-1 resize-none rounded bg-neutral-800 px-2 py-1 text-xs text-neutral-200 outline-none focus:ring-1 focus:ring-neutral-600"
+      />
+      <div className="flex justify-end gap-1.5">
+        <button
+          onClick={onDiscard}
+          className="rounded px-2 py-0.5 text-[11px] text-neutral-400 hover:bg-neutral-800 hover:text-neutral-200 transition-colors"
+        >
+          丢弃
+        </button>
+        <button
+          onClick={onAccept}
+          className="rounded bg-green-600/80 px-2 py-0.5 text-[11px] text-white hover:bg-green-600 transition-colors"
+        >
+          输出
+        </button>
+      </div>
+    </div>
+  );
+}
+
 function StatusIndicator({ status }: { status: FloatingStatus }) {
   return (
     <div className="relative flex h-5 w-5 shrink-0 items-center justify-center">
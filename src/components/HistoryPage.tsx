@@ -1,11 +1,30 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
+import { save } from "@tauri-apps/plugin-dialog";
+import { toast } from "sonner";
 import { Button } from "@/components/ui/button";
 import { Dialog, DialogContent, DialogHeader, DialogTitle, DialogDescription } from "@/components/ui/dialog";
 import { Badge } from "@/components/ui/badge";
-import { Trash2, X } from "lucide-react";
-import type { HistoryRecord } from "@/types/history";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { Download, Trash2, X } from "lucide-react";
+import type { ExportFormat, HistoryRecord } from "@/types/history";
+
+const EXPORT_FORMATS: { value: ExportFormat; label: string; extension: string }[] = [
+  { value: "markdown", label: "Markdown 日志", extension: "md" },
+  { value: "csv", label: "CSV 表格", extension: "csv" },
+  { value: "jsonLines", label: "JSON Lines", extension: "jsonl" },
+  { value: "srt", label: "SRT 字幕", extension: "srt" },
+  { value: "vtt", label: "WebVTT 字幕", extension: "vtt" },
+];
 
 function formatTime(ts: string): string {
   const date = new Date(ts);
@@ -70,6 +89,11 @@ export function HistoryPage() {
   const [selectedRecord, setSelectedRecord] = useState<HistoryRecord | null>(null);
   const [showClearConfirm, setShowClearConfirm] = useState(false);
   const [pendingRemove, setPendingRemove] = useState<string | null>(null);
+  const [showExport, setShowExport] = useState(false);
+  const [exportFormat, setExportFormat] = useState<ExportFormat>("markdown");
+  const [exportFrom, setExportFrom] = useState("");
+  const [exportTo, setExportTo] = useState("");
+  const [exporting, setExporting] = useState(false);
 
   const loadHistory = async () => {
     try {
@@ -90,22 +114,47 @@ export function HistoryPage() {
     }
   };
 
-  const removeHistory = async (timestamp: string, e: React.MouseEvent) => {
+  const removeHistory = async (id: string, e: React.MouseEvent) => {
     e.stopPropagation();
-    setPendingRemove(timestamp);
+    setPendingRemove(id);
   };
 
   const confirmRemove = async () => {
     if (!pendingRemove) return;
     try {
-      await invoke("cmd_remove_history", { timestamp: pendingRemove });
-      setHistory((prev) => prev.filter((r) => r.timestamp !== pendingRemove));
+      await invoke("cmd_remove_history", { id: pendingRemove });
+      setHistory((prev) => prev.filter((r) => r.id !== pendingRemove));
     } catch (err) {
       console.error("Failed to remove history:", err);
     }
     setPendingRemove(null);
   };
 
+  const exportHistory = async () => {
+    const format = EXPORT_FORMATS.find((f) => f.value === exportFormat)!;
+    const date = new Date().toISOString().slice(0, 10);
+    const path = await save({
+      defaultPath: `sayble-history-${date}.${format.extension}`,
+      filters: [{ name: format.label, extensions: [format.extension] }],
+    });
+    if (!path) return;
+    setExporting(true);
+    try {
+      const count = await invoke<number>("cmd_export_history", {
+        path,
+        format: exportFormat,
+        from: exportFrom || null,
+        to: exportTo || null,
+      });
+      toast.success(`已导出 ${count} 条记录`, { description: path });
+      setShowExport(false);
+    } catch (e) {
+      toast.error(`导出失败：${e}`);
+    } finally {
+      setExporting(false);
+    }
+  };
+
   useEffect(() => {
     loadHistory();
   }, []);
@@ -145,15 +194,26 @@ export function HistoryPage() {
             共 {history.length} 条记录
           </h2>
           {history.length > 0 && (
-            <Button
-              variant="ghost"
-              size="sm"
-              className="h-auto py-0.5 px-1.5 text-xs text-muted-foreground"
-              onClick={() => setShowClearConfirm(true)}
-            >
-              <Trash2 className="size-3 mr-1" />
-              清空
-            </Button>
+            <div className="flex items-center gap-1">
+              <Button
+                variant="ghost"
+                size="sm"
+                className="h-auto py-0.5 px-1.5 text-xs text-muted-foreground"
+                onClick={() => setShowExport(true)}
+              >
+                <Download className="size-3 mr-1" />
+                导出
+              </Button>
+              <Button
+                variant="ghost"
+                size="sm"
+                className="h-auto py-0.5 px-1.5 text-xs text-muted-foreground"
+                onClick={() => setShowClearConfirm(true)}
+              >
+                <Trash2 className="size-3 mr-1" />
+                清空
+              </Button>
+            </div>
           )}
         </div>
 
@@ -193,7 +253,7 @@ export function HistoryPage() {
                           </div>
                           <button
                             className="shrink-0 opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded hover:bg-muted text-muted-foreground hover:text-destructive mt-0.5"
-                            onClick={(e) => removeHistory(record.timestamp, e)}
+                            onClick={(e) => removeHistory(record.id, e)}
                             title="删除"
                           >
                             <X className="size-3.5" />
@@ -243,6 +303,61 @@ export function HistoryPage() {
         </DialogContent>
       </Dialog>
 
+      {/* 导出弹窗 */}
+      <Dialog open={showExport} onOpenChange={setShowExport}>
+        <DialogContent>
+          <DialogHeader>
+            <DialogTitle>导出历史记录</DialogTitle>
+            <DialogDescription>
+              日期留空表示不限制。字幕格式只包含带分句时间的记录。
+            </DialogDescription>
+          </DialogHeader>
+          <div className="space-y-3">
+            <div className="flex items-center justify-between gap-3">
+              <Label>格式</Label>
+              <Select value={exportFormat} onValueChange={(v) => setExportFormat(v as ExportFormat)}>
+                <SelectTrigger className="w-48">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {EXPORT_FORMATS.map((f) => (
+                    <SelectItem key={f.value} value={f.value}>{f.label}</SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+            <div className="flex items-center justify-between gap-3">
+              <Label htmlFor="exportFrom">开始日期</Label>
+              <Input
+                id="exportFrom"
+                type="date"
+                className="w-48"
+                value={exportFrom}
+                onChange={(e) => setExportFrom(e.target.value)}
+              />
+            </div>
+            <div className="flex items-center justify-between gap-3">
+              <Label htmlFor="exportTo">结束日期</Label>
+              <Input
+                id="exportTo"
+                type="date"
+                className="w-48"
+                value={exportTo}
+                onChange={(e) => setExportTo(e.target.value)}
+              />
+            </div>
+          </div>
+          <div className="flex justify-end gap-2">
+            <Button variant="outline" onClick={() => setShowExport(false)}>
+              取消
+            </Button>
+            <Button onClick={exportHistory} disabled={exporting}>
+              导出
+            </Button>
+          </div>
+        </DialogContent>
+      </Dialog>
+
       {/* 清空确认弹窗 */}
       <Dialog open={showClearConfirm} onOpenChange={setShowClearConfirm}>
         <DialogContent>
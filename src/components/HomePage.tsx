@@ -46,7 +46,7 @@ export function HomePage({ onNavigate, onOpenSettings }: HomePageProps) {
   const asrMeta = builtinAsrProviders.find((p) => p.type === asrSettings.selectedProvider);
   const asrConfig = asrSettings.providers[asrSettings.selectedProvider] ?? {};
   const asrConfigured = asrMeta
-    ? asrMeta.fields.filter((f) => f.required).every((f) => asrConfig[f.key]?.trim())
+    ? asrMeta.fields.filter((f) => f.required).every((f) => String(asrConfig[f.key] ?? "").trim())
     : false;
 
   // 当前人设名称
@@ -76,8 +76,8 @@ export function HomePage({ onNavigate, onOpenSettings }: HomePageProps) {
   const confirmRemove = async () => {
     if (!pendingRemove) return;
     try {
-      await invoke("cmd_remove_history", { timestamp: pendingRemove });
-      setHistory((prev) => prev.filter((r) => r.timestamp !== pendingRemove));
+      await invoke("cmd_remove_history", { id: pendingRemove });
+      setHistory((prev) => prev.filter((r) => r.id !== pendingRemove));
     } catch (err) {
       console.error("Failed to remove history:", err);
     }
@@ -247,7 +247,7 @@ export function HomePage({ onNavigate, onOpenSettings }: HomePageProps) {
                     </div>
                     <button
                       className="shrink-0 opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded hover:bg-muted text-muted-foreground hover:text-destructive mt-0.5"
-                      onClick={() => setPendingRemove(record.timestamp)}
+                      onClick={() => setPendingRemove(record.id)}
                       title="删除"
                     >
                       <X className="size-3.5" />
@@ -41,7 +41,7 @@ export function VoiceSettings() {
   const activeMeta = filteredProviders.find((p) => p.type === activeType);
   const activeConfig = asrSettings.providers[activeType] ?? {};
 
-  const updateConfig = (key: string, value: string) => {
+  const updateConfig = (key: string, value: string | boolean) => {
     updateAsrSettings({
       ...asrSettings,
       providers: {
@@ -58,7 +58,7 @@ export function VoiceSettings() {
   };
 
   const commitEdit = (key: string) => {
-    if (draftValue.trim() !== (activeConfig[key] ?? "")) {
+    if (draftValue.trim() !== String(activeConfig[key] ?? "")) {
       updateConfig(key, draftValue.trim());
     }
     setEditingField(null);
@@ -68,7 +68,7 @@ export function VoiceSettings() {
     const config = asrSettings.providers[meta.type] ?? {};
     return meta.fields
       .filter((f) => f.required)
-      .every((f) => config[f.key]?.trim());
+      .every((f) => String(config[f.key] ?? "").trim());
   };
 
   const handleTestConnection = async (meta: AsrProviderMeta) => {
@@ -107,7 +107,7 @@ export function VoiceSettings() {
         <div key={field.key} className="flex items-center gap-4">
           <Label className="shrink-0 w-24 text-muted-foreground">{field.label}</Label>
           <Select
-            value={value}
+            value={String(value)}
             onValueChange={(v) => updateConfig(field.key, v)}
           >
             <SelectTrigger className="flex-1">
@@ -131,8 +131,8 @@ export function VoiceSettings() {
           <Label className="shrink-0 w-24 text-muted-foreground">{field.label}</Label>
           <div className="flex-1 flex">
             <Switch
-              checked={value === "true"}
-              onCheckedChange={(v) => updateConfig(field.key, v ? "true" : "false")}
+              checked={value === true || value === "true"}
+              onCheckedChange={(v) => updateConfig(field.key, v)}
             />
           </div>
         </div>
@@ -172,11 +172,11 @@ export function VoiceSettings() {
         ) : (
           <span
             className="flex-1 text-sm cursor-text hover:bg-muted/50 rounded px-2 py-1 transition-colors truncate"
-            onClick={() => startEdit(field.key, activeConfig[field.key] ?? "")}
+            onClick={() => startEdit(field.key, String(activeConfig[field.key] ?? ""))}
           >
             {isPassword
               ? (value ? "••••••••" : <span className="text-muted-foreground">{field.placeholder}</span>)
-              : (value || <span className="text-muted-foreground">{field.placeholder}</span>)}
+              : (String(value) || <span className="text-muted-foreground">{field.placeholder}</span>)}
           </span>
         )}
       </div>
@@ -1,5 +1,5 @@
-import { render, screen } from "@testing-library/react";
-import { describe, it, expect } from "vitest";
+import { fireEvent, render, screen } from "@testing-library/react";
+import { describe, it, expect, vi } from "vitest";
 import { FloatingWindow } from "../FloatingWindow";
 
 describe("FloatingWindow", () => {
@@ -102,4 +102,32 @@ describe("FloatingWindow", () => {
     );
     expect(screen.getByText("01:05")).toBeInTheDocument();
   });
+
+  it("should edit, accept and discard pending output", () => {
+    const onPendingChange = vi.fn();
+    const onAccept = vi.fn();
+    const onDiscard = vi.fn();
+    render(
+      <FloatingWindow
+        status="pending"
+        partialText=""
+        finalText="待确认的文字"
+        duration={0}
+        onPendingChange={onPendingChange}
+        onAccept={onAccept}
+        onDiscard={onDiscard}
+      />
+    );
+    const textarea = screen.getByDisplayValue("待确认的文字");
+    fireEvent.change(textarea, { target: { value: "修改后的文字" } });
+    expect(onPendingChange).toHaveBeenCalledWith("修改后的文字");
+
+    fireEvent.keyDown(textarea, { key: "Enter", ctrlKey: true });
+    fireEvent.click(screen.getByText("输出"));
+    expect(onAccept).toHaveBeenCalledTimes(2);
+
+    fireEvent.keyDown(textarea, { key: "Escape" });
+    fireEvent.click(screen.getByText("丢弃"));
+    expect(onDiscard).toHaveBeenCalledTimes(2);
+  });
 });
@@ -1,22 +1,164 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { ask, open, save } from "@tauri-apps/plugin-dialog";
 import { revealItemInDir } from "@tauri-apps/plugin-opener";
+import { toast } from "sonner";
 import { Label } from "@/components/ui/label";
 import { Button } from "@/components/ui/button";
-import { FolderOpen } from "lucide-react";
+import { Input } from "@/components/ui/input";
+import { Switch } from "@/components/ui/switch";
+import { Download, FolderInput, FolderOpen, Upload } from "lucide-react";
+import { useSettingsStore } from "@/stores/useSettingsStore";
+import type { ControlStatus } from "@/types/settings";
+
+interface ImportSummary {
+  secrets: number;
+  tunnels: number;
+  history: number;
+  secretsSkipped: boolean;
+}
+
+interface DataDirs {
+  dir: string;
+  source: "arg" | "env" | "portable" | "moved" | "default";
+  settings: string;
+  logs: string;
+}
+
+const BACKUP_FILTERS = [{ name: "Sayble 备份", extensions: ["json"] }];
+
+const FIXED_SOURCE_LABELS: Partial<Record<DataDirs["source"], string>> = {
+  arg: "由启动参数 --data-dir 指定",
+  env: "由环境变量 SAYBLE_DATA_DIR 指定",
+  portable: "便携模式，数据保存在程序目录",
+};
 
 export function DataSettings() {
-  const [dataDirs, setDataDirs] = useState<{ settings: string; logs: string }>({ settings: "", logs: "" });
+  const [dataDirs, setDataDirs] = useState<DataDirs>({ dir: "", source: "default", settings: "", logs: "" });
+
+  const loadDataDirs = () => {
+    invoke<DataDirs>("cmd_get_data_dir").then(setDataDirs);
+  };
 
   useEffect(() => {
-    invoke<{ settings: string; logs: string }>("cmd_get_data_dir").then(setDataDirs);
+    loadDataDirs();
   }, []);
 
+  const control = useSettingsStore((s) => s.appSettings.control);
+  const updateAppSetting = useSettingsStore((s) => s.updateAppSetting);
+  const [controlStatus, setControlStatus] = useState<ControlStatus | null>(null);
+  const [controlPort, setControlPort] = useState(String(control.port));
+
+  // 设置保存（防抖 500ms）后服务才会启停，稍后再读取状态
+  useEffect(() => {
+    setControlPort(String(control.port));
+    const timer = setTimeout(() => {
+      invoke<ControlStatus>("cmd_get_control_status").then(setControlStatus).catch(() => {});
+    }, 1000);
+    return () => clearTimeout(timer);
+  }, [control.enabled, control.port]);
+
+  const commitControlPort = () => {
+    const port = Number(controlPort);
+    if (Number.isInteger(port) && port >= 1 && port <= 65535) {
+      if (port !== control.port) updateAppSetting("control", { ...control, port });
+    } else {
+      toast.error("端口必须在 1-65535 之间");
+      setControlPort(String(control.port));
+    }
+  };
+
+  const [includeHistory, setIncludeHistory] = useState(false);
+  const [password, setPassword] = useState("");
+  const [busy, setBusy] = useState(false);
+
+  const exportBackup = async () => {
+    const date = new Date().toISOString().slice(0, 10);
+    const path = await save({ defaultPath: `sayble-backup-${date}.json`, filters: BACKUP_FILTERS });
+    if (!path) return;
+    setBusy(true);
+    try {
+      await invoke("cmd_export_backup", { path, includeHistory, password: password || null });
+      toast.success(password ? "备份已导出，密钥已用密码加密" : "备份已导出（不含密钥）");
+    } catch (e) {
+      toast.error(`导出失败：${e}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const importBackup = async (mode: "merge" | "replace") => {
+    const path = await open({ multiple: false, filters: BACKUP_FILTERS });
+    if (typeof path !== "string") return;
+    if (
+      mode === "replace" &&
+      !(await ask("替换会覆盖当前的设置、隧道和共享设置，确定继续吗？", { title: "导入备份", kind: "warning" }))
+    ) {
+      return;
+    }
+    setBusy(true);
+    try {
+      const summary = await invoke<ImportSummary>("cmd_import_backup", {
+        path,
+        mode,
+        password: password || null,
+      });
+      useSettingsStore.setState({ loaded: false });
+      await useSettingsStore.getState().loadSettings();
+      const parts = [`${summary.tunnels} 个隧道`, `${summary.history} 条历史记录`, `${summary.secrets} 个密钥`];
+      toast.success(`导入完成：${parts.join("、")}`);
+      if (summary.secretsSkipped) {
+        toast.warning("备份中的密钥已加密，未输入密码，已跳过");
+      }
+    } catch (e) {
+      toast.error(`导入失败：${e}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const moveDataDir = async () => {
+    const target = await open({ directory: true, multiple: false });
+    if (typeof target !== "string") return;
+    if (!(await ask(`将所有数据移动到 ${target}？旧位置的数据会在复制完成后删除。`, { title: "移动数据目录" }))) {
+      return;
+    }
+    setBusy(true);
+    try {
+      await invoke<string>("cmd_move_data_dir", { target });
+      loadDataDirs();
+      toast.success("数据目录已移动", { description: "日志将在下次启动后写入新位置" });
+    } catch (e) {
+      toast.error(`移动失败：${e}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const fixedSource = FIXED_SOURCE_LABELS[dataDirs.source];
+
   return (
     <div className="space-y-6">
       <h2 className="text-base font-semibold">数据</h2>
 
       <div className="space-y-4">
+        <div className="flex items-center justify-between gap-4">
+          <div className="min-w-0">
+            <Label>数据目录</Label>
+            <p className="text-xs text-muted-foreground truncate mt-0.5">{dataDirs.dir}</p>
+            {fixedSource && <p className="text-xs text-muted-foreground mt-0.5">{fixedSource}</p>}
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            className="shrink-0"
+            disabled={busy || !!fixedSource}
+            onClick={moveDataDir}
+          >
+            <FolderInput className="size-4 mr-1.5" />
+            移动
+          </Button>
+        </div>
         <div className="flex items-center justify-between gap-4">
           <div className="min-w-0">
             <Label>设置文件</Label>
@@ -48,6 +190,90 @@ export function DataSettings() {
           </Button>
         </div>
       </div>
+
+      <div className="space-y-4">
+        <h3 className="text-sm font-medium">本地控制接口</h3>
+        <p className="text-xs text-muted-foreground">
+          启用后脚本可通过 http://127.0.0.1 控制录音、读取结果和转写 WAV 文件，请求需带 token 文件中的令牌。
+        </p>
+        <div className="flex items-center justify-between">
+          <Label htmlFor="controlEnabled">启用</Label>
+          <Switch
+            id="controlEnabled"
+            checked={control.enabled}
+            onCheckedChange={(enabled) => updateAppSetting("control", { ...control, enabled })}
+          />
+        </div>
+        <div className="flex items-center justify-between gap-4">
+          <Label htmlFor="controlPort" className="shrink-0">
+            端口
+          </Label>
+          <Input
+            id="controlPort"
+            inputMode="numeric"
+            className="w-28"
+            value={controlPort}
+            onChange={(e) => setControlPort(e.target.value)}
+            onBlur={commitControlPort}
+          />
+        </div>
+        {controlStatus?.error && <p className="text-xs text-destructive">{controlStatus.error}</p>}
+        {controlStatus?.running && (
+          <div className="flex items-center justify-between gap-4">
+            <div className="min-w-0">
+              <Label>Token 文件</Label>
+              <p className="text-xs text-muted-foreground truncate mt-0.5">{controlStatus.tokenFile}</p>
+            </div>
+            <Button
+              variant="outline"
+              size="sm"
+              className="shrink-0"
+              onClick={() => revealItemInDir(controlStatus.tokenFile)}
+            >
+              <FolderOpen className="size-4 mr-1.5" />
+              打开
+            </Button>
+          </div>
+        )}
+      </div>
+
+      <div className="space-y-4">
+        <h3 className="text-sm font-medium">备份与恢复</h3>
+        <p className="text-xs text-muted-foreground">
+          备份包含设置、提示词、隧道和共享设置。填写密码时密钥会加密导出，否则不导出密钥。
+        </p>
+        <div className="flex items-center justify-between">
+          <Label htmlFor="backupIncludeHistory">包含历史记录</Label>
+          <Switch id="backupIncludeHistory" checked={includeHistory} onCheckedChange={setIncludeHistory} />
+        </div>
+        <div className="flex items-center justify-between gap-4">
+          <Label htmlFor="backupPassword" className="shrink-0">
+            备份密码
+          </Label>
+          <Input
+            id="backupPassword"
+            type="password"
+            className="w-56"
+            placeholder="可选"
+            value={password}
+            onChange={(e) => setPassword(e.target.value)}
+          />
+        </div>
+        <div className="flex flex-wrap gap-2 justify-end">
+          <Button variant="outline" size="sm" disabled={busy} onClick={exportBackup}>
+            <Download className="size-4 mr-1.5" />
+            导出备份
+          </Button>
+          <Button variant="outline" size="sm" disabled={busy} onClick={() => importBackup("merge")}>
+            <Upload className="size-4 mr-1.5" />
+            导入并合并
+          </Button>
+          <Button variant="outline" size="sm" disabled={busy} onClick={() => importBackup("replace")}>
+            <Upload className="size-4 mr-1.5" />
+            导入并替换
+          </Button>
+        </div>
+      </div>
     </div>
   );
 }
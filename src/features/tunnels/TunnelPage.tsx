@@ -286,7 +286,7 @@ export function TunnelPage() {
       if (payload.kind === "log" && payload.log && payload.tunnelId === activeId) {
         setLogs((prev) => [payload.log!, ...prev].slice(0, 200));
       }
-      if (payload.kind === "deleted") {
+      if (payload.kind === "deleted" || payload.kind === "imported") {
         loadTunnels();
       }
       if (payload.kind === "logsCleared" && (!payload.tunnelId || payload.tunnelId === activeId)) {
@@ -63,7 +63,7 @@ function debouncedSave(get: () => SettingsStore) {
       toast.success("设置已保存");
     } catch (e) {
       console.error("Failed to save:", e);
-      toast.error("保存失败");
+      toast.error(typeof e === "string" ? `保存失败：${e}` : "保存失败");
     }
   }, 500);
 }
@@ -22,8 +22,8 @@ export interface AsrProviderMeta {
   platform?: "windows" | "macos" | "linux"; // 仅在指定平台显示
 }
 
-// 用户填写的认证信息
-export type AsrProviderConfig = Record<string, string>;
+// 用户填写的认证信息，开关字段为布尔值
+export type AsrProviderConfig = Record<string, string | boolean>;
 
 // 完整 ASR 设置
 export interface AsrSettings {
@@ -92,7 +92,7 @@ export const defaultAsrSettings: AsrSettings = {
       appId: "",
       accessKey: "",
       language: "zh",
-      autoPunctuation: "true",
+      autoPunctuation: true,
     },
   },
 };
@@ -1,6 +1,9 @@
 export interface HistoryRecord {
+  id: string;
   timestamp: string;
   asrText: string;
   polishedText: string | null;
   outputText: string;
 }
+
+export type ExportFormat = "markdown" | "csv" | "jsonLines" | "srt" | "vtt";
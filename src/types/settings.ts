@@ -13,6 +13,20 @@ export interface AppSettings {
   toggleHotkey: string;
   deviceId: string;
   liveWindows: LiveWindowConfig[];
+  control: ControlSettings;
+}
+
+/** 本地控制接口，供脚本通过 HTTP 控制录音 */
+export interface ControlSettings {
+  enabled: boolean;
+  port: number;
+}
+
+export interface ControlStatus {
+  running: boolean;
+  port: number | null;
+  tokenFile: string;
+  error: string | null;
 }
 
 export const defaultSettings: AppSettings = {
@@ -23,4 +37,5 @@ export const defaultSettings: AppSettings = {
   toggleHotkey: "右Ctrl",
   deviceId: "",
   liveWindows: [],
+  control: { enabled: false, port: 47621 },
 };
@@ -46,7 +46,7 @@ export interface TunnelLogEntry {
 }
 
 export interface TunnelEvent {
-  kind: "status" | "log" | "deleted" | "logsCleared";
+  kind: "status" | "log" | "deleted" | "logsCleared" | "imported";
   tunnelId?: string;
   status?: TunnelStatus;
   log?: TunnelLogEntry;